@@ -0,0 +1,937 @@
+//! Bounding boxes of geo URIs that may cross the antimeridian or include a pole.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::geodesy::{direct, from_geodetic, inverse, to_geodetic};
+use crate::normalize::{wrap_degrees, wrap_longitude};
+use crate::{CoordRefSystem, Crs, Ellipsoid, Error, GeoUri, GeodesicMethod};
+
+/// The difference in azimuth in degrees below which the easternmost location of a circle is
+/// found.
+const AZIMUTH_TOLERANCE: f64 = 1e-9;
+
+/// The arc spanning all longitudes.
+///
+/// This is the only arc with a west edge of `-180.0`, so it is distinct from the empty arc at
+/// the antimeridian.
+const FULL_ARC: Arc = (-180.0, 180.0);
+
+/// An arc of longitudes from a west edge eastwards to an east edge, as longitudes increasing
+/// eastwards in degrees.
+type Arc = (f64, f64);
+
+/// A bounding box of locations, bounded by two parallels and two meridians.
+///
+/// The box extends eastwards from its west edge to its east edge, so it crosses the end of the
+/// longitude range of the coordinate reference system (the antimeridian for WGS-84) if the west
+/// edge lies east of the east edge, e.g. from `170.0` to `-170.0`. Computed boxes that include a
+/// pole span all longitudes.
+///
+/// Use [`GeoUri::bounding_box`] or [`BoundingBox::from_geo_uris`] to compute one, or
+/// [`BoundingBox::from_bbox`] to create one from the `bbox` form used by web APIs.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{BoundingBox, GeoUri, GeodesicMethod};
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let geo_uris = [
+///     GeoUri::parse("geo:-17.7,178.1")?, // Fiji
+///     GeoUri::parse("geo:-13.8,-171.8")?, // Samoa
+///     GeoUri::parse("geo:-21.1,-175.2")?, // Tonga
+/// ];
+/// let bbox = BoundingBox::from_geo_uris(&geo_uris, GeodesicMethod::Ellipsoidal)?;
+/// assert_eq!(bbox.to_bbox(), [178.1, -21.1, -171.8, -13.8]);
+/// assert!(bbox.contains(&GeoUri::parse("geo:-18.1,180")?));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    /// The coordinate reference system.
+    crs: CoordRefSystem,
+
+    /// The latitude of the south edge in degrees.
+    south: f64,
+
+    /// The latitude of the north edge in degrees.
+    north: f64,
+
+    /// The longitude of the west edge increasing eastwards in degrees.
+    west: f64,
+
+    /// The longitude of the east edge increasing eastwards in degrees.
+    east: f64,
+}
+
+impl BoundingBox {
+    /// Try creating a bounding box from the `bbox` form used by web APIs.
+    ///
+    /// This is an array of the west, south, east and north edges, e.g. as used by GeoJSON and
+    /// OpenStreetMap, in the coordinate reference system. The box crosses the end of the
+    /// longitude range if the west edge lies east of the east edge, and spans all longitudes if
+    /// the west and east edges are the ends of the longitude range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{BoundingBox, CoordRefSystem};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let bbox = BoundingBox::from_bbox([170.0, -20.0, -170.0, -10.0], CoordRefSystem::Wgs84)?;
+    /// assert_eq!(bbox.west(), 170.0);
+    /// assert_eq!(bbox.east(), -170.0);
+    /// assert_eq!(bbox.to_string(), "170,-20,-170,-10");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the edges are invalid in the coordinate reference system or if
+    /// the south edge lies north of the north edge.
+    pub fn from_bbox(bbox: [f64; 4], crs: CoordRefSystem) -> Result<Self, Error> {
+        let [west, south, east, north] = bbox;
+        crs.validate(south, west)?;
+        crs.validate(north, east)?;
+        if south > north {
+            return Err(Error::OutOfRangeLatitude);
+        }
+
+        let arc = if (east - west).abs() >= 360.0 {
+            FULL_ARC
+        } else if crs.longitude_convention().is_east_positive() {
+            (west, east)
+        } else {
+            (-west, -east)
+        };
+
+        Ok(Self::with_arc(crs, south, north, arc))
+    }
+
+    /// Try computing the bounding box of the uncertainty circles of geo URIs.
+    ///
+    /// This is the smallest box containing the uncertainty circles of all geo URIs, see
+    /// [`GeoUri::bounding_box`], which crosses the end of the longitude range if that makes it
+    /// smaller.
+    ///
+    /// # Examples
+    ///
+    /// See [`BoundingBox`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if there are no geo URIs, if the geo URIs have different coordinate
+    /// reference systems or if the coordinate reference system has no reference ellipsoid.
+    pub fn from_geo_uris<'a, I>(geo_uris: I, method: GeodesicMethod) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = &'a GeoUri>,
+    {
+        let mut geo_uris = geo_uris.into_iter();
+        let first = geo_uris.next().ok_or(Error::MissingGeoUris)?;
+        let ellipsoid = first.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+
+        let (mut south, mut north, arc) = first.bounds(&ellipsoid, method);
+        let mut arcs: Vec<_> = arc.into_iter().collect();
+        for geo_uri in geo_uris {
+            if geo_uri.crs != first.crs {
+                return Err(Error::MismatchedCoordRefSystem);
+            }
+            let (geo_uri_south, geo_uri_north, arc) = geo_uri.bounds(&ellipsoid, method);
+            south = south.min(geo_uri_south);
+            north = north.max(geo_uri_north);
+            arcs.extend(arc);
+        }
+
+        Ok(Self::with_arc(first.crs, south, north, cover(&mut arcs)))
+    }
+
+    /// Returns the coordinate reference system.
+    pub fn crs(&self) -> CoordRefSystem {
+        self.crs
+    }
+
+    /// Returns the latitude of the south edge in degrees.
+    pub fn south(&self) -> f64 {
+        self.south
+    }
+
+    /// Returns the latitude of the north edge in degrees.
+    pub fn north(&self) -> f64 {
+        self.north
+    }
+
+    /// Returns the longitude of the west edge in degrees.
+    ///
+    /// If the box spans all longitudes, this is the western end of the longitude range, e.g.
+    /// `-180.0` for WGS-84.
+    pub fn west(&self) -> f64 {
+        self.edge_longitude(self.west, true)
+    }
+
+    /// Returns the longitude of the east edge in degrees.
+    ///
+    /// If the box spans all longitudes, this is the eastern end of the longitude range, e.g.
+    /// `180.0` for WGS-84.
+    pub fn east(&self) -> f64 {
+        if self.west == self.east {
+            return self.west();
+        }
+
+        self.edge_longitude(self.east, false)
+    }
+
+    /// Returns the `bbox` form used by web APIs: an array of the west, south, east and north
+    /// edges.
+    ///
+    /// See [`BoundingBox::from_bbox`].
+    pub fn to_bbox(&self) -> [f64; 4] {
+        [self.west(), self.south, self.east(), self.north]
+    }
+
+    /// Returns whether the box contains the location of a geo URI.
+    ///
+    /// This is never the case if the geo URI has a different coordinate reference system. The
+    /// uncertainty of the geo URI is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{BoundingBox, CoordRefSystem, GeoUri};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let bbox = BoundingBox::from_bbox([170.0, 80.0, -170.0, 90.0], CoordRefSystem::Wgs84)?;
+    /// assert!(bbox.contains(&GeoUri::parse("geo:85,-175")?));
+    /// assert!(bbox.contains(&GeoUri::parse("geo:90,0")?));
+    /// assert!(!bbox.contains(&GeoUri::parse("geo:85,0")?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains(&self, geo_uri: &GeoUri) -> bool {
+        if self.crs != geo_uri.crs || !(self.south..=self.north).contains(&geo_uri.latitude) {
+            return false;
+        }
+
+        let (_, longitude) = geo_uri.east_coords();
+        self.crs.is_longitude_ignored(geo_uri.latitude) || self.contains_longitude(longitude)
+    }
+
+    /// Returns whether the box contains another bounding box.
+    ///
+    /// This is never the case if the boxes have different coordinate reference systems.
+    pub fn contains_box(&self, other: &Self) -> bool {
+        if self.crs != other.crs || other.south < self.south || other.north > self.north {
+            return false;
+        }
+
+        let at_pole = other.south == other.north && self.crs.is_longitude_ignored(other.north);
+        let offset = (other.west - self.west).rem_euclid(360.0);
+        at_pole || self.arc() == FULL_ARC || offset + width(other.arc()) <= width(self.arc())
+    }
+
+    /// Returns whether the box intersects another bounding box, including touching it.
+    ///
+    /// This is never the case if the boxes have different coordinate reference systems.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns the intersection of the box and another bounding box, if they intersect.
+    ///
+    /// If the boxes intersect in two separate parts, which can happen if they cross the end of
+    /// the longitude range on opposite sides, the smallest box containing both is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{BoundingBox, CoordRefSystem};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let bbox = BoundingBox::from_bbox([170.0, -20.0, -170.0, -10.0], CoordRefSystem::Wgs84)?;
+    /// let bbox2 = BoundingBox::from_bbox([-175.0, -15.0, 0.0, 0.0], CoordRefSystem::Wgs84)?;
+    /// let intersection = bbox.intersection(&bbox2).expect("an intersection");
+    /// assert_eq!(intersection.to_bbox(), [-175.0, -15.0, -170.0, -10.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.crs != other.crs {
+            return None;
+        }
+        let south = self.south.max(other.south);
+        let north = self.north.min(other.north);
+        if south > north {
+            return None;
+        }
+
+        let arc = if self.arc() == FULL_ARC {
+            other.arc()
+        } else if other.arc() == FULL_ARC {
+            self.arc()
+        } else {
+            // Each arc that starts within the other one ends where the first of both ends.
+            let (width, other_width) = (width(self.arc()), width(other.arc()));
+            let mut arcs = Vec::with_capacity(2);
+            let offset = (other.west - self.west).rem_euclid(360.0);
+            if offset <= width {
+                let east = if other_width <= width - offset {
+                    other.east
+                } else {
+                    self.east
+                };
+                arcs.push((other.west, east));
+            }
+            let offset = (self.west - other.west).rem_euclid(360.0);
+            if offset > 0.0 && offset <= other_width {
+                let east = if width <= other_width - offset {
+                    self.east
+                } else {
+                    other.east
+                };
+                arcs.push((self.west, east));
+            }
+            if arcs.is_empty() {
+                // The boxes only meet at the poles, where the longitude is ignored.
+                let south_pole = self.crs.is_longitude_ignored(south);
+                let north_pole = self.crs.is_longitude_ignored(north);
+                return match (south_pole, north_pole) {
+                    (true, true) => Some(Self::with_arc(self.crs, south, north, FULL_ARC)),
+                    (true, false) => Some(Self::with_arc(self.crs, south, south, FULL_ARC)),
+                    (false, true) => Some(Self::with_arc(self.crs, north, north, FULL_ARC)),
+                    (false, false) => None,
+                };
+            }
+
+            cover(&mut arcs)
+        };
+
+        Some(Self::with_arc(self.crs, south, north, arc))
+    }
+
+    /// Returns the smallest bounding box containing the box and another bounding box.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{BoundingBox, CoordRefSystem};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let bbox = BoundingBox::from_bbox([170.0, -20.0, 175.0, -10.0], CoordRefSystem::Wgs84)?;
+    /// let bbox2 = BoundingBox::from_bbox([-175.0, -15.0, -170.0, 0.0], CoordRefSystem::Wgs84)?;
+    /// let union = bbox.union(&bbox2)?;
+    /// assert_eq!(union.to_bbox(), [170.0, -20.0, -170.0, 0.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the boxes have different coordinate reference systems.
+    pub fn union(&self, other: &Self) -> Result<Self, Error> {
+        if self.crs != other.crs {
+            return Err(Error::MismatchedCoordRefSystem);
+        }
+        let arc = cover(&mut [self.arc(), other.arc()]);
+
+        Ok(Self::with_arc(
+            self.crs,
+            self.south.min(other.south),
+            self.north.max(other.north),
+            arc,
+        ))
+    }
+
+    /// Returns a copy of the box expanded by a distance in meters in all directions.
+    ///
+    /// The expanded box contains all locations within the distance of the box along the
+    /// surface, which is computed using the method (see [`GeoUri::distance_to`]). If it reaches
+    /// a pole, it spans all longitudes. A negative distance counts as zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{BoundingBox, CoordRefSystem, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let bbox = BoundingBox::from_bbox([179.0, 0.0, 179.9, 1.0], CoordRefSystem::Wgs84)?;
+    /// let expanded = bbox.expanded(50_000.0, GeodesicMethod::Ellipsoidal)?;
+    /// assert!(expanded.west() < 178.6);
+    /// assert!(expanded.east() > -179.7);
+    /// assert!(expanded.south() < -0.4 && expanded.north() > 1.4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid.
+    pub fn expanded(&self, distance: f64, method: GeodesicMethod) -> Result<Self, Error> {
+        let ellipsoid = self.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+        let distance = distance.max(0.0);
+        let (south, _, south_half_width) =
+            circle_bounds(&self.crs, &ellipsoid, method, self.south, distance);
+        let (_, north, north_half_width) =
+            circle_bounds(&self.crs, &ellipsoid, method, self.north, distance);
+        let arc = match (south_half_width, north_half_width) {
+            (Some(south_half_width), Some(north_half_width)) => {
+                let half_width = south_half_width.max(north_half_width);
+                if width(self.arc()) + 2.0 * half_width >= 360.0 {
+                    FULL_ARC
+                } else {
+                    (self.west - half_width, self.east + half_width)
+                }
+            }
+            _ => FULL_ARC,
+        };
+
+        Ok(Self::with_arc(self.crs, south, north, arc))
+    }
+
+    /// Returns a bounding box with the edges and an arc of longitudes.
+    fn with_arc(crs: CoordRefSystem, south: f64, north: f64, arc: Arc) -> Self {
+        let (west, east) = if arc == FULL_ARC {
+            FULL_ARC
+        } else {
+            (wrap_degrees(arc.0), wrap_degrees(arc.1))
+        };
+
+        Self {
+            crs,
+            south: south.clamp(-90.0, 90.0),
+            north: north.clamp(-90.0, 90.0),
+            west,
+            east,
+        }
+    }
+
+    /// Returns the arc of longitudes.
+    fn arc(&self) -> Arc {
+        (self.west, self.east)
+    }
+
+    /// Returns whether the box spans a longitude increasing eastwards in degrees.
+    fn contains_longitude(&self, longitude: f64) -> bool {
+        (longitude - self.west).rem_euclid(360.0) <= width(self.arc())
+    }
+
+    /// Returns the longitude of a west or east edge in the longitude convention of the
+    /// coordinate reference system.
+    ///
+    /// Of the two longitudes at the ends of the range that denote the same meridian, this is
+    /// the one that keeps the box within the range.
+    fn edge_longitude(&self, longitude: f64, west_edge: bool) -> f64 {
+        let convention = self.crs.longitude_convention();
+        let range = convention.range();
+        let (west_end, east_end) = if convention.is_east_positive() {
+            (*range.start(), *range.end())
+        } else {
+            (*range.end(), *range.start())
+        };
+        if self.arc() == FULL_ARC {
+            return if west_edge { west_end } else { east_end };
+        }
+
+        let longitude = if convention.is_east_positive() {
+            longitude
+        } else {
+            -longitude
+        };
+        let longitude = wrap_longitude(longitude, convention);
+        if longitude == west_end || longitude == east_end {
+            if west_edge {
+                west_end
+            } else {
+                east_end
+            }
+        } else {
+            longitude
+        }
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    /// Formats the `bbox` form used by web APIs: the west, south, east and north edges
+    /// separated by commas.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [west, south, east, north] = self.to_bbox();
+        write!(f, "{west},{south},{east},{north}")
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl GeoUri {
+    /// Returns the bounding box of the uncertainty circle.
+    ///
+    /// This is the smallest box containing the uncertainty circle, where a missing uncertainty
+    /// counts as zero (see [`GeoUri::circle_contains_point`]). It crosses the end of the
+    /// longitude range if the circle does, and spans all longitudes if the circle contains a
+    /// pole.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:0,180;u=1000")?;
+    /// let bbox = geo_uri.bounding_box(GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!((bbox.west() * 1e5).round(), 17_999_102.0);
+    /// assert_eq!((bbox.east() * 1e5).round(), -17_999_102.0);
+    /// assert_eq!((bbox.north() * 1e5).round(), 904.0);
+    ///
+    /// let geo_uri = GeoUri::parse("geo:-89.999,45;u=1000")?;
+    /// let bbox = geo_uri.bounding_box(GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(bbox.to_bbox()[..3], [-180.0, -90.0, 180.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid.
+    pub fn bounding_box(&self, method: GeodesicMethod) -> Result<BoundingBox, Error> {
+        let ellipsoid = self.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+        let (south, north, arc) = self.bounds(&ellipsoid, method);
+
+        Ok(BoundingBox::with_arc(
+            self.crs,
+            south,
+            north,
+            arc.unwrap_or(FULL_ARC),
+        ))
+    }
+
+    /// Returns the south and north edges and the arc of longitudes of the bounding box of the
+    /// uncertainty circle.
+    ///
+    /// There is no arc for a location at a pole without uncertainty, which is in any box that
+    /// reaches the pole.
+    fn bounds(&self, ellipsoid: &Ellipsoid, method: GeodesicMethod) -> (f64, f64, Option<Arc>) {
+        let (_, longitude) = self.east_coords();
+        let radius = self.radius();
+        let (south, north, half_width) =
+            circle_bounds(&self.crs, ellipsoid, method, self.latitude, radius);
+        let arc = match half_width {
+            Some(half_width) if half_width >= 180.0 => Some(FULL_ARC),
+            Some(half_width) => Some((longitude - half_width, longitude + half_width)),
+            None if radius == 0.0 && self.crs.is_longitude_ignored(self.latitude) => None,
+            None => Some(FULL_ARC),
+        };
+
+        (south, north, arc)
+    }
+}
+
+/// Returns the south and north edges and the half width of the bounding box of a circle around
+/// a location at a latitude in a coordinate reference system with a radius in meters, in degrees.
+///
+/// There is no half width if the circle contains a pole, in which case the box spans all
+/// longitudes. Otherwise, the half width is the difference in longitude to the easternmost
+/// location of the circle, where the geodesic from the centre arrives due east.
+fn circle_bounds(
+    crs: &CoordRefSystem,
+    ellipsoid: &Ellipsoid,
+    method: GeodesicMethod,
+    latitude: f64,
+    radius: f64,
+) -> (f64, f64, Option<f64>) {
+    if radius == 0.0 {
+        return (latitude, latitude, (latitude.abs() < 90.0).then_some(0.0));
+    }
+
+    let latitude = to_geodetic(crs, latitude);
+
+    let reaches =
+        |pole: f64| inverse(ellipsoid, method, (latitude, 0.0), (pole, 0.0)).distance <= radius;
+    let (south, north) = (reaches(-90.0), reaches(90.0));
+    if south || north {
+        let south = if south {
+            -90.0
+        } else {
+            direct(ellipsoid, method, (latitude, 0.0), 180.0, radius).0
+        };
+        let north = if north {
+            90.0
+        } else {
+            direct(ellipsoid, method, (latitude, 0.0), 0.0, radius).0
+        };
+
+        return (from_geodetic(crs, south), from_geodetic(crs, north), None);
+    }
+
+    let south = direct(ellipsoid, method, (latitude, 0.0), 180.0, radius).0;
+    let north = direct(ellipsoid, method, (latitude, 0.0), 0.0, radius).0;
+    let (mut low, mut high) = (0.0, 180.0);
+    let mut half_width: f64 = 0.0;
+    while high - low > AZIMUTH_TOLERANCE {
+        let azimuth = (low + high) / 2.0;
+        let (_, longitude, final_azimuth) =
+            direct(ellipsoid, method, (latitude, 0.0), azimuth, radius);
+        half_width = half_width.max(longitude);
+        if final_azimuth < 90.0 {
+            low = azimuth;
+        } else {
+            high = azimuth;
+        }
+    }
+
+    (
+        from_geodetic(crs, south),
+        from_geodetic(crs, north),
+        Some(half_width),
+    )
+}
+
+/// Returns the width of an arc in degrees, up to `360.0`.
+fn width(arc: Arc) -> f64 {
+    if arc == FULL_ARC {
+        360.0
+    } else {
+        (arc.1 - arc.0).rem_euclid(360.0)
+    }
+}
+
+/// Returns the smallest arc containing arcs, which spans all longitudes if there are none.
+///
+/// This leaves out the largest gap between the arcs. The edges of the arc are edges of the arcs,
+/// so they are exact.
+fn cover(arcs: &mut [Arc]) -> Arc {
+    if arcs.is_empty() || arcs.contains(&FULL_ARC) {
+        return FULL_ARC;
+    }
+
+    // Merge overlapping arcs into intervals of the west edge, the unwrapped east edge and the
+    // actual east edge.
+    arcs.sort_unstable_by(|arc, other| arc.0.total_cmp(&other.0));
+    let mut merged: Vec<(f64, f64, f64)> = Vec::with_capacity(arcs.len());
+    for &arc in arcs.iter() {
+        let (west, east) = arc;
+        let unwrapped_east = west + width(arc);
+        match merged.last_mut() {
+            Some((_, merged_east, merged_edge)) if west <= *merged_east => {
+                if unwrapped_east > *merged_east {
+                    (*merged_east, *merged_edge) = (unwrapped_east, east);
+                }
+            }
+            _ => merged.push((west, unwrapped_east, east)),
+        }
+    }
+
+    // The last interval may wrap around past the start of the first ones. The gaps are given as
+    // their size, the west edge after them and the east edge before them.
+    let (first_west, _, _) = merged[0];
+    let (_, last_east, last_edge) = merged[merged.len() - 1];
+    let wrapped_east = last_east - 360.0;
+    let mut gap = (first_west + 360.0 - last_east, first_west, last_edge);
+    for pair in merged.windows(2) {
+        let (east, edge) = if wrapped_east > pair[0].1 {
+            (wrapped_east, last_edge)
+        } else {
+            (pair[0].1, pair[0].2)
+        };
+        let size = pair[1].0 - east;
+        if size > gap.0 {
+            gap = (size, pair[1].0, edge);
+        }
+    }
+
+    if gap.0 <= 0.0 {
+        FULL_ARC
+    } else {
+        (gap.1, gap.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{GRID, WEST_EARTH};
+    use crate::GeoUriBuilderError;
+
+    /// Returns a bounding box on WGS-84 from the `bbox` form.
+    fn bbox(bbox: [f64; 4]) -> Result<BoundingBox, Error> {
+        BoundingBox::from_bbox(bbox, CoordRefSystem::Wgs84)
+    }
+
+    #[test]
+    fn bounding_box_from_bbox() -> Result<(), Error> {
+        for edges in [
+            [5.0, 52.0, 6.0, 53.0],
+            [170.0, -20.0, -170.0, -10.0],
+            [-180.0, -90.0, 180.0, 90.0],
+            [-180.0, 0.0, -170.0, 10.0],
+            [170.0, 0.0, 180.0, 10.0],
+            [-10.0, 80.0, 10.0, 90.0],
+        ] {
+            assert_eq!(bbox(edges)?.to_bbox(), edges);
+        }
+        assert_eq!(
+            bbox([180.0, 0.0, -180.0, 1.0])?.to_bbox(),
+            [-180.0, 0.0, 180.0, 1.0]
+        );
+        assert_eq!(
+            bbox([-180.0, 0.0, -180.0, 1.0])?.to_bbox(),
+            [-180.0, 0.0, -180.0, 1.0]
+        );
+        assert_eq!(bbox([1.5, -2.25, 3.0, 4.0])?.to_string(), "1.5,-2.25,3,4");
+
+        let mars = BoundingBox::from_bbox([350.0, 0.0, 10.0, 1.0], CoordRefSystem::Mars)?;
+        assert_eq!(mars.to_bbox(), [350.0, 0.0, 10.0, 1.0]);
+        assert!(mars.contains(&GeoUri::parse("geo:0.5,0;crs=mars")?));
+        assert!(!mars.contains(&GeoUri::parse("geo:0.5,180;crs=mars")?));
+        let mars = BoundingBox::from_bbox([0.0, 0.0, 360.0, 1.0], CoordRefSystem::Mars)?;
+        assert_eq!(mars.to_bbox(), [0.0, 0.0, 360.0, 1.0]);
+        let mars = BoundingBox::from_bbox([0.0, 0.0, 10.0, 1.0], CoordRefSystem::Mars)?;
+        assert_eq!(mars.to_bbox(), [0.0, 0.0, 10.0, 1.0]);
+
+        // West-positive longitudes: the west edge has the larger longitude.
+        let crs = CoordRefSystem::Custom(&WEST_EARTH);
+        let west = BoundingBox::from_bbox([20.0, 0.0, 10.0, 1.0], crs)?;
+        assert_eq!(west.to_bbox(), [20.0, 0.0, 10.0, 1.0]);
+        let mut builder = GeoUri::builder();
+        builder.crs(crs).latitude(0.5);
+        let geo_uri = builder.longitude(15.0).build().expect("a geo URI");
+        assert!(west.contains(&geo_uri));
+        let geo_uri = builder.longitude(25.0).build().expect("a geo URI");
+        assert!(!west.contains(&geo_uri));
+        let west = BoundingBox::from_bbox([10.0, 0.0, 350.0, 1.0], crs)?;
+        assert!(!west.contains(&geo_uri));
+        let geo_uri = builder.longitude(355.0).build().expect("a geo URI");
+        assert!(west.contains(&geo_uri));
+        assert_eq!(west.to_bbox(), [10.0, 0.0, 350.0, 1.0]);
+        let west = BoundingBox::from_bbox([360.0, 0.0, 0.0, 1.0], crs)?;
+        assert_eq!(west.to_bbox(), [360.0, 0.0, 0.0, 1.0]);
+        let west = BoundingBox::from_bbox([360.0, 0.0, 350.0, 1.0], crs)?;
+        assert_eq!(west.to_bbox(), [360.0, 0.0, 350.0, 1.0]);
+
+        assert_eq!(bbox([0.0, 10.0, 1.0, 0.0]), Err(Error::OutOfRangeLatitude));
+        assert_eq!(bbox([0.0, 0.0, 1.0, 91.0]), Err(Error::OutOfRangeLatitude));
+        assert_eq!(
+            bbox([0.0, 0.0, 181.0, 1.0]),
+            Err(Error::OutOfRangeLongitude)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_bounding_box() -> Result<(), Error> {
+        for (uri, method) in [
+            ("geo:52.0907,5.1214;u=1000", GeodesicMethod::Ellipsoidal),
+            ("geo:52.0907,5.1214;u=1000", GeodesicMethod::Spherical),
+            ("geo:-10,179.99;u=5000", GeodesicMethod::Ellipsoidal),
+            ("geo:80,-179.9;u=100000", GeodesicMethod::Ellipsoidal),
+            (
+                "geo:4.5,359.99;crs=mars;u=10000",
+                GeodesicMethod::Ellipsoidal,
+            ),
+        ] {
+            let geo_uri = GeoUri::parse(uri)?;
+            let bbox = geo_uri.bounding_box(method)?;
+            let polygon = geo_uri.circle_polygon(3600, method)?;
+            for vertex in &polygon {
+                assert!(bbox.contains(vertex), "{vertex} not in {bbox}");
+            }
+
+            // The box is tight: the edges are no further than a centimeter from the circle.
+            let expanded = BoundingBox::from_geo_uris(&polygon, method)?.expanded(0.01, method)?;
+            assert!(expanded.contains_box(&bbox), "{bbox} not in {expanded}");
+        }
+
+        let bbox =
+            GeoUri::parse("geo:-10,179.99;u=5000")?.bounding_box(GeodesicMethod::Spherical)?;
+        assert!(bbox.west() > 179.9 && bbox.east() < -179.9);
+
+        let bbox = GeoUri::parse("geo:89.99,45;u=5000")?.bounding_box(GeodesicMethod::Spherical)?;
+        assert_eq!(bbox.north(), 90.0);
+        assert_eq!((bbox.west(), bbox.east()), (-180.0, 180.0));
+        assert!(bbox.south() > 89.9);
+
+        let bbox =
+            GeoUri::parse("geo:52.0907,5.1214")?.bounding_box(GeodesicMethod::Ellipsoidal)?;
+        assert_eq!(bbox.to_bbox(), [5.1214, 52.0907, 5.1214, 52.0907]);
+        let bbox = GeoUri::parse("geo:-90,0")?.bounding_box(GeodesicMethod::Ellipsoidal)?;
+        assert_eq!(bbox.to_bbox(), [-180.0, -90.0, 180.0, -90.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounding_box_from_geo_uris() -> Result<(), GeoUriBuilderError> {
+        let method = GeodesicMethod::Ellipsoidal;
+        let parse = |uris: &[&str]| -> Result<Vec<_>, Error> {
+            uris.iter().map(|uri| GeoUri::parse(uri)).collect()
+        };
+        let geo_uris = parse(&["geo:10,170", "geo:-10,-170", "geo:0,175", "geo:5,-175"])?;
+        let bbox = BoundingBox::from_geo_uris(&geo_uris, method)?;
+        assert_eq!(bbox.to_bbox(), [170.0, -10.0, -170.0, 10.0]);
+
+        let geo_uris = parse(&["geo:0,-120", "geo:0,0", "geo:0,120", "geo:0,60"])?;
+        let bbox = BoundingBox::from_geo_uris(&geo_uris, method)?;
+        assert_eq!(bbox.to_bbox(), [-120.0, 0.0, 120.0, 0.0]);
+
+        // Locations at a pole fit in any box that reaches it.
+        let geo_uris = parse(&["geo:80,10", "geo:90,0", "geo:85,20"])?;
+        let bbox = BoundingBox::from_geo_uris(&geo_uris, method)?;
+        assert_eq!(bbox.to_bbox(), [10.0, 80.0, 20.0, 90.0]);
+        let geo_uris = parse(&["geo:90,0", "geo:-90,0"])?;
+        let bbox = BoundingBox::from_geo_uris(&geo_uris, method)?;
+        assert_eq!(bbox.to_bbox(), [-180.0, -90.0, 180.0, 90.0]);
+
+        // Uncertainty circles are included.
+        let geo_uris = parse(&["geo:0,179.999;u=1000", "geo:1,179"])?;
+        let bbox = BoundingBox::from_geo_uris(&geo_uris, method)?;
+        assert_eq!(bbox.west(), 179.0);
+        assert!(bbox.east() < -179.99 && bbox.south() < -0.009);
+
+        assert_eq!(
+            BoundingBox::from_geo_uris(&[], method),
+            Err(Error::MissingGeoUris)
+        );
+        let geo_uris = parse(&["geo:0,0", "geo:0,0;crs=moon"])?;
+        assert_eq!(
+            BoundingBox::from_geo_uris(&geo_uris, method),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+        let geo_uri = GeoUri::builder()
+            .crs(CoordRefSystem::Custom(&GRID))
+            .latitude(0.0)
+            .longitude(0.0)
+            .build()?;
+        assert_eq!(
+            BoundingBox::from_geo_uris([&geo_uri], method),
+            Err(Error::MissingEllipsoid)
+        );
+        assert_eq!(geo_uri.bounding_box(method), Err(Error::MissingEllipsoid));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounding_box_contains() -> Result<(), Error> {
+        let bbox = bbox([170.0, -20.0, -170.0, -10.0])?;
+        assert!(bbox.contains(&GeoUri::parse("geo:-15,175")?));
+        assert!(bbox.contains(&GeoUri::parse("geo:-15,-180")?));
+        assert!(bbox.contains(&GeoUri::parse("geo:-20,-170")?));
+        assert!(!bbox.contains(&GeoUri::parse("geo:-15,0")?));
+        assert!(!bbox.contains(&GeoUri::parse("geo:-21,175")?));
+        assert!(!bbox.contains(&GeoUri::parse("geo:-15,175;crs=moon")?));
+
+        assert!(bbox.contains_box(&bbox));
+        assert!(bbox.contains_box(&self::bbox([175.0, -15.0, -175.0, -12.0])?));
+        assert!(bbox.contains_box(&self::bbox([-179.0, -15.0, -175.0, -12.0])?));
+        assert!(!bbox.contains_box(&self::bbox([165.0, -15.0, -175.0, -12.0])?));
+        assert!(!bbox.contains_box(&self::bbox([-175.0, -15.0, 175.0, -12.0])?));
+        assert!(!bbox.contains_box(&self::bbox([-180.0, -15.0, 180.0, -12.0])?));
+        assert!(self::bbox([-180.0, -90.0, 180.0, 90.0])?.contains_box(&bbox));
+
+        let polar = self::bbox([10.0, 80.0, 20.0, 90.0])?;
+        assert!(polar.contains(&GeoUri::parse("geo:90,0")?));
+        assert!(polar.contains_box(&self::bbox([-180.0, 90.0, 180.0, 90.0])?));
+        assert!(!polar.contains_box(&self::bbox([-180.0, 89.0, 180.0, 90.0])?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounding_box_intersection_union() -> Result<(), Error> {
+        let bbox1 = bbox([170.0, -20.0, -170.0, -10.0])?;
+        for (edges, intersection, union) in [
+            (
+                [175.0, -15.0, 180.0, 0.0],
+                Some([175.0, -15.0, 180.0, -10.0]),
+                [170.0, -20.0, -170.0, 0.0],
+            ),
+            (
+                [-175.0, -30.0, 0.0, -25.0],
+                None,
+                [170.0, -30.0, 0.0, -10.0],
+            ),
+            ([0.0, -15.0, 10.0, -12.0], None, [0.0, -20.0, -170.0, -10.0]),
+            (
+                [-180.0, -90.0, 180.0, 90.0],
+                Some([170.0, -20.0, -170.0, -10.0]),
+                [-180.0, -90.0, 180.0, 90.0],
+            ),
+            // The intersection consists of two parts.
+            (
+                [-175.0, -15.0, 175.0, -12.0],
+                Some([170.0, -15.0, -170.0, -12.0]),
+                [-180.0, -20.0, 180.0, -10.0],
+            ),
+        ] {
+            let bbox2 = bbox(edges)?;
+            assert_eq!(bbox1.intersects(&bbox2), intersection.is_some());
+            assert_eq!(
+                bbox1.intersection(&bbox2).map(|b| b.to_bbox()),
+                intersection
+            );
+            assert_eq!(
+                bbox2.intersection(&bbox1).map(|b| b.to_bbox()),
+                intersection
+            );
+            assert_eq!(bbox1.union(&bbox2)?.to_bbox(), union);
+            assert_eq!(bbox2.union(&bbox1)?.to_bbox(), union);
+        }
+
+        // Boxes that only meet at a pole.
+        let polar = bbox([10.0, 80.0, 20.0, 90.0])?;
+        let polar2 = bbox([-20.0, 85.0, -10.0, 90.0])?;
+        assert_eq!(
+            polar.intersection(&polar2).map(|b| b.to_bbox()),
+            Some([-180.0, 90.0, 180.0, 90.0])
+        );
+        assert!(!bbox([10.0, 80.0, 20.0, 89.0])?.intersects(&polar2));
+
+        let moon = BoundingBox::from_bbox([0.0, 0.0, 1.0, 1.0], CoordRefSystem::Moon)?;
+        assert!(!moon.intersects(&bbox1));
+        assert_eq!(moon.union(&bbox1), Err(Error::MismatchedCoordRefSystem));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounding_box_expanded() -> Result<(), Error> {
+        let method = GeodesicMethod::Ellipsoidal;
+        let bbox1 = bbox([170.0, -20.0, -170.0, -10.0])?;
+        let expanded = bbox1.expanded(100_000.0, method)?;
+        assert!(expanded.contains_box(&bbox1));
+        // Circles around the edges are contained up to the distance expanded by, but not well
+        // beyond it.
+        for uri in ["geo:-20,175", "geo:-15,-170", "geo:-10,170", "geo:-20,-170"] {
+            for distance in [50_000.0, 99_000.0, 110_000.0, 150_000.0] {
+                let geo_uri = GeoUri::parse(&format!("{uri};u={distance}"))?;
+                let is_contained = geo_uri
+                    .circle_polygon(360, method)?
+                    .iter()
+                    .all(|vertex| expanded.contains(vertex));
+                assert_eq!(
+                    is_contained,
+                    distance < 100_000.0,
+                    "{geo_uri} and {expanded}"
+                );
+            }
+        }
+        assert!(!expanded.contains(&GeoUri::parse("geo:-22,175")?));
+        assert!(!expanded.contains(&GeoUri::parse("geo:-15,168.9")?));
+        assert_eq!(bbox1.expanded(0.0, method)?, bbox1);
+        assert_eq!(bbox1.expanded(-1.0, method)?, bbox1);
+
+        let expanded = bbox([10.0, 80.0, 20.0, 89.5])?.expanded(100_000.0, method)?;
+        assert_eq!(expanded.to_bbox()[2..], [180.0, 90.0]);
+        assert_eq!(expanded.west(), -180.0);
+        let expanded = bbox([0.0, 0.0, 1.0, 1.0])?.expanded(30_000_000.0, method)?;
+        assert_eq!(expanded.to_bbox(), [-180.0, -90.0, 180.0, 90.0]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+//! Weighted centroids of geo URIs and fusion of their uncertainties.
+
+use alloc::vec::Vec;
+
+use crate::geodesic::{atan2d, sincosd};
+use crate::geodesy::{direct, inverse};
+use crate::{Crs, Error, GeoUri, GeodesicMethod};
+
+/// The maximum number of iterations to find the centroid.
+const MAX_ITERATIONS: u32 = 50;
+
+/// The length in meters of the step below which the centroid is found.
+const TOLERANCE: f64 = 1e-6;
+
+/// The length of the mean of the unit normal vectors below which the centroid is indeterminate.
+const INDETERMINATE_NORM: f64 = 1e-12;
+
+/// The weighting of geo URIs when computing their centroid.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{GeoUri, GeodesicMethod, Weighting};
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let geo_uris = [
+///     GeoUri::parse("geo:0,0;u=10")?,
+///     GeoUri::parse("geo:0,0.001;u=20")?,
+/// ];
+/// let method = GeodesicMethod::Ellipsoidal;
+/// let equal = GeoUri::centroid(&geo_uris, Weighting::Equal, method)?;
+/// assert_eq!((equal.longitude() * 1e4).round(), 5.0);
+/// assert_eq!(equal.uncertainty().map(f64::round), Some(11.0));
+/// let fused = GeoUri::centroid(&geo_uris, Weighting::InverseVariance, method)?;
+/// assert_eq!((fused.longitude() * 1e4).round(), 2.0);
+/// assert_eq!(fused.uncertainty().map(f64::round), Some(9.0));
+/// # Ok(())
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Weighting {
+    /// All geo URIs have the same weight.
+    ///
+    /// The uncertainty of the centroid is only known if all geo URIs have one.
+    #[default]
+    Equal,
+
+    /// The geo URIs are weighted by the inverse of the square of their uncertainty.
+    ///
+    /// The uncertainty is regarded as the standard deviation of independent estimates of the
+    /// same location, so the centroid is their best estimate. All geo URIs need an uncertainty.
+    /// If some have an uncertainty of zero, only those are taken into account, and geo URIs with
+    /// an infinite uncertainty are only taken into account if all have one.
+    InverseVariance,
+}
+
+impl Weighting {
+    /// Returns the weights of the geo URIs, which are not normalized.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the weighting needs an uncertainty that a geo URI lacks.
+    fn weights(self, geo_uris: &[&GeoUri]) -> Result<Vec<f64>, Error> {
+        match self {
+            Self::Equal => Ok(geo_uris.iter().map(|_| 1.0).collect()),
+            Self::InverseVariance => {
+                let uncertainties = geo_uris
+                    .iter()
+                    .map(|geo_uri| geo_uri.uncertainty.ok_or(Error::MissingUncertainty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let weights: Vec<_> = if uncertainties.contains(&0.0) {
+                    uncertainties
+                        .iter()
+                        .map(|&uncertainty| if uncertainty == 0.0 { 1.0 } else { 0.0 })
+                        .collect()
+                } else {
+                    uncertainties
+                        .iter()
+                        .map(|uncertainty| uncertainty.powi(-2))
+                        .collect()
+                };
+
+                // Infinite uncertainties carry no weight, unless all uncertainties are infinite.
+                if weights.iter().all(|&weight| weight == 0.0) {
+                    Self::Equal.weights(geo_uris)
+                } else {
+                    Ok(weights)
+                }
+            }
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl GeoUri {
+    /// Try computing the geo URI of the weighted centroid of the locations of geo URIs.
+    ///
+    /// The centroid is the location that minimizes the weighted sum of the squared distances
+    /// to the locations, where the distances are computed on the reference ellipsoid using the
+    /// method (see [`GeoUri::distance_to`]). Unlike averaging the coordinates, this works
+    /// across the end of the longitude range and near the poles. It is found iteratively,
+    /// starting at the normalized weighted mean of the directions of the locations.
+    ///
+    /// The geo URIs are weighted according to the weighting. The uncertainty of the centroid
+    /// combines the uncertainties of the geo URIs as if they were the standard deviations of
+    /// independent estimates, i.e. the square root of the sum of the squares of the weighted
+    /// uncertainties divided by the sum of the weights. If a geo URI lacks an uncertainty, the
+    /// centroid has none either. Similarly, the altitude of the centroid is the weighted mean of
+    /// the altitudes if all geo URIs have one. The centroid has the coordinate reference
+    /// system of the geo URIs and no other parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod, Weighting};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uris = [
+    ///     GeoUri::parse("geo:10,179")?,
+    ///     GeoUri::parse("geo:10,-179")?,
+    ///     GeoUri::parse("geo:12,180")?,
+    /// ];
+    /// let method = GeodesicMethod::Ellipsoidal;
+    /// let centroid = GeoUri::centroid(&geo_uris, Weighting::Equal, method)?;
+    /// assert_eq!(centroid.latitude().round(), 11.0);
+    /// assert_eq!(centroid.longitude().abs(), 180.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See also [`Weighting`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if there are no geo URIs, if the geo URIs have different coordinate
+    /// reference systems, if the coordinate reference system has no reference ellipsoid, if
+    /// the weighting needs an uncertainty that a geo URI lacks or if the centroid is
+    /// indeterminate because the locations are spread evenly around the ellipsoid or so widely
+    /// that the iteration does not converge.
+    pub fn centroid<'a, I>(
+        geo_uris: I,
+        weighting: Weighting,
+        method: GeodesicMethod,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = &'a GeoUri>,
+    {
+        let geo_uris: Vec<_> = geo_uris.into_iter().collect();
+        let first = geo_uris.first().ok_or(Error::MissingGeoUris)?;
+        if geo_uris.iter().any(|geo_uri| geo_uri.crs != first.crs) {
+            return Err(Error::MismatchedCoordRefSystem);
+        }
+        let ellipsoid = first.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+
+        // Geo URIs without weight are left out altogether, so they don't affect the altitude
+        // and uncertainty either.
+        let (geo_uris, weights): (Vec<&GeoUri>, Vec<f64>) = geo_uris
+            .iter()
+            .copied()
+            .zip(weighting.weights(&geo_uris)?)
+            .filter(|&(_, weight)| weight > 0.0)
+            .unzip();
+        let total_weight: f64 = weights.iter().sum();
+        let uncertainty = geo_uris
+            .iter()
+            .zip(&weights)
+            .map(|(geo_uri, weight)| geo_uri.uncertainty.map(|u| (weight * u).powi(2)))
+            .sum::<Option<f64>>()
+            .map(|variance| variance.sqrt() / total_weight);
+        let altitude = geo_uris
+            .iter()
+            .zip(&weights)
+            .map(|(geo_uri, weight)| geo_uri.altitude.map(|altitude| weight * altitude))
+            .sum::<Option<f64>>()
+            .map(|altitude| altitude / total_weight);
+        let coords: Vec<_> = geo_uris
+            .iter()
+            .map(|geo_uri| geo_uri.east_coords())
+            .collect();
+        let weights: Vec<_> = weights.iter().map(|weight| weight / total_weight).collect();
+
+        // Start at the weighted mean of the unit normal vectors, projected onto the surface.
+        let (mut x, mut y, mut z): (f64, f64, f64) = (0.0, 0.0, 0.0);
+        for (&(latitude, longitude), weight) in coords.iter().zip(&weights) {
+            let (sin_phi, cos_phi) = sincosd(latitude);
+            let (sin_lambda, cos_lambda) = sincosd(longitude);
+            x += weight * cos_phi * cos_lambda;
+            y += weight * cos_phi * sin_lambda;
+            z += weight * sin_phi;
+        }
+        let rho = x.hypot(y);
+        if rho.hypot(z) < INDETERMINATE_NORM {
+            return Err(Error::IndeterminateCentroid);
+        }
+        let mut centroid = (atan2d(z, rho), atan2d(y, x));
+
+        // Move to the weighted mean of the locations in the azimuthal equidistant projection
+        // around the centroid until it no longer changes.
+        for iteration in 0.. {
+            if iteration == MAX_ITERATIONS {
+                return Err(Error::IndeterminateCentroid);
+            }
+
+            let (mut east, mut north): (f64, f64) = (0.0, 0.0);
+            for (&coords, weight) in coords.iter().zip(&weights) {
+                let distance = inverse(&ellipsoid, method, centroid, coords);
+                let (sin_alpha, cos_alpha) = sincosd(distance.initial_azimuth);
+                east += weight * distance.distance * sin_alpha;
+                north += weight * distance.distance * cos_alpha;
+            }
+            let step = east.hypot(north);
+            let (latitude, longitude, _) =
+                direct(&ellipsoid, method, centroid, atan2d(east, north), step);
+            centroid = (latitude, longitude);
+            if step < TOLERANCE {
+                break;
+            }
+        }
+
+        let mut geo_uri = first.at_east_coords(centroid.0, centroid.1, altitude)?;
+        geo_uri.uncertainty = uncertainty;
+
+        Ok(geo_uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{assert_near, GRID, WEST_EARTH};
+    use crate::{CoordRefSystem, GeoUriBuilderError};
+
+    /// Parses geo URIs.
+    fn parse_all(geo_uris: &[&str]) -> Result<Vec<GeoUri>, Error> {
+        geo_uris
+            .iter()
+            .map(|geo_uri| GeoUri::parse(geo_uri))
+            .collect()
+    }
+
+    #[test]
+    fn geo_uri_centroid() -> Result<(), Error> {
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            // A single geo URI is its own centroid, without the other parameters.
+            let geo_uri = GeoUri::parse("geo:52.0907,5.1214,12.5;u=30;foo=bar")?;
+            let centroid = GeoUri::centroid([&geo_uri], Weighting::Equal, method)?;
+            assert!(centroid.approx_eq(&geo_uri, 1e-6));
+            assert_eq!(centroid.altitude(), Some(12.5));
+            assert_eq!(centroid.uncertainty(), Some(30.0));
+            assert_eq!(centroid.param("foo"), None);
+
+            // Symmetric locations across the antimeridian.
+            let geo_uris = parse_all(&["geo:0,179", "geo:0,-179"])?;
+            let centroid = GeoUri::centroid(&geo_uris, Weighting::Equal, method)?;
+            assert_near(centroid.latitude(), 0.0, 1e-9);
+            assert_near(centroid.longitude().abs(), 180.0, 1e-9);
+            assert_eq!(centroid.altitude(), None);
+            assert_eq!(centroid.uncertainty(), None);
+
+            // Symmetric locations around a pole.
+            let geo_uris = parse_all(&["geo:89,0", "geo:89,120", "geo:89,-120"])?;
+            let centroid = GeoUri::centroid(&geo_uris, Weighting::Equal, method)?;
+            assert_near(centroid.latitude(), 90.0, 1e-9);
+
+            // The weighted distances to the locations balance out at the centroid.
+            let geo_uris = parse_all(&[
+                "geo:52.0907,5.1214,10;u=10",
+                "geo:48.8530,2.3499,20;u=20",
+                "geo:51.5072,-0.1276,40;u=40",
+            ])?;
+            for weighting in [Weighting::Equal, Weighting::InverseVariance] {
+                let weights = weighting.weights(&geo_uris.iter().collect::<Vec<_>>())?;
+                let centroid = GeoUri::centroid(&geo_uris, weighting, method)?;
+                let (mut east, mut north) = (0.0, 0.0);
+                for (geo_uri, weight) in geo_uris.iter().zip(&weights) {
+                    let distance = centroid.distance_to(geo_uri, method)?;
+                    let (sin_alpha, cos_alpha) = sincosd(distance.initial_azimuth());
+                    east += weight * distance.distance() * sin_alpha;
+                    north += weight * distance.distance() * cos_alpha;
+                }
+                assert_near(east, 0.0, 1e-5);
+                assert_near(north, 0.0, 1e-5);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_centroid_weighting() -> Result<(), Error> {
+        let method = GeodesicMethod::Ellipsoidal;
+        let geo_uris = parse_all(&["geo:0,0,10;u=1", "geo:0,0.001,20;u=2"])?;
+        let distance = geo_uris[0].distance_to(&geo_uris[1], method)?.distance();
+
+        let centroid = GeoUri::centroid(&geo_uris, Weighting::Equal, method)?;
+        let to_centroid = geo_uris[0].distance_to(&centroid, method)?.distance();
+        assert_near(to_centroid, distance * 0.5, 1e-6);
+        assert_eq!(centroid.altitude(), Some(15.0));
+        assert_near(centroid.uncertainty().unwrap(), 5.0f64.sqrt() / 2.0, 1e-12);
+
+        let centroid = GeoUri::centroid(&geo_uris, Weighting::InverseVariance, method)?;
+        let to_centroid = geo_uris[0].distance_to(&centroid, method)?.distance();
+        assert_near(to_centroid, distance * 0.2, 1e-6);
+        assert_near(centroid.altitude().unwrap(), 12.0, 1e-12);
+        assert_near(centroid.uncertainty().unwrap(), 0.8f64.sqrt(), 1e-12);
+
+        // Exact locations outweigh all others.
+        let geo_uris = parse_all(&["geo:0,0;u=0", "geo:0,0.001;u=0", "geo:1,1;u=5"])?;
+        let centroid = GeoUri::centroid(&geo_uris, Weighting::InverseVariance, method)?;
+        assert_near(centroid.latitude(), 0.0, 1e-9);
+        assert_near(centroid.longitude(), 0.0005, 1e-9);
+        assert_eq!(centroid.uncertainty(), Some(0.0));
+
+        // Locations with an infinite uncertainty carry no weight.
+        let mut unknown = GeoUri::parse("geo:1,1")?;
+        unknown.set_uncertainty(Some(f64::INFINITY))?;
+        let known = GeoUri::parse("geo:0,0;u=5")?;
+        let centroid = GeoUri::centroid([&known, &unknown], Weighting::InverseVariance, method)?;
+        assert!(centroid.approx_eq(&known, 1e-6));
+        assert_eq!(centroid.uncertainty(), Some(5.0));
+        let centroid = GeoUri::centroid([&unknown], Weighting::InverseVariance, method)?;
+        assert!(centroid.approx_eq(&unknown, 1e-6));
+        assert_eq!(centroid.uncertainty(), Some(f64::INFINITY));
+
+        let geo_uris = parse_all(&["geo:0,0;u=1", "geo:0,0.001"])?;
+        assert_eq!(
+            GeoUri::centroid(&geo_uris, Weighting::InverseVariance, method),
+            Err(Error::MissingUncertainty)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_centroid_custom_crs() -> Result<(), GeoUriBuilderError> {
+        let method = GeodesicMethod::Ellipsoidal;
+        let west_earth = CoordRefSystem::Custom(&WEST_EARTH);
+        let geo_uris = [
+            GeoUri::builder()
+                .crs(west_earth)
+                .latitude(10.0)
+                .longitude(1.0)
+                .build()?,
+            GeoUri::builder()
+                .crs(west_earth)
+                .latitude(10.0)
+                .longitude(359.0)
+                .build()?,
+        ];
+        let centroid = GeoUri::centroid(&geo_uris, Weighting::Equal, method)?;
+        assert_eq!(centroid.crs(), west_earth);
+        assert!(centroid.latitude() > 10.0);
+        assert_near(centroid.longitude(), 0.0, 1e-9);
+
+        let mars = GeoUri::parse("geo:0,10;crs=mars")?;
+        let mars2 = GeoUri::parse("geo:0,20;crs=mars")?;
+        let centroid = GeoUri::centroid([&mars, &mars2], Weighting::Equal, method)?;
+        assert_eq!(centroid.crs(), CoordRefSystem::Mars);
+        assert_near(centroid.longitude(), 15.0, 1e-9);
+
+        let earth = GeoUri::parse("geo:0,10")?;
+        assert_eq!(
+            GeoUri::centroid([&mars, &earth], Weighting::Equal, method),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        let grid = GeoUri::builder()
+            .crs(CoordRefSystem::Custom(&GRID))
+            .latitude(1.0)
+            .longitude(2.0)
+            .build()?;
+        assert_eq!(
+            GeoUri::centroid([&grid], Weighting::Equal, method),
+            Err(Error::MissingEllipsoid)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_centroid_invalid() -> Result<(), Error> {
+        let method = GeodesicMethod::Ellipsoidal;
+        assert_eq!(
+            GeoUri::centroid([], Weighting::Equal, method),
+            Err(Error::MissingGeoUris)
+        );
+
+        let geo_uris = parse_all(&["geo:0,0", "geo:0,180"])?;
+        assert_eq!(
+            GeoUri::centroid(&geo_uris, Weighting::Equal, method),
+            Err(Error::IndeterminateCentroid)
+        );
+        let geo_uris = parse_all(&["geo:90,0", "geo:-90,0"])?;
+        assert_eq!(
+            GeoUri::centroid(&geo_uris, Weighting::Equal, method),
+            Err(Error::IndeterminateCentroid)
+        );
+
+        // Nearly antipodal locations, for which the iteration keeps jumping around the pole.
+        let geo_uris = parse_all(&["geo:0,0", "geo:0.001,180"])?;
+        assert_eq!(
+            GeoUri::centroid(&geo_uris, Weighting::Equal, GeodesicMethod::Spherical),
+            Err(Error::IndeterminateCentroid)
+        );
+
+        Ok(())
+    }
+}
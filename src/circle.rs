@@ -0,0 +1,485 @@
+//! Geometry of uncertainty circles on the reference ellipsoid.
+
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use crate::{Crs, Ellipsoid, Error, GeoUri, GeodesicMethod};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl GeoUri {
+    /// Returns whether the location of another geo URI lies within the uncertainty circle.
+    ///
+    /// The uncertainty circle is the set of locations whose distance along the surface to the
+    /// location is at most the uncertainty, where a missing uncertainty counts as zero. The
+    /// distance is computed using the method (see [`GeoUri::distance_to`]), and the altitudes
+    /// and the uncertainty of the other geo URI are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.0907,5.1214;u=100")?;
+    /// let geo_uri2 = GeoUri::parse("geo:52.0910,5.1220")?;
+    /// assert!(geo_uri.circle_contains_point(&geo_uri2, GeodesicMethod::Ellipsoidal)?);
+    /// let geo_uri2 = GeoUri::parse("geo:52.0920,5.1214")?;
+    /// assert!(!geo_uri.circle_contains_point(&geo_uri2, GeodesicMethod::Ellipsoidal)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn circle_contains_point(
+        &self,
+        other: &Self,
+        method: GeodesicMethod,
+    ) -> Result<bool, Error> {
+        let distance = self.distance_to(other, method)?.distance();
+
+        Ok(distance <= self.radius())
+    }
+
+    /// Returns whether the uncertainty circle contains the uncertainty circle of another geo
+    /// URI.
+    ///
+    /// This is the case if the distance between the locations plus the uncertainty of the other
+    /// geo URI is at most the uncertainty, where a missing uncertainty counts as zero. See
+    /// [`GeoUri::circle_contains_point`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.0907,5.1214;u=100")?;
+    /// let geo_uri2 = GeoUri::parse("geo:52.0910,5.1220;u=10")?;
+    /// assert!(geo_uri.circle_contains_circle(&geo_uri2, GeodesicMethod::Ellipsoidal)?);
+    /// assert!(!geo_uri2.circle_contains_circle(&geo_uri, GeodesicMethod::Ellipsoidal)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn circle_contains_circle(
+        &self,
+        other: &Self,
+        method: GeodesicMethod,
+    ) -> Result<bool, Error> {
+        let distance = self.distance_to(other, method)?.distance();
+
+        Ok(distance + other.radius() <= self.radius())
+    }
+
+    /// Returns whether the uncertainty circle overlaps the uncertainty circle of another geo
+    /// URI.
+    ///
+    /// This is the case if the distance between the locations is at most the sum of both
+    /// uncertainties, where a missing uncertainty counts as zero, so circles that only touch
+    /// overlap as well. See [`GeoUri::circle_contains_point`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.0907,5.1214;u=50")?;
+    /// let geo_uri2 = GeoUri::parse("geo:52.0910,5.1220;u=10")?;
+    /// assert!(geo_uri.circle_overlaps(&geo_uri2, GeodesicMethod::Ellipsoidal)?);
+    /// let geo_uri2 = GeoUri::parse("geo:52.0920,5.1214;u=10")?;
+    /// assert!(!geo_uri.circle_overlaps(&geo_uri2, GeodesicMethod::Ellipsoidal)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn circle_overlaps(&self, other: &Self, method: GeodesicMethod) -> Result<bool, Error> {
+        let distance = self.distance_to(other, method)?.distance();
+
+        Ok(distance <= self.radius() + other.radius())
+    }
+
+    /// Returns the area of the uncertainty circle in square meters.
+    ///
+    /// A missing uncertainty counts as zero. With [`GeodesicMethod::Spherical`], the circle is
+    /// a spherical cap on a sphere with the mean radius of the reference ellipsoid. With
+    /// [`GeodesicMethod::Ellipsoidal`], the sphere has the Gaussian radius of curvature of the
+    /// reference ellipsoid at the location instead, which is accurate for circles that are small
+    /// compared to the ellipsoid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.0907,5.1214;u=100")?;
+    /// let area = geo_uri.circle_area(GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(area.round(), 31_416.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid.
+    pub fn circle_area(&self, method: GeodesicMethod) -> Result<f64, Error> {
+        let ellipsoid = self.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+        let radius = self.sphere_radius(&ellipsoid, method);
+
+        Ok(cap_area(self.radius() / radius) * radius * radius)
+    }
+
+    /// Returns the area of the overlap of the uncertainty circle and the uncertainty circle of
+    /// another geo URI in square meters.
+    ///
+    /// The distance between the locations is computed using the method (see
+    /// [`GeoUri::distance_to`]), and the circles are taken to be on a sphere as described for
+    /// [`GeoUri::circle_area`], where the Gaussian radius of curvature is averaged over both
+    /// locations. If one circle contains the other, this is the area of the smaller one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:0,0;u=100")?;
+    /// let geo_uri2 = GeoUri::parse("geo:0,0.0009;u=100")?;
+    /// let area = geo_uri.circle_overlap_area(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(area.round(), 12_251.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn circle_overlap_area(&self, other: &Self, method: GeodesicMethod) -> Result<f64, Error> {
+        let ellipsoid = self.common_ellipsoid(other)?;
+        let distance = self.distance_to(other, method)?.distance();
+        let radius = (self.sphere_radius(&ellipsoid, method)
+            + other.sphere_radius(&ellipsoid, method))
+            / 2.0;
+        let rho1 = self.radius() / radius;
+        let rho2 = other.radius() / radius;
+        let theta = distance / radius;
+
+        let area = if theta >= rho1 + rho2 {
+            0.0
+        } else if theta <= (rho1 - rho2).abs() {
+            cap_area(rho1.min(rho2))
+        } else {
+            lens_area(rho1, rho2, theta)
+        };
+
+        Ok(area * radius * radius)
+    }
+
+    /// Returns the vertices of a polygon approximating the uncertainty circle.
+    ///
+    /// The vertices lie on the circle at equal angles, starting due north and going
+    /// counterclockwise as required for exterior rings by GeoJSON, without repeating the first
+    /// vertex at the end. They are computed using the method along geodesics (see
+    /// [`GeoUri::destination`]), so they are correct near the poles and across the antimeridian,
+    /// and have the altitude of this geo URI. A missing uncertainty counts as zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:0,180;u=1000")?;
+    /// let polygon = geo_uri.circle_polygon(4, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(polygon.len(), 4);
+    /// assert!(polygon[0].latitude() > 0.0);
+    /// assert!(polygon[1].longitude() < 180.0);
+    /// assert!(polygon[2].latitude() < 0.0);
+    /// assert!(polygon[3].longitude() < -179.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid.
+    pub fn circle_polygon(
+        &self,
+        vertices: usize,
+        method: GeodesicMethod,
+    ) -> Result<Vec<Self>, Error> {
+        (0..vertices)
+            .map(|i| {
+                let azimuth = -360.0 * i as f64 / vertices as f64;
+                self.destination(self.radius(), azimuth, method)
+            })
+            .collect()
+    }
+
+    /// Returns the radius of the uncertainty circle in meters, where a missing uncertainty
+    /// counts as zero.
+    pub(crate) fn radius(&self) -> f64 {
+        self.uncertainty.unwrap_or_default()
+    }
+
+    /// Returns the radius of the sphere on which the uncertainty circle is taken to be with the
+    /// method.
+    fn sphere_radius(&self, ellipsoid: &Ellipsoid, method: GeodesicMethod) -> f64 {
+        match method {
+            GeodesicMethod::Spherical => ellipsoid.mean_radius(),
+            GeodesicMethod::Ellipsoidal => {
+                let e2 = ellipsoid.flattening() * (2.0 - ellipsoid.flattening());
+                let (latitude, _) = self.east_coords();
+                let sin_phi = latitude.to_radians().sin();
+
+                ellipsoid.equatorial_radius() * (1.0 - e2).sqrt() / (1.0 - e2 * sin_phi * sin_phi)
+            }
+        }
+    }
+}
+
+/// Returns the area of a spherical cap with an angular radius on the unit sphere.
+fn cap_area(rho: f64) -> f64 {
+    4.0 * PI * (rho / 2.0).sin().powi(2)
+}
+
+/// Returns the area of the overlap of two intersecting spherical caps with angular radii at an
+/// angular distance on the unit sphere.
+///
+/// This is the sum of the segments of both caps beyond the great circle through the
+/// intersections, with the angles at the centres computed using the half-angle formula to stay
+/// accurate for small caps.
+fn lens_area(rho1: f64, rho2: f64, theta: f64) -> f64 {
+    let s = (rho1 + rho2 + theta) / 2.0;
+    let angle = |opposite: f64, adjacent: f64| {
+        let tan2 = (s - adjacent).sin() * (s - theta).sin() / (s.sin() * (s - opposite).sin());
+        2.0 * tan2.max(0.0).sqrt().atan()
+    };
+
+    segment_area(rho1, angle(rho2, rho1)) + segment_area(rho2, angle(rho1, rho2))
+}
+
+/// Returns the area of the segment of a spherical cap with an angular radius beyond the great
+/// circle through two locations on its edge, which are twice an angle apart as seen from the
+/// centre, on the unit sphere.
+///
+/// This is the sector minus the triangle with the centre, whose spherical excess is computed
+/// so as to stay accurate for small caps.
+fn segment_area(rho: f64, angle: f64) -> f64 {
+    let t = (rho / 2.0).tan().powi(2);
+    let excess = 2.0 * (t * (2.0 * angle).sin()).atan2(1.0 + t * (2.0 * angle).cos());
+
+    4.0 * angle * (rho / 2.0).sin().powi(2) - excess
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{assert_near, GRID};
+    use crate::{CoordRefSystem, GeoUriBuilderError};
+
+    /// Returns the area of the overlap of two circles with radii at a distance in the plane.
+    fn planar_lens_area(r1: f64, r2: f64, d: f64) -> f64 {
+        let alpha1 = ((d * d + r1 * r1 - r2 * r2) / (2.0 * d * r1)).acos();
+        let alpha2 = ((d * d + r2 * r2 - r1 * r1) / (2.0 * d * r2)).acos();
+
+        r1 * r1 * (alpha1 - alpha1.sin() * alpha1.cos())
+            + r2 * r2 * (alpha2 - alpha2.sin() * alpha2.cos())
+    }
+
+    #[test]
+    fn geo_uri_circle_contains_overlaps() -> Result<(), Error> {
+        let method = GeodesicMethod::Ellipsoidal;
+        let geo_uri = GeoUri::parse("geo:0,0;u=1000")?;
+        let east = |longitude: f64, uncertainty: f64| {
+            GeoUri::parse(&format!("geo:0,{longitude};u={uncertainty}"))
+        };
+        // One degree of longitude on the equator is 111 319.491 m.
+        let meters = |distance: f64| distance / 111_319.491;
+
+        assert!(geo_uri.circle_contains_point(&east(meters(999.0), 5000.0)?, method)?);
+        assert!(!geo_uri.circle_contains_point(&east(meters(1001.0), 0.0)?, method)?);
+        assert!(geo_uri.circle_contains_point(&east(meters(-999.0), 0.0)?, method)?);
+        assert!(GeoUri::parse("geo:0,0")?.circle_contains_point(&geo_uri, method)?);
+
+        assert!(geo_uri.circle_contains_circle(&east(meters(499.0), 500.0)?, method)?);
+        assert!(!geo_uri.circle_contains_circle(&east(meters(501.0), 500.0)?, method)?);
+        assert!(geo_uri.circle_contains_circle(&geo_uri, method)?);
+        assert!(!east(0.0, 500.0)?.circle_contains_circle(&geo_uri, method)?);
+
+        assert!(geo_uri.circle_overlaps(&east(meters(1499.0), 500.0)?, method)?);
+        assert!(!geo_uri.circle_overlaps(&east(meters(1501.0), 500.0)?, method)?);
+        assert!(geo_uri.circle_overlaps(&east(meters(999.0), 0.0)?, method)?);
+
+        // Across the antimeridian.
+        let geo_uri = GeoUri::parse("geo:10,180;u=1000")?;
+        let geo_uri2 = GeoUri::parse("geo:10,-179.995;u=10")?;
+        assert!(geo_uri.circle_contains_circle(&geo_uri2, method)?);
+        assert!(geo_uri.circle_contains_circle(&geo_uri2, GeodesicMethod::Spherical)?);
+
+        assert_eq!(
+            geo_uri.circle_overlaps(&GeoUri::parse("geo:10,180;crs=moon")?, method),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_circle_area() -> Result<(), Error> {
+        let geo_uri = GeoUri::parse("geo:52.0907,5.1214;u=100")?;
+        let area = PI * 100.0 * 100.0;
+        assert_near(
+            geo_uri.circle_area(GeodesicMethod::Ellipsoidal)?,
+            area,
+            1e-3,
+        );
+        assert_near(geo_uri.circle_area(GeodesicMethod::Spherical)?, area, 1e-3);
+        assert_eq!(
+            GeoUri::parse("geo:52.0907,5.1214")?.circle_area(GeodesicMethod::Ellipsoidal)?,
+            0.0
+        );
+
+        // A large circle is smaller than in the plane because of the curvature.
+        let geo_uri = GeoUri::parse("geo:90,0;u=1000000")?;
+        let area = geo_uri.circle_area(GeodesicMethod::Spherical)?;
+        assert_near(area, 3_135_148_059_369.65, 1.0);
+        assert!(geo_uri.circle_area(GeodesicMethod::Ellipsoidal)? > area);
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_circle_overlap_area() -> Result<(), Error> {
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let geo_uri = GeoUri::parse("geo:52.0907,5.1214;u=100")?;
+            for (uri, uncertainty) in [
+                ("geo:52.0907,5.1224", 50.0),
+                ("geo:52.0910,5.1214", 100.0),
+                ("geo:52.0917,5.1230", 80.0),
+            ] {
+                let mut geo_uri2 = GeoUri::parse(uri)?;
+                geo_uri2.set_uncertainty(Some(uncertainty))?;
+                let distance = geo_uri.distance_to(&geo_uri2, method)?.distance();
+                let area = geo_uri.circle_overlap_area(&geo_uri2, method)?;
+                assert_near(area, planar_lens_area(100.0, uncertainty, distance), 1e-3);
+                assert_near(geo_uri2.circle_overlap_area(&geo_uri, method)?, area, 1e-9);
+            }
+
+            let geo_uri2 = GeoUri::parse("geo:52.0908,5.1214;u=10")?;
+            let area = geo_uri.circle_overlap_area(&geo_uri2, method)?;
+            assert_eq!(area, geo_uri2.circle_area(method)?);
+            let geo_uri2 = GeoUri::parse("geo:52.1,5.1214;u=10")?;
+            assert_eq!(geo_uri.circle_overlap_area(&geo_uri2, method)?, 0.0);
+            let geo_uri2 = GeoUri::parse("geo:52.0907,5.1214")?;
+            assert_eq!(geo_uri.circle_overlap_area(&geo_uri2, method)?, 0.0);
+        }
+
+        // Large circles across the antimeridian.
+        let geo_uri = GeoUri::parse("geo:0,179;u=1000000")?;
+        let geo_uri2 = GeoUri::parse("geo:0,-179;u=1000000")?;
+        let area = geo_uri.circle_overlap_area(&geo_uri2, GeodesicMethod::Spherical)?;
+        let radius = Ellipsoid::WGS84.mean_radius();
+        let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Spherical)?;
+        assert_near(
+            area / (radius * radius),
+            lens_area(1e6 / radius, 1e6 / radius, distance.distance() / radius),
+            1e-15,
+        );
+        assert!(area < planar_lens_area(1e6, 1e6, distance.distance()));
+        assert!(area > 0.9 * planar_lens_area(1e6, 1e6, distance.distance()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn lens_area_accuracy() {
+        // Compared to the formula of Tovchigrechko and Vakser evaluated with 50 digits.
+        for (rho1, rho2, theta, expected) in [
+            (0.1, 0.1, 0.1, 0.012_284_289_305_775_364),
+            (0.3, 0.1, 0.25, 0.024_446_773_114_174_05),
+            (1e-5, 1e-5, 1e-5, 1.228_369_698_609_345_8e-10),
+            (1e-7, 2e-7, 2.5e-7, 5.224_193_020_631_084e-15),
+            (2.0, 0.5, 1.8, 0.585_780_992_679_476_7),
+        ] {
+            assert_near(lens_area(rho1, rho2, theta), expected, expected * 1e-14);
+        }
+    }
+
+    #[test]
+    fn geo_uri_circle_polygon() -> Result<(), GeoUriBuilderError> {
+        for (uri, method) in [
+            ("geo:0,180,10;u=1000", GeodesicMethod::Ellipsoidal),
+            ("geo:-89.99,45;u=5000", GeodesicMethod::Ellipsoidal),
+            ("geo:52.0907,5.1214;u=100", GeodesicMethod::Spherical),
+            (
+                "geo:4.5,359.999;crs=mars;u=10000",
+                GeodesicMethod::Ellipsoidal,
+            ),
+        ] {
+            let geo_uri = GeoUri::parse(uri)?;
+            let polygon = geo_uri.circle_polygon(16, method)?;
+            assert_eq!(polygon.len(), 16);
+            let mut previous_azimuth = 382.5;
+            for vertex in &polygon {
+                assert_eq!(vertex.crs(), geo_uri.crs());
+                assert_eq!(vertex.altitude(), geo_uri.altitude());
+                let distance = geo_uri.distance_to(vertex, method)?;
+                assert_near(
+                    distance.distance(),
+                    geo_uri.uncertainty().unwrap_or(0.0),
+                    1e-6,
+                );
+                let azimuth = distance.initial_azimuth();
+                let azimuth = if azimuth < 1e-6 { 360.0 } else { azimuth };
+                assert_near(previous_azimuth - azimuth, 22.5, 1e-6);
+                previous_azimuth = azimuth;
+            }
+        }
+
+        let geo_uri = GeoUri::parse("geo:0,180;u=1000")?;
+        let polygon = geo_uri.circle_polygon(4, GeodesicMethod::Ellipsoidal)?;
+        assert!(polygon[0].latitude() > 0.0);
+        assert!(polygon[1].longitude() < 180.0 && polygon[1].longitude() > 179.0);
+        assert!(polygon[3].longitude() < -179.0);
+        assert!(geo_uri
+            .circle_polygon(0, GeodesicMethod::Ellipsoidal)?
+            .is_empty());
+
+        let geo_uri = GeoUri::builder()
+            .crs(CoordRefSystem::Custom(&GRID))
+            .latitude(0.0)
+            .longitude(0.0)
+            .uncertainty(10.0)
+            .build()?;
+        assert_eq!(
+            geo_uri.circle_polygon(4, GeodesicMethod::Ellipsoidal),
+            Err(Error::MissingEllipsoid)
+        );
+        assert_eq!(
+            geo_uri.circle_area(GeodesicMethod::Ellipsoidal),
+            Err(Error::MissingEllipsoid)
+        );
+
+        Ok(())
+    }
+}
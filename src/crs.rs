@@ -0,0 +1,506 @@
+//! Pluggable coordinate reference systems.
+
+use core::fmt;
+use core::ops::RangeInclusive;
+
+use crate::{CoordRefSystem, Error};
+
+/// A coordinate reference system that the coordinates of a geo URI can be expressed in.
+///
+/// Besides the built-in coordinate reference systems of [`CoordRefSystem`], custom ones can be
+/// implemented using this trait. Use [`CoordRefSystem::Custom`] to build a
+/// [`GeoUri`](crate::GeoUri) with a custom coordinate reference system, and
+/// [`ParseOptions::custom_crs`](crate::ParseOptions::custom_crs) to make the parser recognize it
+/// in the `crs` parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{Axis, CoordRefSystem, Crs, Error, GeoUri, ParseOptions};
+///
+/// /// A local engineering grid with northing and easting in meters.
+/// #[derive(Debug)]
+/// struct SiteGrid;
+///
+/// impl Crs for SiteGrid {
+///     fn name(&self) -> &str {
+///         "site-grid"
+///     }
+///
+///     fn validate(&self, northing: f64, easting: f64) -> Result<(), Error> {
+///         if !(0.0..=1000.0).contains(&northing) {
+///             return Err(Error::OutOfRangeLatitude);
+///         }
+///         if !(0.0..=1000.0).contains(&easting) {
+///             return Err(Error::OutOfRangeLongitude);
+///         }
+///
+///         Ok(())
+///     }
+///
+///     fn axes(&self) -> [Axis; 3] {
+///         [
+///             Axis::new("northing", "meter"),
+///             Axis::new("easting", "meter"),
+///             Axis::new("height", "meter"),
+///         ]
+///     }
+/// }
+///
+/// static SITE_GRID: SiteGrid = SiteGrid;
+/// static CUSTOM_CRS: [&dyn Crs; 1] = [&SITE_GRID];
+///
+/// # fn main() -> Result<(), Error> {
+/// let mut options = ParseOptions::new();
+/// options.custom_crs(&CUSTOM_CRS);
+/// let geo_uri = GeoUri::parse_with("geo:120.5,400;crs=site-grid", &options)?;
+/// assert_eq!(geo_uri.crs(), CoordRefSystem::Custom(&SITE_GRID));
+/// assert_eq!(geo_uri.to_string(), "geo:120.5,400;crs=site-grid");
+///
+/// assert_eq!(
+///     GeoUri::parse_with("geo:1200,400;crs=site-grid", &options),
+///     Err(Error::OutOfRangeLatitude)
+/// );
+/// assert_eq!(
+///     GeoUri::parse("geo:120.5,400;crs=site-grid"), // Not registered!
+///     Err(Error::InvalidCoordRefSystem)
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub trait Crs: fmt::Debug + Send + Sync {
+    /// Returns the name used for the coordinate reference system in the `crs` parameter.
+    ///
+    /// Names are compared case-insensitively and should only consist of letters, digits and `-`.
+    fn name(&self) -> &str;
+
+    /// Validates geolocation coordinates against the coordinate reference system.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the first or second coordinate is out of range with respect to
+    /// the coordinate reference system, which should be [`Error::OutOfRangeLatitude`] or
+    /// [`Error::OutOfRangeLongitude`] respectively.
+    fn validate(&self, latitude: f64, longitude: f64) -> Result<(), Error>;
+
+    /// Returns the reference ellipsoid of the body the coordinates are on, if any.
+    ///
+    /// By default, there is no reference ellipsoid.
+    fn ellipsoid(&self) -> Option<Ellipsoid> {
+        None
+    }
+
+    /// Returns the range and direction of the longitude (second coordinate).
+    ///
+    /// By default, longitudes increase eastwards in the range `-180.0..=180.0` degrees.
+    fn longitude_convention(&self) -> LongitudeConvention {
+        LongitudeConvention::default()
+    }
+
+    /// Returns how the latitude (first coordinate) relates to the reference ellipsoid.
+    ///
+    /// By default, the latitude is geodetic.
+    fn latitude_convention(&self) -> LatitudeConvention {
+        LatitudeConvention::default()
+    }
+
+    /// Returns the meaning and unit of the three coordinates.
+    ///
+    /// By default, these are a latitude and longitude in degrees and an altitude in meters.
+    fn axes(&self) -> [Axis; 3] {
+        [
+            Axis::new("latitude", "degree"),
+            Axis::new("longitude", "degree"),
+            Axis::new("altitude", "meter"),
+        ]
+    }
+
+    /// Returns whether the longitude (second coordinate) is irrelevant when comparing locations
+    /// at the given latitude (first coordinate).
+    ///
+    /// This is the case for the poles of geographic coordinate reference systems. By default,
+    /// the longitude is never ignored.
+    fn is_longitude_ignored(&self, latitude: f64) -> bool {
+        let _ = latitude;
+
+        false
+    }
+}
+
+impl Crs for CoordRefSystem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Wgs84 => "wgs84",
+            Self::Mercury => "mercury",
+            Self::Venus => "venus",
+            Self::Moon => "moon",
+            Self::Mars => "mars",
+            Self::Custom(crs) => crs.name(),
+        }
+    }
+
+    fn validate(&self, latitude: f64, longitude: f64) -> Result<(), Error> {
+        if let Self::Custom(crs) = self {
+            return crs.validate(latitude, longitude);
+        }
+
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(Error::OutOfRangeLatitude);
+        }
+        if !self.longitude_convention().range().contains(&longitude) {
+            return Err(Error::OutOfRangeLongitude);
+        }
+
+        Ok(())
+    }
+
+    fn ellipsoid(&self) -> Option<Ellipsoid> {
+        match self {
+            Self::Wgs84 => Some(Ellipsoid::WGS84),
+            Self::Mercury => Some(Ellipsoid::new(2_440_530.0, 2_438_260.0)),
+            Self::Venus => Some(Ellipsoid::new(6_051_800.0, 6_051_800.0)),
+            Self::Moon => Some(Ellipsoid::new(1_737_400.0, 1_737_400.0)),
+            Self::Mars => Some(Ellipsoid::new(3_396_190.0, 3_376_200.0)),
+            Self::Custom(crs) => crs.ellipsoid(),
+        }
+    }
+
+    fn longitude_convention(&self) -> LongitudeConvention {
+        match self {
+            Self::Wgs84 | Self::Moon => LongitudeConvention::East180,
+            Self::Mercury | Self::Venus | Self::Mars => LongitudeConvention::East360,
+            Self::Custom(crs) => crs.longitude_convention(),
+        }
+    }
+
+    fn latitude_convention(&self) -> LatitudeConvention {
+        match self {
+            Self::Wgs84 => LatitudeConvention::Geodetic,
+            Self::Mercury | Self::Venus | Self::Moon | Self::Mars => {
+                LatitudeConvention::Planetocentric
+            }
+            Self::Custom(crs) => crs.latitude_convention(),
+        }
+    }
+
+    fn axes(&self) -> [Axis; 3] {
+        match self {
+            Self::Custom(crs) => crs.axes(),
+            _ => [
+                Axis::new("latitude", "degree"),
+                Axis::new("longitude", "degree"),
+                Axis::new("altitude", "meter"),
+            ],
+        }
+    }
+
+    fn is_longitude_ignored(&self, latitude: f64) -> bool {
+        match self {
+            Self::Custom(crs) => crs.is_longitude_ignored(latitude),
+            _ => latitude == 90.0 || latitude == -90.0,
+        }
+    }
+}
+
+/// A reference ellipsoid (of revolution) approximating the shape of a celestial body.
+///
+/// See [`Crs::ellipsoid`].
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::Ellipsoid;
+///
+/// let ellipsoid = Ellipsoid::WGS84;
+/// assert_eq!(ellipsoid.equatorial_radius(), 6_378_137.0);
+/// assert!((1.0 / ellipsoid.flattening() - 298.257_223_563).abs() < 1e-6);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ellipsoid {
+    /// The equatorial radius (semi-major axis) in meters.
+    equatorial_radius: f64,
+
+    /// The polar radius (semi-minor axis) in meters.
+    polar_radius: f64,
+}
+
+impl Ellipsoid {
+    /// The WGS-84 reference ellipsoid of the Earth.
+    pub const WGS84: Self = Self::new(6_378_137.0, 6_356_752.314_245_179);
+
+    /// Creates a new ellipsoid with the given equatorial and polar radius in meters.
+    pub const fn new(equatorial_radius: f64, polar_radius: f64) -> Self {
+        Self {
+            equatorial_radius,
+            polar_radius,
+        }
+    }
+
+    /// Returns the equatorial radius (semi-major axis) in meters.
+    pub const fn equatorial_radius(&self) -> f64 {
+        self.equatorial_radius
+    }
+
+    /// Returns the polar radius (semi-minor axis) in meters.
+    pub const fn polar_radius(&self) -> f64 {
+        self.polar_radius
+    }
+
+    /// Returns the flattening of the ellipsoid.
+    pub fn flattening(&self) -> f64 {
+        (self.equatorial_radius - self.polar_radius) / self.equatorial_radius
+    }
+
+    /// Returns the arithmetic mean radius of the ellipsoid in meters.
+    pub fn mean_radius(&self) -> f64 {
+        (2.0 * self.equatorial_radius + self.polar_radius) / 3.0
+    }
+}
+
+/// The relation of latitudes in a coordinate reference system to the reference ellipsoid.
+///
+/// On a sphere, both conventions are the same. Computations on the reference ellipsoid (e.g.
+/// [`GeoUri::distance_to`](crate::GeoUri::distance_to)) convert planetocentric latitudes to and
+/// from geodetic latitudes, where the altitude is taken to be along the normal to the ellipsoid.
+///
+/// See [`Crs::latitude_convention`].
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LatitudeConvention {
+    /// The latitude is the angle between the equatorial plane and the normal to the reference
+    /// ellipsoid, i.e. geodetic or planetographic latitude.
+    #[default]
+    Geodetic,
+
+    /// The latitude is the angle between the equatorial plane and the line from the centre of
+    /// the reference ellipsoid to the location on its surface, i.e. planetocentric latitude.
+    Planetocentric,
+}
+
+/// The range and direction of longitudes in a coordinate reference system.
+///
+/// See [`Crs::longitude_convention`].
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LongitudeConvention {
+    /// Longitudes increase eastwards in the range `-180.0..=180.0` degrees.
+    #[default]
+    East180,
+
+    /// Longitudes increase eastwards in the range `0.0..=360.0` degrees.
+    East360,
+
+    /// Longitudes increase westwards in the range `0.0..=360.0` degrees.
+    ///
+    /// This is used by planetographic coordinate reference systems of prograde rotating bodies.
+    West360,
+}
+
+impl LongitudeConvention {
+    /// Returns the range of valid longitudes in degrees.
+    pub fn range(&self) -> RangeInclusive<f64> {
+        match self {
+            Self::East180 => -180.0..=180.0,
+            Self::East360 | Self::West360 => 0.0..=360.0,
+        }
+    }
+
+    /// Returns whether longitudes increase eastwards.
+    pub fn is_east_positive(&self) -> bool {
+        matches!(self, Self::East180 | Self::East360)
+    }
+}
+
+/// The meaning and unit of a coordinate of a coordinate reference system.
+///
+/// See [`Crs::axes`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Axis {
+    /// The name of the axis.
+    name: &'static str,
+
+    /// The unit of the coordinate along the axis.
+    unit: &'static str,
+}
+
+impl Axis {
+    /// Creates a new axis with the given name and unit.
+    pub const fn new(name: &'static str, unit: &'static str) -> Self {
+        Self { name, unit }
+    }
+
+    /// Returns the name of the axis, e.g. `latitude`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the unit of the coordinate along the axis, e.g. `degree`.
+    pub fn unit(&self) -> &'static str {
+        self.unit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeoUri, ParseOptions};
+
+    #[derive(Debug)]
+    struct TestGrid;
+
+    impl Crs for TestGrid {
+        fn name(&self) -> &str {
+            "Test-Grid"
+        }
+
+        fn validate(&self, latitude: f64, longitude: f64) -> Result<(), Error> {
+            if latitude < 0.0 {
+                return Err(Error::OutOfRangeLatitude);
+            }
+            if longitude < 0.0 {
+                return Err(Error::OutOfRangeLongitude);
+            }
+
+            Ok(())
+        }
+
+        fn is_longitude_ignored(&self, latitude: f64) -> bool {
+            latitude == 0.0
+        }
+    }
+
+    static TEST_GRID: TestGrid = TestGrid;
+    static CUSTOM_CRS: [&dyn Crs; 1] = [&TEST_GRID];
+
+    #[test]
+    fn crs_wgs84() {
+        let crs = CoordRefSystem::Wgs84;
+        assert_eq!(crs.name(), "wgs84");
+        assert_eq!(Crs::validate(&crs, 52.107, 5.134), Ok(()));
+        assert_eq!(crs.axes()[0], Axis::new("latitude", "degree"));
+        assert_eq!(crs.axes()[2].unit(), "meter");
+        assert!(crs.is_longitude_ignored(90.0));
+        assert!(crs.is_longitude_ignored(-90.0));
+        assert!(!crs.is_longitude_ignored(52.107));
+        assert_eq!(crs.ellipsoid(), Some(Ellipsoid::WGS84));
+        assert_eq!(crs.longitude_convention(), LongitudeConvention::East180);
+        assert_eq!(crs.latitude_convention(), LatitudeConvention::Geodetic);
+    }
+
+    #[test]
+    fn crs_planetary() -> Result<(), Error> {
+        let crs = CoordRefSystem::Mars;
+        assert_eq!(crs.name(), "mars");
+        assert_eq!(crs.longitude_convention(), LongitudeConvention::East360);
+        assert_eq!(
+            crs.latitude_convention(),
+            LatitudeConvention::Planetocentric
+        );
+        assert_eq!(crs.validate(-4.5895, 137.4417), Ok(()));
+        assert_eq!(crs.validate(-4.5895, 360.0), Ok(()));
+        assert_eq!(
+            crs.validate(-4.5895, -137.4417),
+            Err(Error::OutOfRangeLongitude)
+        );
+        assert_eq!(crs.validate(94.0, 137.4417), Err(Error::OutOfRangeLatitude));
+        assert!(crs.is_longitude_ignored(-90.0));
+        assert_eq!(
+            crs.ellipsoid().map(|ellipsoid| ellipsoid.polar_radius()),
+            Some(3_376_200.0)
+        );
+
+        let crs = CoordRefSystem::Moon;
+        assert_eq!(crs.longitude_convention(), LongitudeConvention::East180);
+        assert_eq!(crs.validate(0.6875, 23.4333), Ok(()));
+        assert_eq!(crs.validate(0.6875, 200.0), Err(Error::OutOfRangeLongitude));
+        assert_eq!(
+            crs.ellipsoid().map(|ellipsoid| ellipsoid.flattening()),
+            Some(0.0)
+        );
+
+        let geo_uri =
+            GeoUri::parse_with("geo:0.6875,23.4333;crs=Moon;u=100", &ParseOptions::strict())?;
+        assert_eq!(geo_uri.crs(), CoordRefSystem::Moon);
+        assert_eq!(geo_uri.to_string(), "geo:0.6875,23.4333;crs=moon;u=100");
+        assert_ne!(geo_uri, GeoUri::parse("geo:0.6875,23.4333;u=100")?);
+        assert_eq!(
+            GeoUri::parse("geo:10,300;crs=venus")?.crs(),
+            CoordRefSystem::Venus
+        );
+        assert_eq!(
+            GeoUri::parse("geo:10,300;crs=mercury")?.crs(),
+            CoordRefSystem::Mercury
+        );
+        assert_eq!(
+            GeoUri::parse("geo:10,300;crs=moon"),
+            Err(Error::OutOfRangeLongitude)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn ellipsoid() {
+        let ellipsoid = Ellipsoid::WGS84;
+        assert_eq!(ellipsoid.equatorial_radius(), 6_378_137.0);
+        assert!((1.0 / ellipsoid.flattening() - 298.257_223_563).abs() < 1e-6);
+        assert!((ellipsoid.mean_radius() - 6_371_008.771).abs() < 1e-3);
+
+        let ellipsoid = Ellipsoid::new(1000.0, 1000.0);
+        assert_eq!(ellipsoid.flattening(), 0.0);
+        assert_eq!(ellipsoid.mean_radius(), 1000.0);
+    }
+
+    #[test]
+    fn longitude_convention() {
+        assert_eq!(LongitudeConvention::default(), LongitudeConvention::East180);
+        assert_eq!(LongitudeConvention::East180.range(), -180.0..=180.0);
+        assert_eq!(LongitudeConvention::East360.range(), 0.0..=360.0);
+        assert_eq!(LongitudeConvention::West360.range(), 0.0..=360.0);
+        assert!(LongitudeConvention::East360.is_east_positive());
+        assert!(!LongitudeConvention::West360.is_east_positive());
+    }
+
+    #[test]
+    fn crs_custom() -> Result<(), Error> {
+        let crs = CoordRefSystem::Custom(&TEST_GRID);
+        assert_eq!(crs.name(), "Test-Grid");
+        assert_eq!(crs.validate(1000.0, 2000.0), Ok(()));
+        assert_eq!(crs.validate(-1.0, 2000.0), Err(Error::OutOfRangeLatitude));
+        assert_eq!(crs.axes()[1].name(), "longitude");
+        assert_eq!(crs.ellipsoid(), None);
+        assert_eq!(crs.longitude_convention(), LongitudeConvention::East180);
+        assert_ne!(crs, CoordRefSystem::Wgs84);
+
+        let mut options = ParseOptions::new();
+        options.custom_crs(&CUSTOM_CRS);
+        let geo_uri = GeoUri::parse_with("geo:1000,2000;crs=test-grid;u=5", &options)?;
+        assert_eq!(geo_uri.crs(), crs);
+        assert_eq!(geo_uri.latitude(), 1000.0);
+        assert_eq!(geo_uri.to_string(), "geo:1000,2000;crs=Test-Grid;u=5");
+        assert_eq!(
+            GeoUri::parse_with("geo:1000,-2000;crs=test-grid", &options),
+            Err(Error::OutOfRangeLongitude)
+        );
+        assert_eq!(
+            GeoUri::parse("geo:1000,2000;crs=test-grid"),
+            Err(Error::InvalidCoordRefSystem)
+        );
+
+        // The longitude is ignored where the custom coordinate reference system says so.
+        let geo_uri = GeoUri::parse_with("geo:0,20;crs=test-grid", &options)?;
+        let geo_uri2 = GeoUri::parse_with("geo:0,3000;crs=TEST-GRID", &options)?;
+        assert_eq!(geo_uri, geo_uri2);
+        let geo_uri2 = GeoUri::parse("geo:0,20")?;
+        assert_ne!(geo_uri, geo_uri2);
+
+        let mut builder = GeoUri::builder();
+        builder
+            .crs(CoordRefSystem::Custom(&TEST_GRID))
+            .latitude(1.0)
+            .longitude(-1.0);
+        assert!(builder.build().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,587 @@
+//! Conversions to and from Earth-centred and local Cartesian coordinates.
+
+use crate::geodesic::{atan2d, sincosd};
+use crate::{CoordRefSystem, Crs, Ellipsoid, Error, GeoUri};
+
+/// Earth-centred, Earth-fixed (ECEF) Cartesian coordinates in meters.
+///
+/// The origin is the centre of the reference ellipsoid, the X axis points to the intersection
+/// of the equator and the prime meridian, the Y axis to longitude 90° east and the Z axis to the
+/// north pole.
+///
+/// Use [`GeoUri::to_ecef`] and [`GeoUri::from_ecef`] to convert geo URIs to and from them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ecef {
+    /// The X coordinate in meters.
+    x: f64,
+
+    /// The Y coordinate in meters.
+    y: f64,
+
+    /// The Z coordinate in meters.
+    z: f64,
+}
+
+impl Ecef {
+    /// Creates ECEF coordinates from the X, Y and Z coordinates in meters.
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Returns the X coordinate in meters.
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    /// Returns the Y coordinate in meters.
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    /// Returns the Z coordinate in meters.
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+}
+
+/// Local East-North-Up (ENU) Cartesian coordinates in meters relative to a reference location.
+///
+/// The axes point to the east, to the north and up along the normal of the reference ellipsoid
+/// at the reference location.
+///
+/// Use [`GeoUri::to_enu`] and [`GeoUri::from_enu`] to convert geo URIs to and from them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Enu {
+    /// The east coordinate in meters.
+    east: f64,
+
+    /// The north coordinate in meters.
+    north: f64,
+
+    /// The up coordinate in meters.
+    up: f64,
+}
+
+impl Enu {
+    /// Creates ENU coordinates from the east, north and up coordinates in meters.
+    pub const fn new(east: f64, north: f64, up: f64) -> Self {
+        Self { east, north, up }
+    }
+
+    /// Returns the east coordinate in meters.
+    pub fn east(&self) -> f64 {
+        self.east
+    }
+
+    /// Returns the north coordinate in meters.
+    pub fn north(&self) -> f64 {
+        self.north
+    }
+
+    /// Returns the up coordinate in meters.
+    pub fn up(&self) -> f64 {
+        self.up
+    }
+}
+
+impl From<Ned> for Enu {
+    fn from(ned: Ned) -> Self {
+        Self::new(ned.east, ned.north, -ned.down)
+    }
+}
+
+/// Local North-East-Down (NED) Cartesian coordinates in meters relative to a reference location.
+///
+/// The axes point to the north, to the east and down along the normal of the reference
+/// ellipsoid at the reference location. This is the convention commonly used in aviation.
+///
+/// Use [`GeoUri::to_ned`] and [`GeoUri::from_ned`] to convert geo URIs to and from them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ned {
+    /// The north coordinate in meters.
+    north: f64,
+
+    /// The east coordinate in meters.
+    east: f64,
+
+    /// The down coordinate in meters.
+    down: f64,
+}
+
+impl Ned {
+    /// Creates NED coordinates from the north, east and down coordinates in meters.
+    pub const fn new(north: f64, east: f64, down: f64) -> Self {
+        Self { north, east, down }
+    }
+
+    /// Returns the north coordinate in meters.
+    pub fn north(&self) -> f64 {
+        self.north
+    }
+
+    /// Returns the east coordinate in meters.
+    pub fn east(&self) -> f64 {
+        self.east
+    }
+
+    /// Returns the down coordinate in meters.
+    pub fn down(&self) -> f64 {
+        self.down
+    }
+}
+
+impl From<Enu> for Ned {
+    fn from(enu: Enu) -> Self {
+        Self::new(enu.north, enu.east, -enu.up)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl GeoUri {
+    /// Returns the Earth-centred, Earth-fixed coordinates of the location.
+    ///
+    /// The coordinates are relative to the reference ellipsoid of the coordinate reference
+    /// system, where the altitude is the height above the ellipsoid and a missing altitude is
+    /// taken to be zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:0,90,100")?;
+    /// let ecef = geo_uri.to_ecef()?;
+    /// assert_eq!(ecef.x().round(), 0.0);
+    /// assert_eq!(ecef.y(), 6_378_237.0);
+    /// assert_eq!(ecef.z(), 0.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid.
+    pub fn to_ecef(&self) -> Result<Ecef, Error> {
+        let ellipsoid = self.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+
+        Ok(self.ecef_on(&ellipsoid))
+    }
+
+    /// Try creating a geo URI from Earth-centred, Earth-fixed coordinates.
+    ///
+    /// The coordinates are relative to the reference ellipsoid of the coordinate reference
+    /// system, and the altitude of the geo URI is set to the height above the ellipsoid. At the
+    /// poles, the longitude is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{CoordRefSystem, Ecef, GeoUri};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let ecef = Ecef::new(0.0, 0.0, 6_356_852.314_245_179);
+    /// let geo_uri = GeoUri::from_ecef(ecef, CoordRefSystem::Wgs84)?;
+    /// assert_eq!(geo_uri.latitude(), 90.0);
+    /// assert_eq!(geo_uri.longitude(), 0.0);
+    /// assert_eq!(geo_uri.altitude().map(f64::round), Some(100.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid or if
+    /// the coordinates are not finite.
+    pub fn from_ecef(ecef: Ecef, crs: CoordRefSystem) -> Result<Self, Error> {
+        let ellipsoid = crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+        let (latitude, longitude, altitude) = geodetic(&ellipsoid, ecef);
+        let geo_uri = Self {
+            crs,
+            ..Self::default()
+        };
+
+        geo_uri.at_east_coords(latitude, longitude, Some(altitude))
+    }
+
+    /// Returns the local East-North-Up coordinates of the location relative to the location of
+    /// a reference geo URI.
+    ///
+    /// The coordinates are computed exactly via the Earth-centred, Earth-fixed coordinates (see
+    /// [`GeoUri::to_ecef`]), where a missing altitude is taken to be zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let reference = GeoUri::parse("geo:52.0907,5.1214,0")?;
+    /// let geo_uri = GeoUri::parse("geo:52.0907,5.1214,112.5")?; // The top of the Dom tower
+    /// let enu = geo_uri.to_enu(&reference)?;
+    /// assert_eq!(enu.east().round(), 0.0);
+    /// assert_eq!(enu.north().round(), 0.0);
+    /// assert!((enu.up() - 112.5).abs() < 1e-6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn to_enu(&self, reference: &Self) -> Result<Enu, Error> {
+        let ellipsoid = self.common_ellipsoid(reference)?;
+        let ecef = self.ecef_on(&ellipsoid);
+        let origin = reference.ecef_on(&ellipsoid);
+        let (dx, dy, dz) = (ecef.x - origin.x, ecef.y - origin.y, ecef.z - origin.z);
+        let (latitude, longitude) = reference.east_coords();
+        let (sin_phi, cos_phi) = sincosd(latitude);
+        let (sin_lambda, cos_lambda) = sincosd(longitude);
+        let t = cos_lambda * dx + sin_lambda * dy;
+
+        Ok(Enu {
+            east: -sin_lambda * dx + cos_lambda * dy,
+            north: -sin_phi * t + cos_phi * dz,
+            up: cos_phi * t + sin_phi * dz,
+        })
+    }
+
+    /// Try creating a geo URI from local East-North-Up coordinates relative to the location of
+    /// a reference geo URI.
+    ///
+    /// The geo URI has the coordinate reference system of the reference geo URI, and its
+    /// altitude is set to the height above the reference ellipsoid. See [`GeoUri::to_enu`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{Enu, GeoUri};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let reference = GeoUri::parse("geo:52.0907,5.1214")?;
+    /// let geo_uri = GeoUri::from_enu(Enu::new(0.0, 1000.0, 0.0), &reference)?;
+    /// assert_eq!((geo_uri.latitude() * 1e5).round(), 5_209_969.0);
+    /// assert_eq!((geo_uri.longitude() * 1e5).round(), 512_140.0);
+    /// assert_eq!(geo_uri.altitude().map(f64::round), Some(0.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid or if
+    /// the coordinates are not finite.
+    pub fn from_enu(enu: Enu, reference: &Self) -> Result<Self, Error> {
+        let ellipsoid = reference.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+        let origin = reference.ecef_on(&ellipsoid);
+        let (latitude, longitude) = reference.east_coords();
+        let (sin_phi, cos_phi) = sincosd(latitude);
+        let (sin_lambda, cos_lambda) = sincosd(longitude);
+        let t = -sin_phi * enu.north + cos_phi * enu.up;
+        let ecef = Ecef {
+            x: origin.x - sin_lambda * enu.east + cos_lambda * t,
+            y: origin.y + cos_lambda * enu.east + sin_lambda * t,
+            z: origin.z + cos_phi * enu.north + sin_phi * enu.up,
+        };
+
+        Self::from_ecef(ecef, reference.crs)
+    }
+
+    /// Returns the local North-East-Down coordinates of the location relative to the location
+    /// of a reference geo URI.
+    ///
+    /// These are the East-North-Up coordinates (see [`GeoUri::to_enu`]) with the axes
+    /// reordered and the vertical axis flipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let reference = GeoUri::parse("geo:52.0907,5.1214,112.5")?;
+    /// let geo_uri = GeoUri::parse("geo:52.0907,5.1214,0")?;
+    /// let ned = geo_uri.to_ned(&reference)?;
+    /// assert!((ned.down() - 112.5).abs() < 1e-6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn to_ned(&self, reference: &Self) -> Result<Ned, Error> {
+        self.to_enu(reference).map(Ned::from)
+    }
+
+    /// Try creating a geo URI from local North-East-Down coordinates relative to the location
+    /// of a reference geo URI.
+    ///
+    /// See [`GeoUri::from_enu`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid or if
+    /// the coordinates are not finite.
+    pub fn from_ned(ned: Ned, reference: &Self) -> Result<Self, Error> {
+        Self::from_enu(Enu::from(ned), reference)
+    }
+
+    /// Returns the Earth-centred, Earth-fixed coordinates of the location relative to a
+    /// reference ellipsoid.
+    fn ecef_on(&self, ellipsoid: &Ellipsoid) -> Ecef {
+        let (latitude, longitude) = self.east_coords();
+        let altitude = self.altitude.unwrap_or(0.0);
+        let a = ellipsoid.equatorial_radius();
+        let e2 = ellipsoid.flattening() * (2.0 - ellipsoid.flattening());
+        let (sin_phi, cos_phi) = sincosd(latitude);
+        let (sin_lambda, cos_lambda) = sincosd(longitude);
+        let n = a / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+
+        Ecef {
+            x: (n + altitude) * cos_phi * cos_lambda,
+            y: (n + altitude) * cos_phi * sin_lambda,
+            z: (n * (1.0 - e2) + altitude) * sin_phi,
+        }
+    }
+}
+
+/// Returns the latitude, longitude increasing eastwards and height above the reference ellipsoid
+/// of Earth-centred, Earth-fixed coordinates, in degrees and meters.
+///
+/// This uses the exact closed-form method of Vermeille (2011), which also works near the centre
+/// of the ellipsoid.
+fn geodetic(ellipsoid: &Ellipsoid, Ecef { x, y, z }: Ecef) -> (f64, f64, f64) {
+    let a = ellipsoid.equatorial_radius();
+    let e2 = ellipsoid.flattening() * (2.0 - ellipsoid.flattening());
+    let e4 = e2 * e2;
+    let rho = x.hypot(y);
+    let p = (rho / a).powi(2);
+    let q = (1.0 - e2) * (z / a).powi(2);
+    let r = (p + q - e4) / 6.0;
+    let evolute = 8.0 * r.powi(3) + e4 * p * q;
+    let longitude = atan2d(y, x);
+
+    if evolute <= 0.0 && q == 0.0 {
+        // On the singular disc in the equatorial plane inside the evolute, where the nearest
+        // locations on the ellipsoid are north and south of it.
+        let rad1 = (1.0 - e2).sqrt();
+        let rad2 = (e2 - p).sqrt();
+        let latitude = atan2d((e4 - p).sqrt(), (p * (1.0 - e2)).sqrt());
+        let height = -a * rad1 * rad2 / e2.sqrt();
+
+        return (latitude, longitude, height);
+    }
+
+    let u = if evolute > 0.0 {
+        // Outside the evolute, where the cube roots are computed so as to avoid cancellation.
+        let rad1 = evolute.sqrt();
+        let rad2 = (e4 * p * q).sqrt();
+        if evolute > 10.0 * e2 {
+            let rad3 = (rad1 + rad2).powi(2).cbrt();
+            r + 0.5 * rad3 + 2.0 * r * r / rad3
+        } else {
+            r + 0.5 * (rad1 + rad2).powi(2).cbrt() + 0.5 * (rad1 - rad2).powi(2).cbrt()
+        }
+    } else {
+        // Inside the evolute.
+        let rad1 = (-evolute).sqrt();
+        let rad2 = (-8.0 * r.powi(3)).sqrt();
+        let rad3 = (e4 * p * q).sqrt();
+        let angle = rad3.atan2(rad1 + rad2) * 2.0 / 3.0;
+        -4.0 * r * angle.sin() * (core::f64::consts::FRAC_PI_6 + angle).cos()
+    };
+    let v = (u * u + e4 * q).sqrt();
+    let w = e2 * (u + v - q) / (2.0 * v);
+    let k = (u + v) / ((w * w + u + v).sqrt() + w);
+    let d = k * rho / (k + e2);
+    let hypot = d.hypot(z);
+    let latitude = 2.0 * atan2d(z, hypot + d);
+    let height = (k + e2 - 1.0) * hypot / k;
+
+    (latitude, longitude, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{assert_near, GRID, WEST_EARTH};
+    use crate::GeoUriBuilderError;
+
+    #[test]
+    fn geo_uri_to_ecef() -> Result<(), Error> {
+        let ecef = GeoUri::parse("geo:0,0")?.to_ecef()?;
+        assert_eq!(ecef, Ecef::new(6_378_137.0, 0.0, 0.0));
+        let ecef = GeoUri::parse("geo:-90,45,-10")?.to_ecef()?;
+        assert_eq!(ecef, Ecef::new(0.0, 0.0, -6_356_742.314_245_179));
+        let ecef = GeoUri::parse("geo:0,180")?.to_ecef()?;
+        assert_eq!(ecef, Ecef::new(-6_378_137.0, 0.0, 0.0));
+
+        let ecef = GeoUri::parse("geo:52.0907,5.1214")?.to_ecef()?;
+        assert_near(ecef.x(), 3_911_325.521_143, 1e-6);
+        assert_near(ecef.y(), 350_549.070_222, 1e-6);
+        assert_near(ecef.z(), 5_009_010.320_594, 1e-6);
+
+        let ecef = GeoUri::parse("geo:0,90;crs=moon")?.to_ecef()?;
+        assert_eq!(ecef.y(), 1_737_400.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_from_ecef() -> Result<(), Error> {
+        for uri in [
+            "geo:0,0,0",
+            "geo:52.0907,5.1214,112.5",
+            "geo:-41.32,174.81,-1000",
+            "geo:89.9,-179.9,35786000",
+            "geo:-33.5,-70.6,-6000000",
+        ] {
+            let geo_uri = GeoUri::parse(uri)?;
+            let ecef = geo_uri.to_ecef()?;
+            let geo_uri2 = GeoUri::from_ecef(ecef, CoordRefSystem::Wgs84)?;
+            assert!(
+                geo_uri2.approx_eq(&geo_uri, 1e-6),
+                "{geo_uri2} != {geo_uri}"
+            );
+            let altitude = geo_uri.altitude().unwrap_or_default();
+            assert_near(geo_uri2.altitude().unwrap_or_default(), altitude, 1e-6);
+        }
+
+        // Near the centre, the location is not unique, but converts back to the same coordinates.
+        for ecef in [
+            Ecef::new(1000.0, 0.0, 0.0),
+            Ecef::new(0.0, -1000.0, 10.0),
+            Ecef::new(0.0, 0.0, 0.0),
+        ] {
+            let ecef2 = GeoUri::from_ecef(ecef, CoordRefSystem::Wgs84)?.to_ecef()?;
+            assert_near(ecef2.x(), ecef.x(), 1e-6);
+            assert_near(ecef2.y(), ecef.y(), 1e-6);
+            assert_near(ecef2.z(), ecef.z(), 1e-6);
+        }
+
+        let geo_uri = GeoUri::from_ecef(Ecef::new(0.0, 0.0, -7e6), CoordRefSystem::Wgs84)?;
+        assert_eq!(geo_uri.latitude(), -90.0);
+        assert_eq!(geo_uri.longitude(), 0.0);
+        let geo_uri = GeoUri::from_ecef(Ecef::new(-1e6, 0.0, 0.0), CoordRefSystem::Mars)?;
+        assert_eq!(geo_uri.latitude(), 0.0);
+        assert_eq!(geo_uri.longitude(), 180.0);
+        let geo_uri = GeoUri::from_ecef(Ecef::new(0.0, -1e6, 0.0), CoordRefSystem::Mars)?;
+        assert_eq!(geo_uri.longitude(), 270.0);
+
+        assert_eq!(
+            GeoUri::from_ecef(Ecef::new(f64::NAN, 0.0, 0.0), CoordRefSystem::Wgs84),
+            Err(Error::OutOfRangeLatitude)
+        );
+        assert_eq!(
+            GeoUri::from_ecef(Ecef::new(f64::INFINITY, 0.0, 0.0), CoordRefSystem::Wgs84),
+            Err(Error::OutOfRangeLatitude)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_ecef_planetocentric() -> Result<(), Error> {
+        // On the surface, the planetocentric latitude is the angle from the equatorial plane.
+        let ecef = GeoUri::parse("geo:45,0;crs=mars")?.to_ecef()?;
+        assert_near(ecef.z() / ecef.x(), 1.0, 1e-12);
+        let ecef = GeoUri::parse("geo:-30,90;crs=mercury")?.to_ecef()?;
+        assert_near(ecef.z() / ecef.y(), -(30.0_f64.to_radians().tan()), 1e-12);
+
+        for uri in ["geo:45,0;crs=mars", "geo:-71.5,123.4,2500;crs=mars"] {
+            let geo_uri = GeoUri::parse(uri)?;
+            let geo_uri2 = GeoUri::from_ecef(geo_uri.to_ecef()?, CoordRefSystem::Mars)?;
+            assert!(
+                geo_uri2.approx_eq(&geo_uri, 1e-6),
+                "{geo_uri2} != {geo_uri}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_ecef_custom_crs() -> Result<(), GeoUriBuilderError> {
+        let crs = CoordRefSystem::Custom(&WEST_EARTH);
+
+        let geo_uri = GeoUri::builder()
+            .crs(crs)
+            .latitude(0.0)
+            .longitude(90.0)
+            .build()?;
+        let ecef = geo_uri.to_ecef()?;
+        assert_near(ecef.x(), 0.0, 1e-9);
+        assert_eq!(ecef.y(), -6_378_137.0);
+        let geo_uri = GeoUri::from_ecef(ecef, crs)?;
+        assert_eq!(geo_uri.longitude(), 90.0);
+
+        let crs = CoordRefSystem::Custom(&GRID);
+        let geo_uri = GeoUri::builder()
+            .crs(crs)
+            .latitude(0.0)
+            .longitude(0.0)
+            .build()?;
+        assert_eq!(geo_uri.to_ecef(), Err(Error::MissingEllipsoid));
+        assert_eq!(
+            GeoUri::from_ecef(Ecef::new(0.0, 0.0, 0.0), crs),
+            Err(Error::MissingEllipsoid)
+        );
+        assert_eq!(
+            GeoUri::from_enu(Enu::new(0.0, 0.0, 0.0), &geo_uri),
+            Err(Error::MissingEllipsoid)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_enu_ned() -> Result<(), Error> {
+        let reference = GeoUri::parse("geo:52.0907,5.1214,10")?;
+        for (enu, uri) in [
+            (Enu::new(0.0, 0.0, 0.0), "geo:52.0907,5.1214,10"),
+            (Enu::new(0.0, 0.0, 102.5), "geo:52.0907,5.1214,112.5"),
+            (
+                Enu::new(1000.0, -500.0, 20.0),
+                "geo:52.086206,5.135989,30.098",
+            ),
+        ] {
+            let geo_uri = GeoUri::from_enu(enu, &reference)?;
+            let expected = GeoUri::parse(uri)?;
+            assert!(geo_uri.approx_eq(&expected, 0.1), "{geo_uri} != {expected}");
+
+            let enu2 = geo_uri.to_enu(&reference)?;
+            assert_near(enu2.east(), enu.east(), 1e-6);
+            assert_near(enu2.north(), enu.north(), 1e-6);
+            assert_near(enu2.up(), enu.up(), 1e-6);
+
+            let ned = geo_uri.to_ned(&reference)?;
+            assert_eq!(ned, Ned::from(enu2));
+            assert!(GeoUri::from_ned(ned, &reference)?.approx_eq(&geo_uri, 1e-6));
+        }
+
+        // At the poles, the axes are those at the prime meridian.
+        let pole = GeoUri::parse("geo:90,0")?;
+        let geo_uri = GeoUri::from_enu(Enu::new(0.0, -1000.0, 0.0), &pole)?;
+        assert_near(geo_uri.latitude(), 89.991, 1e-3);
+        assert_near(geo_uri.longitude(), 0.0, 1e-9);
+
+        let enu = GeoUri::parse("geo:52.0907,5.1214")?.to_enu(&GeoUri::parse("geo:52.09,5.12")?)?;
+        assert_eq!(Ned::from(enu), Ned::new(enu.north(), enu.east(), -enu.up()));
+        assert_eq!(Enu::from(Ned::from(enu)), enu);
+
+        assert_eq!(
+            GeoUri::parse("geo:0,0;crs=moon")?.to_enu(&GeoUri::parse("geo:0,0")?),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+//! Percent-encoding and -decoding of geo URI parameters.
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use crate::Error;
+
+/// Returns whether the byte is a `paramchar` that does not need to be percent-encoded.
+///
+/// These are the `p-unreserved` and `unreserved` characters of RFC 5870.
+pub(crate) fn is_paramchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"[]:&+$-._~".contains(&b)
+}
+
+/// Percent-decodes a parameter name or value.
+///
+/// Percent signs that are not followed by two hexadecimal digits are kept as is.
+///
+/// # Errors
+///
+/// Returns an error if the decoded parameter name or value is not valid UTF-8.
+#[cfg(feature = "alloc")]
+pub(crate) fn percent_decode(s: &str) -> Result<String, Error> {
+    // Avoid (re)allocating if there is nothing to decode.
+    if !s.contains('%') {
+        return Ok(s.to_owned());
+    }
+
+    String::from_utf8(percent_decode_bytes(s)).map_err(|_| Error::InvalidParam)
+}
+
+/// Percent-decodes a parameter name or value, replacing invalid UTF-8 sequences.
+///
+/// Invalid UTF-8 sequences in the decoded parameter name or value are replaced by
+/// `U+FFFD REPLACEMENT CHARACTER`.
+#[cfg(feature = "alloc")]
+pub(crate) fn percent_decode_lossy(s: &str) -> String {
+    // Avoid (re)allocating if there is nothing to decode.
+    if !s.contains('%') {
+        return s.to_owned();
+    }
+
+    String::from_utf8_lossy(&percent_decode_bytes(s)).into_owned()
+}
+
+/// Percent-decodes a parameter name or value into bytes.
+#[cfg(feature = "alloc")]
+fn percent_decode_bytes(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex_value = |i: usize| bytes.get(i).and_then(|b| (*b as char).to_digit(16));
+        match (bytes[index], hex_value(index + 1), hex_value(index + 2)) {
+            (b'%', Some(high), Some(low)) => {
+                // Two hexadecimal digits always fit in a byte.
+                decoded.push((high * 16 + low) as u8);
+                index += 3;
+            }
+            (b, _, _) => {
+                decoded.push(b);
+                index += 1;
+            }
+        }
+    }
+
+    decoded
+}
+
+/// Percent-decodes a query value where `+` encodes a space.
+///
+/// # Errors
+///
+/// Returns an error if the decoded query value is not valid UTF-8.
+#[cfg(feature = "alloc")]
+pub(crate) fn query_decode(s: &str) -> Result<String, Error> {
+    if s.contains('+') {
+        percent_decode(&s.replace('+', " "))
+    } else {
+        percent_decode(s)
+    }
+}
+
+/// Wrapper that percent-encodes a parameter name or value when displayed.
+///
+/// All bytes that are not a `paramchar` (see [`is_paramchar`]) are encoded.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PercentEncoded<'a>(pub(crate) &'a str);
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for PercentEncoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        percent_encode(f, self.0, is_paramchar)
+    }
+}
+
+/// Wrapper that percent-encodes a query value when displayed.
+///
+/// Contrary to [`PercentEncoded`], the query separator `&` and `+` (which encodes a space) are
+/// also encoded.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct QueryEncoded<'a>(pub(crate) &'a str);
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for QueryEncoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        percent_encode(f, self.0, |b| is_paramchar(b) && b != b'&' && b != b'+')
+    }
+}
+
+/// Writes the string percent-encoding all characters for which `keep` does not hold.
+#[cfg(feature = "alloc")]
+fn percent_encode(f: &mut fmt::Formatter<'_>, s: &str, keep: fn(u8) -> bool) -> fmt::Result {
+    for c in s.chars() {
+        if c.is_ascii() && keep(c as u8) {
+            write!(f, "{c}")?;
+        } else {
+            for b in c.encode_utf8(&mut [0; 4]).bytes() {
+                write!(f, "%{b:02X}")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decode() {
+        assert_eq!(super::percent_decode("foo"), Ok(String::from("foo")));
+        assert_eq!(
+            super::percent_decode("this%2dthat"),
+            Ok(String::from("this-that"))
+        );
+        assert_eq!(super::percent_decode("a%20b%3B"), Ok(String::from("a b;")));
+        assert_eq!(super::percent_decode("caf%C3%A9"), Ok(String::from("café")));
+        assert_eq!(super::percent_decode("100%"), Ok(String::from("100%")));
+        assert_eq!(super::percent_decode("%zz%4"), Ok(String::from("%zz%4")));
+        assert_eq!(super::percent_decode("%C3"), Err(Error::InvalidParam));
+    }
+
+    #[test]
+    fn percent_decode_lossy() {
+        assert_eq!(super::percent_decode_lossy("a%20b"), "a b");
+        assert_eq!(super::percent_decode_lossy("caf%C3%A9"), "café");
+        assert_eq!(super::percent_decode_lossy("%C3"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn query_decode() {
+        assert_eq!(super::query_decode("foo"), Ok(String::from("foo")));
+        assert_eq!(
+            super::query_decode("Dom+tower%2B1"),
+            Ok(String::from("Dom tower+1"))
+        );
+    }
+
+    #[test]
+    fn percent_encoded() {
+        assert_eq!(PercentEncoded("foo").to_string(), "foo");
+        assert_eq!(PercentEncoded("").to_string(), "");
+        assert_eq!(
+            PercentEncoded("a-b.c_d~e[f]:g&h+i$").to_string(),
+            "a-b.c_d~e[f]:g&h+i$"
+        );
+        assert_eq!(PercentEncoded("a b;c=d%").to_string(), "a%20b%3Bc%3Dd%25");
+        assert_eq!(PercentEncoded("café").to_string(), "caf%C3%A9");
+        assert_eq!(PercentEncoded("éé").to_string(), "%C3%A9%C3%A9");
+    }
+
+    #[test]
+    fn query_encoded() {
+        assert_eq!(QueryEncoded("foo").to_string(), "foo");
+        assert_eq!(QueryEncoded("a+b&c d").to_string(), "a%2Bb%26c%20d");
+        assert_eq!(QueryEncoded("café(1)").to_string(), "caf%C3%A9%281%29");
+    }
+}
@@ -0,0 +1,484 @@
+//! Configurable formatting of geo URIs.
+
+use core::fmt::{self, Write as _};
+
+#[cfg(feature = "alloc")]
+use crate::encoding::PercentEncoded;
+use crate::{CoordRefSystem, Crs, Error, GeoUri, URI_SCHEME_NAME};
+
+/// The number of decimal places a number is formatted with.
+///
+/// See [`FormatOptions`] for how to select the precision.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// The number of decimal places of the original textual representation.
+    ///
+    /// This is the number of decimal places the number had when it was parsed, or that was set
+    /// using the [`GeoUriBuilder`](crate::GeoUriBuilder). If it is unknown, the shortest
+    /// representation is used.
+    #[default]
+    Preserved,
+
+    /// The shortest representation that parses back to the same number.
+    ///
+    /// This never has trailing zeros.
+    Shortest,
+
+    /// Rounded to the given number of decimal places.
+    ///
+    /// Whether trailing zeros are kept depends on [`FormatOptions::trailing_zeros`]. If they
+    /// are, this is a fixed number of decimal places, otherwise a maximum number.
+    Decimals(u8),
+}
+
+/// Options for formatting geo URIs.
+///
+/// Use [`GeoUri::display_with`] to format a geo URI with these options.
+/// The default options format geo URIs the same as the [`Display`](fmt::Display) implementation
+/// of [`GeoUri`].
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{FormatOptions, GeoUri, Precision};
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let geo_uri = GeoUri::parse("geo:52.107,5.134,3.6")?;
+///
+/// let mut options = FormatOptions::new();
+/// options
+///     .coords_precision(Precision::Decimals(5))
+///     .altitude_precision(Precision::Decimals(0))
+///     .explicit_crs(true)
+///     .force_uncertainty(Some(10.0))?
+///     .uppercase_scheme(true);
+/// assert_eq!(
+///     geo_uri.display_with(&options).to_string(),
+///     "GEO:52.10700,5.13400,4;crs=wgs84;u=10"
+/// );
+///
+/// options.trailing_zeros(false);
+/// assert_eq!(
+///     geo_uri.display_with(&options).to_string(),
+///     "GEO:52.107,5.134,4;crs=wgs84;u=10"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FormatOptions {
+    /// The precision of the latitude and longitude coordinates.
+    coords_precision: Precision,
+
+    /// The precision of the altitude coordinate.
+    altitude_precision: Precision,
+
+    /// The precision of the uncertainty.
+    uncertainty_precision: Precision,
+
+    /// Whether to keep trailing zeros for a fixed number of decimal places.
+    trailing_zeros: bool,
+
+    /// Whether to write the `crs` parameter for the default coordinate reference system.
+    explicit_crs: bool,
+
+    /// The uncertainty to write in the `u` parameter if there is no uncertainty, if any.
+    force_uncertainty: Option<f64>,
+
+    /// Whether to write the scheme in uppercase.
+    uppercase_scheme: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            coords_precision: Precision::default(),
+            altitude_precision: Precision::default(),
+            uncertainty_precision: Precision::default(),
+            trailing_zeros: true,
+            explicit_crs: false,
+            force_uncertainty: None,
+            uppercase_scheme: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Returns the default format options.
+    ///
+    /// By default, numbers are formatted with their original number of decimal places if known,
+    /// or in their shortest representation otherwise, the `crs` parameter
+    /// is only written for other coordinate reference systems than WGS-84, the `u` parameter is
+    /// only written if there is an uncertainty and the scheme is written in lowercase.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The precision of the latitude and longitude coordinates.
+    pub fn coords_precision(&mut self, value: Precision) -> &mut Self {
+        self.coords_precision = value;
+
+        self
+    }
+
+    /// The precision of the altitude coordinate.
+    pub fn altitude_precision(&mut self, value: Precision) -> &mut Self {
+        self.altitude_precision = value;
+
+        self
+    }
+
+    /// The precision of the uncertainty.
+    pub fn uncertainty_precision(&mut self, value: Precision) -> &mut Self {
+        self.uncertainty_precision = value;
+
+        self
+    }
+
+    /// Whether to keep trailing zeros (and a trailing decimal point) when formatting with
+    /// [`Precision::Decimals`] or [`Precision::Preserved`].
+    ///
+    /// This is enabled by default.
+    pub fn trailing_zeros(&mut self, value: bool) -> &mut Self {
+        self.trailing_zeros = value;
+
+        self
+    }
+
+    /// Whether to write the `crs` parameter also for the default coordinate reference system,
+    /// i.e. `crs=wgs84`.
+    pub fn explicit_crs(&mut self, value: bool) -> &mut Self {
+        self.explicit_crs = value;
+
+        self
+    }
+
+    /// The uncertainty to write in the `u` parameter also if there is no uncertainty.
+    ///
+    /// Note that an absent uncertainty means that it is unknown, whereas an uncertainty of zero
+    /// means that the location is exact, so choose a value that reflects the actual uncertainty.
+    ///
+    /// # Errors
+    ///
+    /// If the uncertainty distance is not zero or positive, an error will be returned.
+    pub fn force_uncertainty(&mut self, value: Option<f64>) -> Result<&mut Self, Error> {
+        if let Some(unc) = value {
+            if unc.is_nan() || unc < 0.0 {
+                return Err(Error::OutOfRangeUncertainty);
+            }
+        }
+        self.force_uncertainty = value;
+
+        Ok(self)
+    }
+
+    /// Whether to write the scheme in uppercase, i.e. `GEO:`.
+    pub fn uppercase_scheme(&mut self, value: bool) -> &mut Self {
+        self.uppercase_scheme = value;
+
+        self
+    }
+
+    /// Writes a number with the precision, given its original number of decimal places.
+    fn write_num(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        num: f64,
+        precision: Precision,
+        preserved: Option<u8>,
+    ) -> fmt::Result {
+        let decimals = match precision {
+            Precision::Preserved => preserved,
+            Precision::Shortest => None,
+            Precision::Decimals(decimals) => Some(decimals),
+        };
+
+        match decimals {
+            None => write!(f, "{num}"),
+            Some(decimals) if self.trailing_zeros => write!(f, "{num:.*}", usize::from(decimals)),
+            Some(decimals) => write!(TrimmingWriter::new(f), "{num:.*}", usize::from(decimals)),
+        }
+    }
+}
+
+/// The number of decimal places of the components of a geo URI in their original textual
+/// representation, if known.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Decimals {
+    /// The number of decimal places of the latitude coordinate.
+    pub(crate) latitude: Option<u8>,
+
+    /// The number of decimal places of the longitude coordinate.
+    pub(crate) longitude: Option<u8>,
+
+    /// The number of decimal places of the altitude coordinate.
+    pub(crate) altitude: Option<u8>,
+
+    /// The number of decimal places of the uncertainty.
+    pub(crate) uncertainty: Option<u8>,
+}
+
+/// Wrapper that formats a geo URI using [`FormatOptions`].
+///
+/// Use [`GeoUri::display_with`] to construct it.
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayWith<'a> {
+    /// The geo URI to format.
+    geo_uri: &'a GeoUri,
+
+    /// The options to format with.
+    options: FormatOptions,
+}
+
+impl<'a> DisplayWith<'a> {
+    /// Creates a wrapper that formats the geo URI with the options.
+    pub(crate) fn new(geo_uri: &'a GeoUri, options: FormatOptions) -> Self {
+        Self { geo_uri, options }
+    }
+}
+
+impl fmt::Display for DisplayWith<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { geo_uri, options } = self;
+
+        if options.uppercase_scheme {
+            for c in URI_SCHEME_NAME.chars() {
+                write!(f, "{}", c.to_ascii_uppercase())?;
+            }
+        } else {
+            write!(f, "{URI_SCHEME_NAME}")?;
+        }
+        write!(f, ":")?;
+
+        let decimals = geo_uri.decimals;
+        options.write_num(
+            f,
+            geo_uri.latitude,
+            options.coords_precision,
+            decimals.latitude,
+        )?;
+        write!(f, ",")?;
+        options.write_num(
+            f,
+            geo_uri.longitude,
+            options.coords_precision,
+            decimals.longitude,
+        )?;
+        if let Some(altitude) = geo_uri.altitude {
+            write!(f, ",")?;
+            options.write_num(f, altitude, options.altitude_precision, decimals.altitude)?;
+        }
+
+        // Don't write the CRS if it is the default, unless asked to.
+        if options.explicit_crs || geo_uri.crs != CoordRefSystem::default() {
+            write!(f, ";crs={}", geo_uri.crs.name())?;
+        }
+
+        match geo_uri.uncertainty {
+            Some(uncertainty) => {
+                write!(f, ";u=")?;
+                options.write_num(
+                    f,
+                    uncertainty,
+                    options.uncertainty_precision,
+                    decimals.uncertainty,
+                )?;
+            }
+            None => {
+                if let Some(uncertainty) = options.force_uncertainty {
+                    write!(f, ";u=")?;
+                    options.write_num(f, uncertainty, options.uncertainty_precision, None)?;
+                }
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        for (name, value) in &geo_uri.params {
+            write!(f, ";{}", PercentEncoded(name))?;
+            if !value.is_empty() {
+                write!(f, "={}", PercentEncoded(value))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writer that removes trailing zeros after the decimal point of a formatted number.
+///
+/// The zeros are held back until a non-zero digit follows; the decimal point is held back until
+/// a digit follows. Whatever is still held back when done writing is dropped.
+struct TrimmingWriter<'a, 'f> {
+    /// The formatter to write to.
+    f: &'a mut fmt::Formatter<'f>,
+
+    /// Whether the decimal point has been encountered.
+    in_fraction: bool,
+
+    /// Whether the decimal point is held back.
+    pending_point: bool,
+
+    /// The number of zeros held back.
+    pending_zeros: usize,
+}
+
+impl<'a, 'f> TrimmingWriter<'a, 'f> {
+    /// Creates a writer that writes to the formatter.
+    fn new(f: &'a mut fmt::Formatter<'f>) -> Self {
+        Self {
+            f,
+            in_fraction: false,
+            pending_point: false,
+            pending_zeros: 0,
+        }
+    }
+}
+
+impl fmt::Write for TrimmingWriter<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '.' if !self.in_fraction => {
+                    self.in_fraction = true;
+                    self.pending_point = true;
+                }
+                '0' if self.in_fraction => self.pending_zeros += 1,
+                _ => {
+                    if self.pending_point {
+                        self.f.write_char('.')?;
+                        self.pending_point = false;
+                    }
+                    for _ in 0..self.pending_zeros {
+                        self.f.write_char('0')?;
+                    }
+                    self.pending_zeros = 0;
+                    self.f.write_char(c)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_options() {
+        let options = FormatOptions::default();
+        assert_eq!(options.coords_precision, Precision::Preserved);
+        assert!(options.trailing_zeros);
+        assert!(!options.explicit_crs);
+        assert_eq!(options.force_uncertainty, None);
+        assert!(!options.uppercase_scheme);
+
+        let mut options = FormatOptions::new();
+        options
+            .coords_precision(Precision::Decimals(3))
+            .uncertainty_precision(Precision::Decimals(1))
+            .trailing_zeros(false);
+        assert_eq!(options.coords_precision, Precision::Decimals(3));
+        assert_eq!(options.altitude_precision, Precision::Preserved);
+        assert_eq!(options.uncertainty_precision, Precision::Decimals(1));
+        assert!(!options.trailing_zeros);
+    }
+
+    #[test]
+    fn display_with() -> Result<(), Error> {
+        let geo_uri = GeoUri::parse("geo:52.107,-5.134,3.6;u=25.25")?;
+        assert_eq!(
+            geo_uri.display_with(&FormatOptions::default()).to_string(),
+            geo_uri.to_string()
+        );
+
+        let mut options = FormatOptions::new();
+        options.coords_precision(Precision::Decimals(2));
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "geo:52.11,-5.13,3.6;u=25.25"
+        );
+
+        options
+            .coords_precision(Precision::Decimals(6))
+            .altitude_precision(Precision::Decimals(3))
+            .uncertainty_precision(Precision::Decimals(0));
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "geo:52.107000,-5.134000,3.600;u=25"
+        );
+
+        options.trailing_zeros(false);
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "geo:52.107,-5.134,3.6;u=25"
+        );
+
+        let geo_uri = GeoUri::parse("geo:10,-0.5;crs=moon")?;
+        options.explicit_crs(true).uppercase_scheme(true);
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "GEO:10,-0.5;crs=moon"
+        );
+
+        let geo_uri = GeoUri::parse("geo:90,0")?;
+        options.force_uncertainty(Some(100.0))?;
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "GEO:90,0;crs=wgs84;u=100"
+        );
+        let geo_uri = GeoUri::parse("geo:90,0;u=5")?;
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "GEO:90,0;crs=wgs84;u=5"
+        );
+        for uncertainty in [-5.0, f64::NAN] {
+            assert!(matches!(
+                options.force_uncertainty(Some(uncertainty)),
+                Err(Error::OutOfRangeUncertainty)
+            ));
+        }
+        assert_eq!(options.force_uncertainty, Some(100.0));
+
+        let geo_uri = GeoUri::parse("geo:52.1070,-5.1340,3.60;u=10.0")?;
+        assert_eq!(
+            geo_uri.display_with(&FormatOptions::new()).to_string(),
+            "geo:52.1070,-5.1340,3.60;u=10.0"
+        );
+        let mut options = FormatOptions::new();
+        options.trailing_zeros(false);
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "geo:52.107,-5.134,3.6;u=10"
+        );
+        let mut options = FormatOptions::new();
+        options
+            .coords_precision(Precision::Shortest)
+            .altitude_precision(Precision::Shortest)
+            .uncertainty_precision(Precision::Shortest);
+        assert_eq!(
+            geo_uri.display_with(&options).to_string(),
+            "geo:52.107,-5.134,3.6;u=10"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn trimming_writer() {
+        struct Trimmed(&'static str);
+
+        impl fmt::Display for Trimmed {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Write::write_str(&mut TrimmingWriter::new(f), self.0)
+            }
+        }
+
+        assert_eq!(Trimmed("100").to_string(), "100");
+        assert_eq!(Trimmed("100.000").to_string(), "100");
+        assert_eq!(Trimmed("-0.050").to_string(), "-0.05");
+        assert_eq!(Trimmed("1.00100").to_string(), "1.001");
+    }
+}
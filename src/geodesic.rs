@@ -0,0 +1,1091 @@
+//! Solutions of the geodesic problems on an ellipsoid of revolution.
+//!
+//! This is a port of the algorithms described in C. F. F. Karney, "Algorithms for geodesics",
+//! J. Geodesy 87, 43–55 (2013), as implemented in GeographicLib, using series expansions up to
+//! the sixth order in the third flattening. These are accurate to round-off for the flattening
+//! of planetary ellipsoids, and, unlike Vincenty's method, converge for all pairs of points,
+//! including nearly antipodal ones.
+//!
+//! GeographicLib is copyright (c) 2008-2023 Charles Karney and licensed under the MIT license
+//! (see the `THIRD-PARTY-NOTICES` file).
+
+use core::f64::consts::PI;
+
+use crate::Ellipsoid;
+
+/// The order of the series expansions.
+const ORDER: usize = 6;
+
+/// The number of coefficients of the `C3` series.
+const NC3X: usize = ORDER * (ORDER - 1) / 2;
+
+/// The number of Newton iterations before falling back to bisection.
+const MAXIT1: u32 = 20;
+
+/// The total number of iterations of the inverse problem.
+const MAXIT2: u32 = MAXIT1 + f64::MANTISSA_DIGITS + 10;
+
+/// The square root of the smallest positive normal number (2^-511).
+const TINY: f64 = 1.491_668_146_240_041_3e-154;
+
+/// The machine epsilon.
+const TOL0: f64 = f64::EPSILON;
+
+/// The tolerance for the start of the inverse problem near the cut.
+const TOL1: f64 = 200.0 * TOL0;
+
+/// The square root of the machine epsilon (2^-26).
+const TOL2: f64 = 1.490_116_119_384_765_6e-8;
+
+/// The tolerance for the bisection of the inverse problem.
+const TOLB: f64 = TOL0 * TOL2;
+
+/// The threshold for the start of the inverse problem near the cut.
+const XTHRESH: f64 = 1000.0 * TOL2;
+
+/// A solution of the inverse geodesic problem.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Inverse {
+    /// The length of the geodesic in meters.
+    pub(crate) distance: f64,
+
+    /// The azimuth of the geodesic at the first point in degrees, in the range `-180.0..=180.0`.
+    pub(crate) initial_azimuth: f64,
+
+    /// The azimuth of the geodesic at the second point in degrees, in the range
+    /// `-180.0..=180.0`.
+    pub(crate) final_azimuth: f64,
+}
+
+/// The geodesic problems on an ellipsoid.
+#[derive(Clone, Debug)]
+pub(crate) struct Geodesic {
+    /// The equatorial radius.
+    a: f64,
+
+    /// The flattening.
+    f: f64,
+
+    /// One minus the flattening.
+    f1: f64,
+
+    /// The second eccentricity squared.
+    ep2: f64,
+
+    /// The third flattening.
+    n: f64,
+
+    /// The polar radius.
+    b: f64,
+
+    /// The tolerance for short lines in the inverse problem.
+    etol2: f64,
+
+    /// The coefficients of the `A3` series.
+    a3x: [f64; ORDER],
+
+    /// The coefficients of the `C3` series.
+    c3x: [f64; NC3X],
+}
+
+impl Geodesic {
+    /// Creates the geodesic problems for the ellipsoid.
+    pub(crate) fn new(ellipsoid: &Ellipsoid) -> Self {
+        let a = ellipsoid.equatorial_radius();
+        let f = ellipsoid.flattening();
+        let f1 = 1.0 - f;
+        let e2 = f * (2.0 - f);
+        let n = f / (2.0 - f);
+
+        let mut geodesic = Self {
+            a,
+            f,
+            f1,
+            ep2: e2 / (f1 * f1),
+            n,
+            b: a * f1,
+            etol2: 0.1 * TOL2 / ((f.abs().max(0.001) * (1.0 - f / 2.0).min(1.0)) / 2.0).sqrt(),
+            a3x: [0.0; ORDER],
+            c3x: [0.0; NC3X],
+        };
+        geodesic.a3_coeff();
+        geodesic.c3_coeff();
+
+        geodesic
+    }
+
+    /// Solves the inverse problem: the shortest geodesic between two points.
+    ///
+    /// The points are given as latitude and longitude in degrees, with east-positive longitudes.
+    pub(crate) fn inverse(&self, lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> Inverse {
+        // Compute the longitude difference accurately and make it positive.
+        let (lon12, lon12s) = ang_diff(lon1, lon2);
+        let mut lonsign = if lon12.is_sign_negative() { -1.0 } else { 1.0 };
+        let lon12 = lonsign * ang_round(lon12);
+        let lon12s = ang_round((180.0 - lon12) - lonsign * lon12s);
+        let lam12 = lon12.to_radians();
+        let (slam12, clam12) = if lon12 > 90.0 {
+            let (slam12, clam12) = sincosd(lon12s);
+            (slam12, -clam12)
+        } else {
+            sincosd(lon12)
+        };
+
+        // Swap the points such that the first one is furthest from the equator, and make its
+        // latitude non-positive.
+        let mut lat1 = ang_round(lat1);
+        let mut lat2 = ang_round(lat2);
+        let swapp = if lat1.abs() < lat2.abs() { -1.0 } else { 1.0 };
+        if swapp < 0.0 {
+            lonsign = -lonsign;
+            core::mem::swap(&mut lat1, &mut lat2);
+        }
+        let latsign = if lat1.is_sign_negative() { 1.0 } else { -1.0 };
+        lat1 *= latsign;
+        lat2 *= latsign;
+
+        let (sbet1, cbet1) = sincosd(lat1);
+        let (sbet1, cbet1) = norm(self.f1 * sbet1, cbet1);
+        let cbet1 = cbet1.max(TINY);
+        let (sbet2, cbet2) = sincosd(lat2);
+        let (mut sbet2, cbet2) = norm(self.f1 * sbet2, cbet2);
+        let mut cbet2 = cbet2.max(TINY);
+
+        // Make sure that symmetric latitudes are treated exactly symmetrically.
+        if cbet1 < -sbet1 {
+            if cbet2 == cbet1 {
+                sbet2 = sbet1.copysign(sbet2);
+            }
+        } else if sbet2.abs() == -sbet1 {
+            cbet2 = cbet1;
+        }
+
+        let dn1 = (1.0 + self.ep2 * sbet1 * sbet1).sqrt();
+        let dn2 = (1.0 + self.ep2 * sbet2 * sbet2).sqrt();
+        let points = Points {
+            sbet1,
+            cbet1,
+            dn1,
+            sbet2,
+            cbet2,
+            dn2,
+        };
+
+        let mut distance = f64::NAN;
+        let (mut salp1, mut calp1, mut salp2, mut calp2) = (0.0, 0.0, 0.0, 0.0);
+
+        // A meridional geodesic, or one that passes through a pole.
+        let mut meridian = lat1 == -90.0 || slam12 == 0.0;
+        if meridian {
+            (salp1, calp1) = (slam12, clam12);
+            (salp2, calp2) = (0.0, 1.0);
+
+            let (ssig1, csig1) = (sbet1, calp1 * cbet1);
+            let (ssig2, csig2) = (sbet2, calp2 * cbet2);
+            let sig12 = (csig1 * ssig2 - ssig1 * csig2)
+                .max(0.0)
+                .atan2(csig1 * csig2 + ssig1 * ssig2);
+            let (s12x, m12x) =
+                self.lengths(self.n, sig12, [ssig1, csig1, ssig2, csig2], &points, true);
+
+            // The meridian is the shortest path if its reduced length is positive, otherwise it
+            // passes a conjugate point.
+            if sig12 < 1.0 || m12x >= 0.0 {
+                distance = if sig12 < 3.0 * TINY || (sig12 < TOL0 && (s12x < 0.0 || m12x < 0.0)) {
+                    0.0
+                } else {
+                    s12x * self.b
+                };
+            } else {
+                meridian = false;
+            }
+        }
+
+        if !meridian && sbet1 == 0.0 && (self.f <= 0.0 || lon12s >= self.f * 180.0) {
+            // A geodesic along the equator.
+            (salp1, calp1, salp2, calp2) = (1.0, 0.0, 1.0, 0.0);
+            distance = self.a * lam12;
+        } else if !meridian {
+            let start = self.inverse_start(&points, lam12, slam12, clam12);
+            (salp1, calp1) = (start.salp1, start.calp1);
+            if let Some((sig12, dnm)) = start.short_line {
+                // A short line, solved on the auxiliary sphere.
+                (salp2, calp2) = (start.salp2, start.calp2);
+                distance = sig12 * self.b * dnm;
+            } else {
+                // Find the azimuth at the first point using Newton's method, falling back to
+                // bisection.
+                let mut numit = 0;
+                let (mut tripn, mut tripb) = (false, false);
+                let (mut salp1a, mut calp1a) = (TINY, 1.0);
+                let (mut salp1b, mut calp1b) = (TINY, -1.0);
+                let mut lambda;
+                loop {
+                    lambda = self.lambda12(&points, salp1, calp1, slam12, clam12, numit < MAXIT1);
+                    let v = lambda.lam12;
+                    if numit >= MAXIT2 || tripb || v.abs() < if tripn { 8.0 } else { 1.0 } * TOL0 {
+                        break;
+                    }
+
+                    // Update the bracket of the solution.
+                    if v > 0.0 && (numit < MAXIT1 || calp1 / salp1 > calp1b / salp1b) {
+                        (salp1b, calp1b) = (salp1, calp1);
+                    } else if v < 0.0 && (numit < MAXIT1 || calp1 / salp1 < calp1a / salp1a) {
+                        (salp1a, calp1a) = (salp1, calp1);
+                    }
+                    numit += 1;
+
+                    if numit < MAXIT1 && lambda.dlam12 > 0.0 {
+                        let dalp1 = -v / lambda.dlam12;
+                        if dalp1.abs() < PI {
+                            let (sdalp1, cdalp1) = dalp1.sin_cos();
+                            let nsalp1 = salp1 * cdalp1 + calp1 * sdalp1;
+                            if nsalp1 > 0.0 {
+                                (salp1, calp1) = norm(nsalp1, calp1 * cdalp1 - salp1 * sdalp1);
+                                tripn = v.abs() <= 16.0 * TOL0;
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Bisect if Newton's method fails or goes out of the bracket.
+                    (salp1, calp1) = norm((salp1a + salp1b) / 2.0, (calp1a + calp1b) / 2.0);
+                    tripn = false;
+                    tripb = (salp1a - salp1).abs() + (calp1a - calp1) < TOLB
+                        || (salp1 - salp1b).abs() + (calp1 - calp1b) < TOLB;
+                }
+
+                (salp2, calp2) = (lambda.salp2, lambda.calp2);
+                let (s12x, _) =
+                    self.lengths(lambda.eps, lambda.sig12, lambda.sigmas, &points, false);
+                distance = s12x * self.b;
+            }
+        }
+
+        // Undo the swapping and sign changes.
+        if swapp < 0.0 {
+            core::mem::swap(&mut salp1, &mut salp2);
+            core::mem::swap(&mut calp1, &mut calp2);
+        }
+        salp1 *= swapp * lonsign;
+        calp1 *= swapp * latsign;
+        salp2 *= swapp * lonsign;
+        calp2 *= swapp * latsign;
+
+        Inverse {
+            distance: 0.0 + distance,
+            initial_azimuth: atan2d(salp1, calp1),
+            final_azimuth: atan2d(salp2, calp2),
+        }
+    }
+
+    /// Solves the direct problem: the point reached by following the geodesic from a point with
+    /// an azimuth for a distance.
+    ///
+    /// The points are given as latitude and longitude in degrees, with east-positive longitudes,
+    /// and the azimuth is in degrees clockwise from the north. The distance may be negative to
+    /// follow the geodesic backwards. Returns the latitude, longitude and azimuth at the point
+    /// reached, where the longitude is in the range `-180.0..=180.0`.
+    pub(crate) fn direct(&self, lat1: f64, lon1: f64, azi1: f64, distance: f64) -> (f64, f64, f64) {
+        let (salp1, calp1) = sincosd(ang_round(azi1));
+        let (sbet1, cbet1) = sincosd(ang_round(lat1));
+        let (sbet1, cbet1) = norm(self.f1 * sbet1, cbet1);
+        let cbet1 = cbet1.max(TINY);
+
+        // The azimuth at the equator crossing, and the arc length and longitude of the first
+        // point from there on the auxiliary sphere.
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+        let somg1 = salp0 * sbet1;
+        let comg1 = if sbet1 != 0.0 || calp1 != 0.0 {
+            cbet1 * calp1
+        } else {
+            1.0
+        };
+        let (ssig1, csig1) = norm(sbet1, comg1);
+
+        let k2 = calp0 * calp0 * self.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+
+        // Convert the distance to an arc length on the auxiliary sphere.
+        let a1 = 1.0 + a1m1f(eps);
+        let c1a = c1f(eps);
+        let b11 = sin_series(ssig1, csig1, &c1a);
+        let (sb11, cb11) = b11.sin_cos();
+        let (stau1, ctau1) = (ssig1 * cb11 + csig1 * sb11, csig1 * cb11 - ssig1 * sb11);
+        let tau12 = distance / (self.b * a1);
+        let (stau12, ctau12) = tau12.sin_cos();
+        let b12 = -sin_series(
+            stau1 * ctau12 + ctau1 * stau12,
+            ctau1 * ctau12 - stau1 * stau12,
+            &c1pf(eps),
+        );
+        let mut sig12 = tau12 - (b12 - b11);
+        let (mut ssig12, mut csig12) = sig12.sin_cos();
+        if self.f.abs() > 0.01 {
+            // The reverted series is not accurate enough for large flattenings, so refine the
+            // arc length with a Newton step.
+            let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+            let csig2 = csig1 * csig12 - ssig1 * ssig12;
+            let b12 = sin_series(ssig2, csig2, &c1a);
+            let serr = a1 * (sig12 + (b12 - b11)) - distance / self.b;
+            sig12 -= serr / (1.0 + k2 * ssig2 * ssig2).sqrt();
+            (ssig12, csig12) = sig12.sin_cos();
+        }
+
+        let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+        let mut csig2 = csig1 * csig12 - ssig1 * ssig12;
+        let sbet2 = calp0 * ssig2;
+        let mut cbet2 = salp0.hypot(calp0 * csig2);
+        if cbet2 == 0.0 {
+            // The second point is at a pole.
+            (cbet2, csig2) = (TINY, TINY);
+        }
+
+        let somg2 = salp0 * ssig2;
+        let comg2 = csig2;
+        let omg12 = (somg2 * comg1 - comg2 * somg1).atan2(comg2 * comg1 + somg2 * somg1);
+        let c3a = self.c3f(eps);
+        let b312 = sin_series(ssig2, csig2, &c3a) - sin_series(ssig1, csig1, &c3a);
+        let lam12 = omg12 - self.f * self.a3f(eps) * salp0 * (sig12 + b312);
+
+        (
+            atan2d(sbet2, self.f1 * cbet2),
+            remainder(remainder(lon1) + remainder(lam12.to_degrees())),
+            atan2d(salp0, calp0 * csig2),
+        )
+    }
+
+    /// Computes the coefficients of the `A3` series.
+    fn a3_coeff(&mut self) {
+        const COEFF: [f64; 18] = [
+            -3.0, 128.0, //
+            -2.0, -3.0, 64.0, //
+            -1.0, -3.0, -1.0, 16.0, //
+            3.0, -1.0, -2.0, 8.0, //
+            1.0, -1.0, 2.0, //
+            1.0, 1.0,
+        ];
+
+        let mut o = 0;
+        for (k, j) in (0..ORDER).rev().enumerate() {
+            let m = (ORDER - j - 1).min(j);
+            self.a3x[k] = polyval(&COEFF[o..=o + m], self.n) / COEFF[o + m + 1];
+            o += m + 2;
+        }
+    }
+
+    /// Computes the coefficients of the `C3` series.
+    fn c3_coeff(&mut self) {
+        const COEFF: [f64; 45] = [
+            3.0, 128.0, //
+            2.0, 5.0, 128.0, //
+            -1.0, 3.0, 3.0, 64.0, //
+            -1.0, 0.0, 1.0, 8.0, //
+            -1.0, 1.0, 4.0, //
+            5.0, 256.0, //
+            1.0, 3.0, 128.0, //
+            -3.0, -2.0, 3.0, 64.0, //
+            1.0, -3.0, 2.0, 32.0, //
+            7.0, 512.0, //
+            -10.0, 9.0, 384.0, //
+            5.0, -9.0, 5.0, 192.0, //
+            7.0, 512.0, //
+            -14.0, 7.0, 512.0, //
+            21.0, 2560.0,
+        ];
+
+        let (mut o, mut k) = (0, 0);
+        for l in 1..ORDER {
+            for j in (l..ORDER).rev() {
+                let m = (ORDER - j - 1).min(j);
+                self.c3x[k] = polyval(&COEFF[o..=o + m], self.n) / COEFF[o + m + 1];
+                k += 1;
+                o += m + 2;
+            }
+        }
+    }
+
+    /// Evaluates the `A3` series.
+    fn a3f(&self, eps: f64) -> f64 {
+        polyval(&self.a3x, eps)
+    }
+
+    /// Evaluates the coefficients of the `C3` series.
+    fn c3f(&self, eps: f64) -> [f64; ORDER] {
+        let mut c = [0.0; ORDER];
+        let (mut mult, mut o) = (1.0, 0);
+        for (l, c) in c.iter_mut().enumerate().skip(1) {
+            let m = ORDER - l - 1;
+            mult *= eps;
+            *c = mult * polyval(&self.c3x[o..=o + m], eps);
+            o += m + 1;
+        }
+
+        c
+    }
+
+    /// Computes the distance and reduced length of a geodesic on the auxiliary sphere.
+    ///
+    /// The sines and cosines of the arc lengths of the points from the equator crossing are
+    /// given in the order `[ssig1, csig1, ssig2, csig2]`. The reduced length is only computed
+    /// if requested, and both are in units of the polar radius.
+    fn lengths(
+        &self,
+        eps: f64,
+        sig12: f64,
+        [ssig1, csig1, ssig2, csig2]: [f64; 4],
+        points: &Points,
+        reduced_length: bool,
+    ) -> (f64, f64) {
+        let c1a = c1f(eps);
+        let a1 = 1.0 + a1m1f(eps);
+        let b1 = sin_series(ssig2, csig2, &c1a) - sin_series(ssig1, csig1, &c1a);
+        let s12b = a1 * (sig12 + b1);
+        if !reduced_length {
+            return (s12b, f64::NAN);
+        }
+
+        let c2a = c2f(eps);
+        let a2 = 1.0 + a2m1f(eps);
+        let b2 = sin_series(ssig2, csig2, &c2a) - sin_series(ssig1, csig1, &c2a);
+        let j12 = (a1 - a2) * sig12 + (a1 * b1 - a2 * b2);
+        let m12b =
+            points.dn2 * (csig1 * ssig2) - points.dn1 * (ssig1 * csig2) - csig1 * csig2 * j12;
+
+        (s12b, m12b)
+    }
+
+    /// Computes the reduced length of a geodesic on the auxiliary sphere, and its value `m0`
+    /// for an infinitesimally short geodesic.
+    fn reduced_length(
+        &self,
+        eps: f64,
+        sig12: f64,
+        [ssig1, csig1, ssig2, csig2]: [f64; 4],
+        points: &Points,
+    ) -> (f64, f64) {
+        let a1 = a1m1f(eps);
+        let a2 = a2m1f(eps);
+        let m0 = a1 - a2;
+        let (a1, a2) = (1.0 + a1, 1.0 + a2);
+        let c1a = c1f(eps);
+        let mut c2a = c2f(eps);
+        for l in 1..=ORDER {
+            c2a[l] = a1 * c1a[l] - a2 * c2a[l];
+        }
+        let j12 = m0 * sig12 + (sin_series(ssig2, csig2, &c2a) - sin_series(ssig1, csig1, &c2a));
+        let m12b =
+            points.dn2 * (csig1 * ssig2) - points.dn1 * (ssig1 * csig2) - csig1 * csig2 * j12;
+
+        (m12b, m0)
+    }
+
+    /// Computes a starting point for the Newton iterations of the inverse problem.
+    ///
+    /// For short lines, the solution on the auxiliary sphere is accurate enough, and it is
+    /// returned including the azimuth at the second point.
+    fn inverse_start(&self, points: &Points, lam12: f64, slam12: f64, clam12: f64) -> Start {
+        let Points {
+            sbet1,
+            cbet1,
+            sbet2,
+            cbet2,
+            ..
+        } = *points;
+        let mut start = Start {
+            salp1: 0.0,
+            calp1: 0.0,
+            salp2: f64::NAN,
+            calp2: f64::NAN,
+            short_line: None,
+        };
+
+        let sbet12 = sbet2 * cbet1 - cbet2 * sbet1;
+        let cbet12 = cbet2 * cbet1 + sbet2 * sbet1;
+        let sbet12a = sbet2 * cbet1 + cbet2 * sbet1;
+
+        let shortline = cbet12 >= 0.0 && sbet12 < 0.5 && cbet2 * lam12 < 0.5;
+        let mut dnm = f64::NAN;
+        let (mut somg12, mut comg12) = if shortline {
+            let sbetm2 = (sbet1 + sbet2).powi(2);
+            let sbetm2 = sbetm2 / (sbetm2 + (cbet1 + cbet2).powi(2));
+            dnm = (1.0 + self.ep2 * sbetm2).sqrt();
+            (lam12 / (self.f1 * dnm)).sin_cos()
+        } else {
+            (slam12, clam12)
+        };
+
+        start.salp1 = cbet2 * somg12;
+        start.calp1 = if comg12 >= 0.0 {
+            sbet12 + cbet2 * sbet1 * somg12.powi(2) / (1.0 + comg12)
+        } else {
+            sbet12a - cbet2 * sbet1 * somg12.powi(2) / (1.0 - comg12)
+        };
+
+        let ssig12 = start.salp1.hypot(start.calp1);
+        let csig12 = sbet1 * sbet2 + cbet1 * cbet2 * comg12;
+
+        if shortline && ssig12 < self.etol2 {
+            // A really short line.
+            let salp2 = cbet1 * somg12;
+            let calp2 = sbet12
+                - cbet1
+                    * sbet2
+                    * if comg12 >= 0.0 {
+                        somg12.powi(2) / (1.0 + comg12)
+                    } else {
+                        1.0 - comg12
+                    };
+            (start.salp2, start.calp2) = norm(salp2, calp2);
+            start.short_line = Some((ssig12.atan2(csig12), dnm));
+        } else if self.n.abs() >= 0.1
+            || csig12 >= 0.0
+            || ssig12 >= 6.0 * self.n.abs() * PI * cbet1.powi(2)
+        {
+            // Nothing to do, the zeroth order spherical approximation is good enough.
+        } else {
+            // Nearly antipodal points, scale the problem to the astroid.
+            let lam12x = (-slam12).atan2(-clam12);
+            let (x, y, lamscale);
+            if self.f >= 0.0 {
+                let k2 = sbet1.powi(2) * self.ep2;
+                let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+                lamscale = self.f * cbet1 * self.a3f(eps) * PI;
+                let betscale = lamscale * cbet1;
+                x = lam12x / lamscale;
+                y = sbet12a / betscale;
+            } else {
+                let cbet12a = cbet2 * cbet1 - sbet2 * sbet1;
+                let bet12a = sbet12a.atan2(cbet12a);
+                let (m12b, m0) =
+                    self.reduced_length(self.n, PI + bet12a, [sbet1, -cbet1, sbet2, cbet2], points);
+                x = -1.0 + m12b / (cbet1 * cbet2 * m0 * PI);
+                let betscale = if x < -0.01 {
+                    sbet12a / x
+                } else {
+                    -self.f * cbet1.powi(2) * PI
+                };
+                lamscale = betscale / cbet1;
+                y = lam12x / lamscale;
+            }
+
+            if y > -TOL1 && x > -1.0 - XTHRESH {
+                // Strip near the cut.
+                if self.f >= 0.0 {
+                    start.salp1 = (-x).min(1.0);
+                    start.calp1 = -(1.0 - start.salp1.powi(2)).sqrt();
+                } else {
+                    start.calp1 = x.max(if x > -TOL1 { 0.0 } else { -1.0 });
+                    start.salp1 = (1.0 - start.calp1.powi(2)).sqrt();
+                }
+            } else {
+                let k = astroid(x, y);
+                let omg12a = lamscale
+                    * if self.f >= 0.0 {
+                        -x * k / (1.0 + k)
+                    } else {
+                        -y * (1.0 + k) / k
+                    };
+                (somg12, comg12) = omg12a.sin_cos();
+                comg12 = -comg12;
+                start.salp1 = cbet2 * somg12;
+                start.calp1 = sbet12a - cbet2 * sbet1 * somg12.powi(2) / (1.0 - comg12);
+            }
+        }
+
+        if start.salp1 > 0.0 {
+            (start.salp1, start.calp1) = norm(start.salp1, start.calp1);
+        } else {
+            (start.salp1, start.calp1) = (1.0, 0.0);
+        }
+
+        start
+    }
+
+    /// Computes the longitude difference for a given azimuth at the first point, and the
+    /// derivative with respect to the azimuth if requested.
+    ///
+    /// The returned longitude difference is relative to the one that is sought.
+    fn lambda12(
+        &self,
+        points: &Points,
+        salp1: f64,
+        calp1: f64,
+        slam120: f64,
+        clam120: f64,
+        diffp: bool,
+    ) -> Lambda {
+        let Points {
+            sbet1,
+            cbet1,
+            dn1,
+            sbet2,
+            cbet2,
+            ..
+        } = *points;
+        let calp1 = if sbet1 == 0.0 && calp1 == 0.0 {
+            // Break the degeneracy of the equatorial line.
+            -TINY
+        } else {
+            calp1
+        };
+
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+
+        let somg1 = salp0 * sbet1;
+        let comg1 = calp1 * cbet1;
+        let (ssig1, csig1) = norm(sbet1, comg1);
+
+        let salp2 = if cbet2 == cbet1 { salp1 } else { salp0 / cbet2 };
+        let calp2 = if cbet2 != cbet1 || sbet2.abs() != -sbet1 {
+            ((calp1 * cbet1).powi(2)
+                + if cbet1 < -sbet1 {
+                    (cbet2 - cbet1) * (cbet1 + cbet2)
+                } else {
+                    (sbet1 - sbet2) * (sbet1 + sbet2)
+                })
+            .sqrt()
+                / cbet2
+        } else {
+            calp1.abs()
+        };
+
+        let somg2 = salp0 * sbet2;
+        let comg2 = calp2 * cbet2;
+        let (ssig2, csig2) = norm(sbet2, comg2);
+
+        let sig12 = (csig1 * ssig2 - ssig1 * csig2)
+            .max(0.0)
+            .atan2(csig1 * csig2 + ssig1 * ssig2);
+        let somg12 = (comg1 * somg2 - somg1 * comg2).max(0.0);
+        let comg12 = comg1 * comg2 + somg1 * somg2;
+        let eta = (somg12 * clam120 - comg12 * slam120).atan2(comg12 * clam120 + somg12 * slam120);
+
+        let k2 = calp0.powi(2) * self.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+        let c3a = self.c3f(eps);
+        let b312 = sin_series(ssig2, csig2, &c3a) - sin_series(ssig1, csig1, &c3a);
+        let domg12 = -self.f * self.a3f(eps) * salp0 * (sig12 + b312);
+        let lam12 = eta + domg12;
+
+        let sigmas = [ssig1, csig1, ssig2, csig2];
+        let dlam12 = if !diffp {
+            f64::NAN
+        } else if calp2 == 0.0 {
+            -2.0 * self.f1 * dn1 / sbet1
+        } else {
+            let (m12b, _) = self.reduced_length(eps, sig12, sigmas, points);
+            m12b * self.f1 / (calp2 * cbet2)
+        };
+
+        Lambda {
+            lam12,
+            salp2,
+            calp2,
+            sig12,
+            sigmas,
+            eps,
+            dlam12,
+        }
+    }
+}
+
+/// The reduced latitudes of the two points of the inverse problem.
+#[derive(Copy, Clone, Debug)]
+struct Points {
+    /// The sine of the reduced latitude of the first point.
+    sbet1: f64,
+
+    /// The cosine of the reduced latitude of the first point.
+    cbet1: f64,
+
+    /// The ratio of the geodesic and spherical distance at the first point.
+    dn1: f64,
+
+    /// The sine of the reduced latitude of the second point.
+    sbet2: f64,
+
+    /// The cosine of the reduced latitude of the second point.
+    cbet2: f64,
+
+    /// The ratio of the geodesic and spherical distance at the second point.
+    dn2: f64,
+}
+
+/// The starting point of the inverse problem.
+#[derive(Copy, Clone, Debug)]
+struct Start {
+    /// The sine of the azimuth at the first point.
+    salp1: f64,
+
+    /// The cosine of the azimuth at the first point.
+    calp1: f64,
+
+    /// The sine of the azimuth at the second point, for short lines.
+    salp2: f64,
+
+    /// The cosine of the azimuth at the second point, for short lines.
+    calp2: f64,
+
+    /// The arc length on the auxiliary sphere and the ratio of the geodesic and spherical
+    /// distance at the midpoint, for short lines.
+    short_line: Option<(f64, f64)>,
+}
+
+/// The longitude difference for an azimuth at the first point of the inverse problem.
+#[derive(Copy, Clone, Debug)]
+struct Lambda {
+    /// The longitude difference relative to the one that is sought.
+    lam12: f64,
+
+    /// The sine of the azimuth at the second point.
+    salp2: f64,
+
+    /// The cosine of the azimuth at the second point.
+    calp2: f64,
+
+    /// The arc length on the auxiliary sphere.
+    sig12: f64,
+
+    /// The sines and cosines of the arc lengths of the points from the equator crossing.
+    sigmas: [f64; 4],
+
+    /// The expansion parameter.
+    eps: f64,
+
+    /// The derivative of the longitude difference with respect to the azimuth.
+    dlam12: f64,
+}
+
+/// Evaluates the `A1` series minus one.
+pub(crate) fn a1m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [1.0, 4.0, 64.0, 0.0, 256.0];
+
+    let t = polyval(&COEFF[..4], eps * eps) / COEFF[4];
+    (t + eps) / (1.0 - eps)
+}
+
+/// Evaluates the coefficients of the `C1` series.
+pub(crate) fn c1f(eps: f64) -> [f64; ORDER + 1] {
+    const COEFF: [f64; 18] = [
+        -1.0, 6.0, -16.0, 32.0, //
+        -9.0, 64.0, -128.0, 2048.0, //
+        9.0, -16.0, 768.0, //
+        3.0, -5.0, 512.0, //
+        -7.0, 1280.0, //
+        -7.0, 2048.0,
+    ];
+
+    series_coeff(&COEFF, eps)
+}
+
+/// Evaluates the coefficients of the reverted `C1` series, which converts distances to arc
+/// lengths.
+pub(crate) fn c1pf(eps: f64) -> [f64; ORDER + 1] {
+    const COEFF: [f64; 18] = [
+        205.0, -432.0, 768.0, 1536.0, //
+        4005.0, -4736.0, 3840.0, 12288.0, //
+        -225.0, 116.0, 384.0, //
+        -7173.0, 2695.0, 7680.0, //
+        3467.0, 7680.0, //
+        38081.0, 61440.0,
+    ];
+
+    series_coeff(&COEFF, eps)
+}
+
+/// Evaluates the `A2` series minus one.
+fn a2m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [-11.0, -28.0, -192.0, 0.0, 256.0];
+
+    let t = polyval(&COEFF[..4], eps * eps) / COEFF[4];
+    (t - eps) / (1.0 + eps)
+}
+
+/// Evaluates the coefficients of the `C2` series.
+fn c2f(eps: f64) -> [f64; ORDER + 1] {
+    const COEFF: [f64; 18] = [
+        1.0, 2.0, 16.0, 32.0, //
+        35.0, 64.0, 384.0, 2048.0, //
+        15.0, 80.0, 768.0, //
+        7.0, 35.0, 512.0, //
+        63.0, 1280.0, //
+        77.0, 2048.0,
+    ];
+
+    series_coeff(&COEFF, eps)
+}
+
+/// Evaluates the coefficients of a series in `eps` whose `l`-th coefficient is `eps^l` times a
+/// polynomial in `eps^2`.
+fn series_coeff(coeff: &[f64], eps: f64) -> [f64; ORDER + 1] {
+    let mut c = [0.0; ORDER + 1];
+    let eps2 = eps * eps;
+    let (mut d, mut o) = (eps, 0);
+    for (l, c) in c.iter_mut().enumerate().skip(1) {
+        let m = (ORDER - l) / 2;
+        *c = d * polyval(&coeff[o..=o + m], eps2) / coeff[o + m + 1];
+        o += m + 2;
+        d *= eps;
+    }
+
+    c
+}
+
+/// Evaluates the sine series `sum(c[l] * sin(2 * l * x))` using Clenshaw summation.
+///
+/// The first coefficient is not used.
+pub(crate) fn sin_series(sinx: f64, cosx: f64, c: &[f64]) -> f64 {
+    let ar = 2.0 * (cosx - sinx) * (cosx + sinx);
+    let (mut y0, mut y1) = (0.0, 0.0);
+    let mut k = c.len();
+    if (k - 1) % 2 == 1 {
+        k -= 1;
+        y0 = c[k];
+    }
+    while k > 1 {
+        k -= 1;
+        y1 = ar * y0 - y1 + c[k];
+        k -= 1;
+        y0 = ar * y1 - y0 + c[k];
+    }
+
+    2.0 * sinx * cosx * y0
+}
+
+/// Solves the astroid equation `k^4 + 2 * k^3 - (x^2 + y^2 - 1) * k^2 - 2 * y^2 * k - y^2 = 0`
+/// for its positive root.
+fn astroid(x: f64, y: f64) -> f64 {
+    let p = x * x;
+    let q = y * y;
+    let r = (p + q - 1.0) / 6.0;
+    if q == 0.0 && r <= 0.0 {
+        return 0.0;
+    }
+
+    let s = p * q / 4.0;
+    let r2 = r * r;
+    let r3 = r * r2;
+    let disc = s * (s + 2.0 * r3);
+    let mut u = r;
+    if disc >= 0.0 {
+        let t3 = s + r3;
+        let t3 = t3 + if t3 < 0.0 { -disc.sqrt() } else { disc.sqrt() };
+        let t = t3.cbrt();
+        u += t + if t == 0.0 { 0.0 } else { r2 / t };
+    } else {
+        let ang = (-disc).sqrt().atan2(-(s + r3));
+        u += 2.0 * r * (ang / 3.0).cos();
+    }
+    let v = (u * u + q).sqrt();
+    let uv = if u < 0.0 { q / (v - u) } else { u + v };
+    let w = (uv - q) / (2.0 * v);
+
+    uv / ((uv + w * w).sqrt() + w)
+}
+
+/// Evaluates a polynomial with the coefficients in order of decreasing degree using Horner's
+/// method.
+fn polyval(coeff: &[f64], x: f64) -> f64 {
+    coeff.iter().fold(0.0, |y, c| y * x + c)
+}
+
+/// Normalizes a sine and cosine pair.
+pub(crate) fn norm(sinx: f64, cosx: f64) -> (f64, f64) {
+    let r = sinx.hypot(cosx);
+
+    (sinx / r, cosx / r)
+}
+
+/// Rounds an angle in degrees such that tiny values become zero, which prevents the ill-effects
+/// of underflow.
+fn ang_round(x: f64) -> f64 {
+    const Z: f64 = 1.0 / 16.0;
+
+    let y = x.abs();
+    let w = Z - y;
+    let y = if w > 0.0 { Z - w } else { y };
+
+    y.copysign(x)
+}
+
+/// Returns the remainder of an angle in degrees in the range `-180.0..=180.0`.
+fn remainder(x: f64) -> f64 {
+    let r = x % 360.0;
+    if r.abs() > 180.0 {
+        r - 360.0_f64.copysign(r)
+    } else {
+        r
+    }
+}
+
+/// Returns the exact sum of two numbers as the rounded sum and the error.
+fn sum(u: f64, v: f64) -> (f64, f64) {
+    let s = u + v;
+    let up = s - v;
+    let vpp = s - up;
+    let t = -((up - u) + (vpp - v));
+
+    (s, if s == 0.0 { s } else { t })
+}
+
+/// Returns the difference of two angles in degrees in the range `-180.0..=180.0`, accurately,
+/// as the rounded difference and the error.
+fn ang_diff(x: f64, y: f64) -> (f64, f64) {
+    let (d, t) = sum(remainder(-x), remainder(y));
+    let (d, t) = sum(remainder(d), t);
+    let d = if d == 0.0 || d.abs() == 180.0 {
+        d.copysign(if t == 0.0 { y - x } else { -t })
+    } else {
+        d
+    };
+
+    (d, t)
+}
+
+/// Returns the sine and cosine of an angle in degrees, exactly for multiples of 90 degrees.
+pub(crate) fn sincosd(x: f64) -> (f64, f64) {
+    let r = x % 360.0;
+    let q = if r.is_nan() { 0.0 } else { (r / 90.0).round() };
+    let (s, c) = (r - 90.0 * q).to_radians().sin_cos();
+    let (s, c) = match (q as i64).rem_euclid(4) {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+
+    (if s == 0.0 { s.copysign(x) } else { s }, 0.0 + c)
+}
+
+/// Returns the angle in degrees in the range `-180.0..=180.0` with the given sine and cosine
+/// (up to a common factor), exactly for multiples of 90 degrees.
+pub(crate) fn atan2d(y: f64, x: f64) -> f64 {
+    let (mut x, mut y) = (x, y);
+    let mut q = 0;
+    if y.abs() > x.abs() {
+        core::mem::swap(&mut x, &mut y);
+        q = 2;
+    }
+    if x.is_sign_negative() {
+        x = -x;
+        q += 1;
+    }
+    let ang = y.atan2(x).to_degrees();
+
+    match q {
+        1 => 180.0_f64.copysign(y) - ang,
+        2 => 90.0 - ang,
+        3 => -90.0 + ang,
+        _ => ang,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geodesic_inverse() {
+        let geodesic = Geodesic::new(&Ellipsoid::WGS84);
+
+        // The example from Karney (2013), which Vincenty's method fails to solve.
+        let inverse = geodesic.inverse(-41.32, 174.81, 40.96, -5.50);
+        assert!((inverse.distance - 19_959_679.267_353).abs() < 1e-6);
+        assert!((inverse.initial_azimuth - 161.067_669_986).abs() < 1e-9);
+        assert!((inverse.final_azimuth - 18.825_195_123).abs() < 1e-9);
+
+        let inverse = geodesic.inverse(0.0, 0.0, 0.0, 180.0);
+        assert!((inverse.distance - 20_003_931.458_625).abs() < 1e-6);
+        assert_eq!(inverse.initial_azimuth, 0.0);
+        assert_eq!(inverse.final_azimuth, 180.0);
+
+        let inverse = geodesic.inverse(0.0, 0.0, 0.0, 90.0);
+        assert!((inverse.distance - 10_018_754.171_394).abs() < 1e-6);
+        assert_eq!(inverse.initial_azimuth, 90.0);
+
+        let inverse = geodesic.inverse(52.107, 5.134, 52.107, 5.134);
+        assert_eq!(inverse.distance, 0.0);
+
+        // A sphere.
+        let geodesic = Geodesic::new(&Ellipsoid::new(1_737_400.0, 1_737_400.0));
+        let inverse = geodesic.inverse(0.0, 0.0, 45.0, 90.0);
+        assert!((inverse.distance - 1_737_400.0 * PI / 2.0).abs() < 1e-6);
+        assert!((inverse.initial_azimuth - 45.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn geodesic_direct() {
+        let geodesic = Geodesic::new(&Ellipsoid::WGS84);
+
+        let (lat2, lon2, azi2) =
+            geodesic.direct(-41.32, 174.81, 161.067_669_986, 19_959_679.267_353);
+        assert!((lat2 - 40.96).abs() < 1e-9);
+        assert!((lon2 + 5.50).abs() < 1e-9);
+        assert!((azi2 - 18.825_195_123).abs() < 1e-9);
+
+        let (lat2, lon2, azi2) = geodesic.direct(0.0, 179.5, 90.0, 111_319.490_793);
+        assert!(lat2.abs() < 1e-12);
+        assert!((lon2 + 179.5).abs() < 1e-9);
+        assert_eq!(azi2, 90.0);
+
+        let (lat2, _, _) = geodesic.direct(0.0, 0.0, 0.0, 10_001_965.729_313);
+        assert!((lat2 - 90.0).abs() < 1e-9);
+
+        // The direct problem undoes the inverse problem, also backwards.
+        let points = [
+            (52.0907, 5.1214, 48.8530, 2.3499),
+            (-33.9, 151.2, 51.5, -0.1),
+            (0.5, 0.0, -0.5, 179.7),
+            (89.0, 10.0, 89.0, -170.0),
+        ];
+        for (lat1, lon1, lat2, lon2) in points {
+            let inverse = geodesic.inverse(lat1, lon1, lat2, lon2);
+            let direct = geodesic.direct(lat1, lon1, inverse.initial_azimuth, inverse.distance);
+            assert!((direct.0 - lat2).abs() < 1e-9, "{direct:?}");
+            assert!((direct.1 - lon2).abs() < 1e-9, "{direct:?}");
+            assert!(
+                ang_diff(direct.2, inverse.final_azimuth).0.abs() < 1e-9,
+                "{direct:?}"
+            );
+            let direct = geodesic.direct(lat2, lon2, inverse.final_azimuth, -inverse.distance);
+            assert!((direct.0 - lat1).abs() < 1e-9, "{direct:?}");
+            assert!((direct.1 - lon1).abs() < 1e-9, "{direct:?}");
+            assert!(
+                ang_diff(direct.2, inverse.initial_azimuth).0.abs() < 1e-9,
+                "{direct:?}"
+            );
+        }
+
+        // A flattening large enough to refine the arc length.
+        let geodesic = Geodesic::new(&Ellipsoid::new(6_378_137.0, 6_000_000.0));
+        let inverse = geodesic.inverse(10.0, 20.0, -30.0, 100.0);
+        let (lat2, lon2, _) =
+            geodesic.direct(10.0, 20.0, inverse.initial_azimuth, inverse.distance);
+        assert!((lat2 + 30.0).abs() < 1e-9);
+        assert!((lon2 - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn angles() {
+        assert_eq!(ang_diff(170.0, -170.0), (20.0, 0.0));
+        assert_eq!(ang_diff(-170.0, 170.0), (-20.0, 0.0));
+        assert_eq!(ang_diff(0.0, 180.0).0, 180.0);
+        assert_eq!(ang_diff(0.0, -180.0).0, -180.0);
+        assert_eq!(ang_diff(10.0, 370.0).0, 0.0);
+
+        assert_eq!(sincosd(90.0), (1.0, 0.0));
+        assert_eq!(sincosd(-180.0), (-0.0, -1.0));
+        assert_eq!(sincosd(270.0), (-1.0, 0.0));
+
+        assert_eq!(atan2d(1.0, 0.0), 90.0);
+        assert_eq!(atan2d(0.0, -1.0), 180.0);
+        assert_eq!(atan2d(-0.0, -1.0), -180.0);
+        assert_eq!(atan2d(-1.0, -1.0), -135.0);
+
+        assert_eq!(ang_round(1e-20), 0.0);
+        assert_eq!(ang_round(-45.0), -45.0);
+    }
+}
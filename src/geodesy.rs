@@ -0,0 +1,1197 @@
+//! Geodesic computations on the reference ellipsoid of the coordinate reference system.
+
+use alloc::vec::Vec;
+
+use crate::geodesic::{atan2d, sincosd, Geodesic};
+use crate::{CoordRefSystem, Crs, Ellipsoid, Error, GeoUri, LatitudeConvention, Normalization};
+
+/// The method used for geodesic computations.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{GeoUri, GeodesicMethod};
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let geo_uri = GeoUri::parse("geo:52.0907,5.1214")?;
+/// let geo_uri2 = GeoUri::parse("geo:48.8530,2.3499")?;
+/// let spherical = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Spherical)?;
+/// let ellipsoidal = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+/// assert_eq!(spherical.distance().round(), 409_907.0);
+/// assert_eq!(ellipsoidal.distance().round(), 410_321.0);
+/// # Ok(())
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum GeodesicMethod {
+    /// Great-circle computations on a sphere with the mean radius of the reference ellipsoid.
+    ///
+    /// This is fast, but has an error of up to about 0.5% on the Earth.
+    Spherical,
+
+    /// Geodesic computations on the reference ellipsoid.
+    ///
+    /// This uses the algorithms of Karney, which are accurate to a few nanometers on the Earth
+    /// and, unlike Vincenty's method, also work for nearly antipodal locations.
+    #[default]
+    Ellipsoidal,
+}
+
+/// The distance between two locations along a path, and its azimuths.
+///
+/// The azimuths are in degrees clockwise from the north in the range `0.0` up until `360.0`.
+/// If the locations coincide, the azimuths are arbitrary.
+///
+/// Use [`GeoUri::distance_to`] to compute it along the geodesic, or
+/// [`GeoUri::rhumb_distance_to`] along the rhumb line.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Distance {
+    /// The distance in meters.
+    pub(crate) distance: f64,
+
+    /// The azimuth of the path at the first location in degrees.
+    pub(crate) initial_azimuth: f64,
+
+    /// The azimuth of the path at the second location in degrees.
+    pub(crate) final_azimuth: f64,
+}
+
+impl Distance {
+    /// Returns the distance in meters.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// Returns the azimuth of the path at the first location in degrees.
+    ///
+    /// This is the direction to set off to from the first location to reach the second one.
+    pub fn initial_azimuth(&self) -> f64 {
+        self.initial_azimuth
+    }
+
+    /// Returns the azimuth of the path at the second location in degrees.
+    ///
+    /// This is the direction of travel when arriving at the second location.
+    pub fn final_azimuth(&self) -> f64 {
+        self.final_azimuth
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl GeoUri {
+    /// Returns the distance to the location of another geo URI along the surface, and its
+    /// azimuths.
+    ///
+    /// The distance is computed on the reference ellipsoid of the coordinate reference system
+    /// using the method, and the altitudes are ignored. See [`GeoUri::distance_3d_to`] for
+    /// taking the altitudes into account.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:-41.32,174.81")?; // Wellington
+    /// let geo_uri2 = GeoUri::parse("geo:40.96,-5.50")?; // Salamanca
+    /// let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(distance.distance().round(), 19_959_679.0);
+    /// assert_eq!(distance.initial_azimuth().round(), 161.0);
+    /// assert_eq!(distance.final_azimuth().round(), 19.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn distance_to(&self, other: &Self, method: GeodesicMethod) -> Result<Distance, Error> {
+        let ellipsoid = self.common_ellipsoid(other)?;
+
+        Ok(inverse(
+            &ellipsoid,
+            method,
+            self.east_coords(),
+            other.east_coords(),
+        ))
+    }
+
+    /// Returns the distance to the location of another geo URI, taking the altitudes into
+    /// account.
+    ///
+    /// This combines the distance along the surface (see [`GeoUri::distance_to`]) with the
+    /// difference in altitude as if they were perpendicular, which is a good approximation for
+    /// locations that are not too far apart. If either geo URI has no altitude, only the distance
+    /// along the surface is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.0907,5.1214,0")?;
+    /// let geo_uri2 = GeoUri::parse("geo:52.0907,5.1214,112.5")?; // The top of the Dom tower
+    /// let distance = geo_uri.distance_3d_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(distance, 112.5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn distance_3d_to(&self, other: &Self, method: GeodesicMethod) -> Result<f64, Error> {
+        let distance = self.distance_to(other, method)?.distance;
+
+        match (self.altitude, other.altitude) {
+            (Some(altitude), Some(other_altitude)) => Ok(distance.hypot(altitude - other_altitude)),
+            _ => Ok(distance),
+        }
+    }
+
+    /// Returns whether the location is within a distance of the location of another geo URI.
+    ///
+    /// The distance is the great-circle distance on a sphere with the mean radius of the
+    /// reference ellipsoid of the coordinate reference system, in meters. If both geo URIs have
+    /// an altitude, the difference in altitude is taken into account as well, see
+    /// [`GeoUri::distance_3d_to`] with [`GeodesicMethod::Spherical`].
+    ///
+    /// This is never the case if the geo URIs have different coordinate reference systems or if
+    /// their coordinate reference system has no reference ellipsoid. The uncertainty and the
+    /// other parameters are ignored, see [`GeoUri::approx_eq_within_uncertainty`] for taking the
+    /// uncertainty into account.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.10700,5.13400")?;
+    /// let geo_uri2 = GeoUri::parse("geo:52.10705,5.13390")?;
+    /// assert!(geo_uri.approx_eq(&geo_uri2, 10.0));
+    /// assert!(!geo_uri.approx_eq(&geo_uri2, 5.0));
+    ///
+    /// let geo_uri2 = GeoUri::parse("geo:52.10705,5.13390,25")?;
+    /// assert!(geo_uri.approx_eq(&geo_uri2, 10.0));
+    /// let geo_uri = GeoUri::parse("geo:52.10700,5.13400,0")?;
+    /// assert!(!geo_uri.approx_eq(&geo_uri2, 10.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        self.distance_3d_to(other, GeodesicMethod::Spherical)
+            .is_ok_and(|distance| distance <= tolerance)
+    }
+
+    /// Returns whether the location is within a distance of the location of another geo URI,
+    /// taking the uncertainty of both into account.
+    ///
+    /// This is the case if the uncertainty circles (or spheres, if both geo URIs have an
+    /// altitude) of both locations overlap when enlarged by the tolerance, i.e. if the distance
+    /// between the locations is at most the sum of both uncertainties and the tolerance. A
+    /// missing uncertainty counts as zero. See [`GeoUri::approx_eq`] for how the distance is
+    /// determined.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.10700,5.13400;u=5")?;
+    /// let geo_uri2 = GeoUri::parse("geo:52.10705,5.13390;u=5")?;
+    /// assert!(geo_uri.approx_eq_within_uncertainty(&geo_uri2, 0.0));
+    /// assert!(!geo_uri.approx_eq(&geo_uri2, 0.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn approx_eq_within_uncertainty(&self, other: &Self, tolerance: f64) -> bool {
+        let uncertainty =
+            self.uncertainty.unwrap_or_default() + other.uncertainty.unwrap_or_default();
+
+        self.approx_eq(other, uncertainty + tolerance)
+    }
+
+    /// Returns the geo URI of the location reached by travelling a distance along the geodesic
+    /// that sets off with an azimuth.
+    ///
+    /// The distance is in meters and may be negative to travel backwards, and the azimuth is in
+    /// degrees clockwise from the north. The resulting geo URI has the coordinate reference
+    /// system and altitude of this geo URI, but no uncertainty or other parameters, and its
+    /// coordinates are normalized (see [`GeoUri::normalize`]).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:0,179.5,12")?;
+    /// let destination = geo_uri.destination(111_319.491, 90.0, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(destination.latitude().round(), 0.0);
+    /// assert!((destination.longitude() + 179.5).abs() < 1e-6);
+    /// assert_eq!(destination.altitude(), Some(12.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid, or
+    /// if the resulting coordinates are invalid, e.g. because the distance is not finite.
+    pub fn destination(
+        &self,
+        distance: f64,
+        azimuth: f64,
+        method: GeodesicMethod,
+    ) -> Result<Self, Error> {
+        let ellipsoid = self.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+        let (latitude, longitude, _) =
+            direct(&ellipsoid, method, self.east_coords(), azimuth, distance);
+
+        self.at_east_coords(latitude, longitude, self.altitude)
+    }
+
+    /// Returns the geo URI of the location halfway along the geodesic to the location of
+    /// another geo URI.
+    ///
+    /// See [`GeoUri::intermediate_point`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:10,170,100")?;
+    /// let geo_uri2 = GeoUri::parse("geo:10,-170,300")?;
+    /// let midpoint = geo_uri.midpoint(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(midpoint.latitude().round(), 10.0);
+    /// assert_eq!(midpoint.longitude(), 180.0);
+    /// assert_eq!(midpoint.altitude(), Some(200.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn midpoint(&self, other: &Self, method: GeodesicMethod) -> Result<Self, Error> {
+        self.intermediate_point(other, 0.5, method)
+    }
+
+    /// Returns the geo URI of the location at a fraction of the way along the geodesic to the
+    /// location of another geo URI.
+    ///
+    /// A fraction of `0.0` is the location of this geo URI and `1.0` that of the other one;
+    /// fractions outside of this range extend the geodesic beyond the locations. If both geo
+    /// URIs have an altitude, it is interpolated linearly, otherwise the result has none. See
+    /// [`GeoUri::destination`] for the other properties of the resulting geo URI.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn intermediate_point(
+        &self,
+        other: &Self,
+        fraction: f64,
+        method: GeodesicMethod,
+    ) -> Result<Self, Error> {
+        let distance = self.distance_to(other, method)?;
+
+        self.interpolate(other, &distance, fraction, method)
+    }
+
+    /// Returns the geo URIs of a number of evenly spaced locations along the geodesic to the
+    /// location of another geo URI.
+    ///
+    /// The locations of the geo URIs themselves are not included, so the geodesic is divided
+    /// into `count + 1` parts of equal length. See [`GeoUri::intermediate_point`] for the
+    /// properties of the resulting geo URIs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:0,0")?;
+    /// let geo_uri2 = GeoUri::parse("geo:0,4")?;
+    /// let points = geo_uri.intermediate_points(&geo_uri2, 3, GeodesicMethod::Spherical)?;
+    /// let longitudes: Vec<_> = points.iter().map(|point| point.longitude().round()).collect();
+    /// assert_eq!(longitudes, [1.0, 2.0, 3.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn intermediate_points(
+        &self,
+        other: &Self,
+        count: usize,
+        method: GeodesicMethod,
+    ) -> Result<Vec<Self>, Error> {
+        let distance = self.distance_to(other, method)?;
+        let parts = count as f64 + 1.0;
+
+        (1..=count)
+            .map(|i| self.interpolate(other, &distance, i as f64 / parts, method))
+            .collect()
+    }
+
+    /// Returns the geo URI of the location at a fraction of the distance to the location of
+    /// another geo URI, with the altitude interpolated linearly.
+    fn interpolate(
+        &self,
+        other: &Self,
+        distance: &Distance,
+        fraction: f64,
+        method: GeodesicMethod,
+    ) -> Result<Self, Error> {
+        let mut geo_uri = self.destination(
+            distance.distance * fraction,
+            distance.initial_azimuth,
+            method,
+        )?;
+        geo_uri.altitude = self.interpolate_altitude(other, fraction);
+
+        Ok(geo_uri)
+    }
+
+    /// Returns the altitude at a fraction of the way to another geo URI, interpolated linearly,
+    /// if both geo URIs have an altitude.
+    pub(crate) fn interpolate_altitude(&self, other: &Self, fraction: f64) -> Option<f64> {
+        match (self.altitude, other.altitude) {
+            (Some(altitude), Some(other_altitude)) => {
+                Some(altitude + (other_altitude - altitude) * fraction)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a geo URI with the coordinate reference system of this geo URI at a geodetic
+    /// latitude and a longitude increasing eastwards, in degrees.
+    ///
+    /// The coordinates are normalized, where a latitude just beyond a pole due to rounding is
+    /// clamped, and then validated.
+    pub(crate) fn at_east_coords(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        altitude: Option<f64>,
+    ) -> Result<Self, Error> {
+        let latitude = from_geodetic(&self.crs, latitude);
+        let longitude = if self.crs.longitude_convention().is_east_positive() {
+            longitude
+        } else {
+            -longitude
+        };
+        let (latitude, longitude) = Normalization::Clamp.apply(&self.crs, latitude, longitude);
+        self.crs.validate(latitude, longitude)?;
+
+        Ok(Self {
+            crs: self.crs,
+            latitude,
+            longitude,
+            altitude,
+            ..Self::default()
+        })
+    }
+
+    /// Returns the reference ellipsoid shared by the geo URI and another one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub(crate) fn common_ellipsoid(&self, other: &Self) -> Result<Ellipsoid, Error> {
+        if self.crs != other.crs {
+            return Err(Error::MismatchedCoordRefSystem);
+        }
+
+        self.crs.ellipsoid().ok_or(Error::MissingEllipsoid)
+    }
+
+    /// Returns the geodetic latitude and the longitude increasing eastwards, in degrees.
+    ///
+    /// The longitude is zero where it is ignored, e.g. at the poles.
+    pub(crate) fn east_coords(&self) -> (f64, f64) {
+        let longitude = if self.crs.is_longitude_ignored(self.latitude) {
+            0.0
+        } else if self.crs.longitude_convention().is_east_positive() {
+            self.longitude
+        } else {
+            -self.longitude
+        };
+
+        (to_geodetic(&self.crs, self.latitude), longitude)
+    }
+}
+
+/// Converts a latitude in the coordinate reference system to the geodetic latitude on its
+/// reference ellipsoid, in degrees.
+pub(crate) fn to_geodetic(crs: &CoordRefSystem, latitude: f64) -> f64 {
+    match (crs.latitude_convention(), crs.ellipsoid()) {
+        (LatitudeConvention::Planetocentric, Some(ellipsoid)) => {
+            let (sin_psi, cos_psi) = sincosd(latitude);
+            atan2d(sin_psi, (1.0 - ellipsoid.flattening()).powi(2) * cos_psi)
+        }
+        _ => latitude,
+    }
+}
+
+/// Converts a geodetic latitude on the reference ellipsoid of the coordinate reference system
+/// to a latitude in the coordinate reference system, in degrees.
+pub(crate) fn from_geodetic(crs: &CoordRefSystem, latitude: f64) -> f64 {
+    match (crs.latitude_convention(), crs.ellipsoid()) {
+        (LatitudeConvention::Planetocentric, Some(ellipsoid)) => {
+            let (sin_phi, cos_phi) = sincosd(latitude);
+            atan2d((1.0 - ellipsoid.flattening()).powi(2) * sin_phi, cos_phi)
+        }
+        _ => latitude,
+    }
+}
+
+/// Solves the inverse problem with the method: the distance between two locations and its
+/// azimuths.
+///
+/// The locations are given as latitude and longitude in degrees, with east-positive longitudes.
+pub(crate) fn inverse(
+    ellipsoid: &Ellipsoid,
+    method: GeodesicMethod,
+    (lat1, lon1): (f64, f64),
+    (lat2, lon2): (f64, f64),
+) -> Distance {
+    let (distance, initial_azimuth, final_azimuth) = match method {
+        GeodesicMethod::Spherical => {
+            let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+            let delta_lambda = (lon2 - lon1).to_radians();
+            let distance = ellipsoid.mean_radius() * central_angle((lat1, lon1), (lat2, lon2));
+            let initial_azimuth = (delta_lambda.sin() * phi2.cos())
+                .atan2(phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * delta_lambda.cos());
+            let final_azimuth = (delta_lambda.sin() * phi1.cos())
+                .atan2(phi2.sin() * phi1.cos() * delta_lambda.cos() - phi2.cos() * phi1.sin());
+
+            (
+                distance,
+                initial_azimuth.to_degrees(),
+                final_azimuth.to_degrees(),
+            )
+        }
+        GeodesicMethod::Ellipsoidal => {
+            let inverse = Geodesic::new(ellipsoid).inverse(lat1, lon1, lat2, lon2);
+
+            (
+                inverse.distance,
+                inverse.initial_azimuth,
+                inverse.final_azimuth,
+            )
+        }
+    };
+
+    Distance {
+        distance,
+        initial_azimuth: bearing(initial_azimuth),
+        final_azimuth: bearing(final_azimuth),
+    }
+}
+
+/// Solves the direct problem with the method: the location reached by travelling a distance
+/// along the geodesic that sets off with an azimuth.
+///
+/// The location is given as latitude and longitude in degrees, with east-positive longitudes,
+/// and the azimuth is in degrees clockwise from the north. Returns the latitude, longitude and
+/// azimuth at the location reached, where the longitude is not necessarily in range.
+pub(crate) fn direct(
+    ellipsoid: &Ellipsoid,
+    method: GeodesicMethod,
+    (lat1, lon1): (f64, f64),
+    azimuth: f64,
+    distance: f64,
+) -> (f64, f64, f64) {
+    match method {
+        GeodesicMethod::Spherical => {
+            let (phi1, theta) = (lat1.to_radians(), azimuth.to_radians());
+            let delta = distance / ellipsoid.mean_radius();
+            let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).asin();
+            let delta_lambda = (theta.sin() * delta.sin() * phi1.cos())
+                .atan2(delta.cos() - phi1.sin() * phi2.sin());
+            let final_azimuth = (phi1.cos() * theta.sin())
+                .atan2(phi1.cos() * delta.cos() * theta.cos() - phi1.sin() * delta.sin());
+
+            (
+                phi2.to_degrees(),
+                lon1 + delta_lambda.to_degrees(),
+                final_azimuth.to_degrees(),
+            )
+        }
+        GeodesicMethod::Ellipsoidal => {
+            Geodesic::new(ellipsoid).direct(lat1, lon1, azimuth, distance)
+        }
+    }
+}
+
+/// Converts an azimuth in degrees to the range `0.0` up until `360.0`.
+pub(crate) fn bearing(azimuth: f64) -> f64 {
+    let bearing = azimuth.rem_euclid(360.0);
+
+    // A tiny negative azimuth can round up to a full turn.
+    if bearing >= 360.0 {
+        0.0
+    } else {
+        bearing + 0.0
+    }
+}
+
+/// Returns the central angle between two locations on a sphere in radians.
+///
+/// The locations are given as latitude and longitude in degrees. This uses the haversine formula,
+/// which is well-conditioned for small distances.
+fn central_angle((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let delta_phi = phi2 - phi1;
+    let delta_lambda = (lon2 - lon1).to_radians();
+
+    let haversine = (delta_phi / 2.0).sin().powi(2)
+        + phi1.cos() * phi2.cos() * (delta_lambda / 2.0).sin().powi(2);
+
+    2.0 * haversine.sqrt().min(1.0).asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalize::wrap_degrees;
+    use crate::test_util::{assert_near, GRID, WEST_EARTH};
+    use crate::{CoordRefSystem, GeoUriBuilderError};
+
+    /// The distance of one degree of arc on the mean radius of the WGS-84 ellipsoid.
+    const WGS84_DEGREE: f64 = 111_195.08;
+
+    /// Reference solutions of the inverse problem on the WGS-84 ellipsoid computed with
+    /// GeographicLib: `[lat1, lon1, lat2, lon2, s12, azi1, azi2]`.
+    const GEODESICS: [[f64; 7]; 32] = [
+        [0.0, 0.0, 0.0, 1.0, 111319.4907932264, 90.0, 90.0],
+        [
+            52.0907,
+            5.1214,
+            48.853,
+            2.3499,
+            410321.316940219,
+            -150.27504165640445,
+            -152.4137570762704,
+        ],
+        [0.0, 0.0, 10.0, 0.0, 1105854.8332357334, 0.0, 0.0],
+        [
+            -30.0,
+            20.0,
+            60.0,
+            -100.0,
+            14500274.5411964,
+            -34.75898913697304,
+            -99.65694467538388,
+        ],
+        [
+            89.0,
+            0.0,
+            -89.0,
+            1.0,
+            19780552.23636309,
+            179.4998777491071,
+            179.4998777491071,
+        ],
+        [0.0, 0.0, 0.0, 179.0, 19926188.85199594, 90.0, 90.0],
+        [
+            34.28056654892576,
+            -35.30745782740348,
+            34.003482532823114,
+            37.797810953745085,
+            6581976.108172152,
+            67.6229790421957,
+            112.8263009322403,
+        ],
+        [
+            49.83379801589351,
+            149.8830003549126,
+            -33.53425997472537,
+            136.7145128683731,
+            9325613.197900372,
+            -168.9518981815647,
+            -171.4643431097829,
+        ],
+        [
+            66.88968553196815,
+            76.10249267266164,
+            -63.09087194466909,
+            -15.233784078344058,
+            16158264.271226453,
+            -126.84463535866868,
+            -136.0398686175065,
+        ],
+        [
+            -89.66931786395561,
+            -67.61307318133973,
+            49.77478908557188,
+            72.4841539920856,
+            15546045.681809505,
+            139.8463968380089,
+            0.33065693383457023,
+        ],
+        [
+            20.591057101922658,
+            -97.1743949139156,
+            61.192497811082575,
+            -50.82181073118775,
+            5751967.266519482,
+            26.461090075833223,
+            59.74224166459787,
+        ],
+        [
+            -57.40344963046222,
+            -143.41286832440014,
+            64.56716025808282,
+            67.47013862978852,
+            18181908.209574156,
+            -51.351304835228675,
+            -101.66359897805854,
+        ],
+        [
+            36.41804712940554,
+            -27.130343664381712,
+            -81.81522970329696,
+            -51.815676466841836,
+            13196127.902853798,
+            -176.09341298650088,
+            -157.40059433429866,
+        ],
+        [
+            81.26462658580655,
+            -156.93892913266288,
+            88.45841097501776,
+            93.73985202877958,
+            1045191.0282321544,
+            -8.982745949893923,
+            -118.19281090051942,
+        ],
+        [
+            16.096501103825247,
+            -70.58427326638498,
+            -41.47845682034456,
+            121.6643332533688,
+            16958151.426527817,
+            -160.04671213039305,
+            -25.91903590988661,
+        ],
+        [
+            37.472215291211,
+            130.19588280038437,
+            -32.97066524116672,
+            161.23790675600412,
+            8437366.580547763,
+            153.42042184246228,
+            154.95152468295453,
+        ],
+        [
+            -43.47774155706533,
+            86.56289821996688,
+            -38.79211460166552,
+            109.25701325425587,
+            1968317.0031234906,
+            82.44736741007324,
+            67.39417971011636,
+        ],
+        [
+            -2.1599398153951768,
+            41.72073986699459,
+            -15.09406522861731,
+            -142.69610054138678,
+            18036566.692624908,
+            166.27245767326636,
+            14.214670662192269,
+        ],
+        [
+            38.084456608449045,
+            28.207956548882947,
+            -74.57921257881722,
+            95.91517731460652,
+            13430363.599470496,
+            163.26974617460155,
+            121.7299857832673,
+        ],
+        [
+            -9.549408110535637,
+            44.40648164314132,
+            -33.36430155554816,
+            -64.00045458525625,
+            11101609.446516022,
+            -126.46762156330031,
+            -71.56231112591061,
+        ],
+        [
+            54.405998390804,
+            150.7803645413673,
+            83.97748598890124,
+            7.701226907988087,
+            4524278.513623635,
+            -5.568773624281528,
+            -147.4702816688764,
+        ],
+        [
+            27.62617608673169,
+            -118.11376276854924,
+            -26.462473335322912,
+            89.45462136748552,
+            17297589.356773775,
+            -93.53785717979947,
+            -98.93949008412223,
+        ],
+        [
+            67.5731714966058,
+            147.07405557884908,
+            28.924339430182286,
+            -167.46224334261234,
+            5237198.8130383175,
+            121.47090015942759,
+            158.12791902501442,
+        ],
+        [
+            44.792352346053065,
+            -84.07027847472912,
+            -47.53350395747638,
+            -46.77528285948284,
+            10859631.834069282,
+            155.50587585156504,
+            154.16893178396109,
+        ],
+        [
+            65.11308451073427,
+            -125.75174404520637,
+            81.13669697690912,
+            61.48150935220059,
+            3761969.9211154114,
+            -2.0046080019559334,
+            -174.5204529671832,
+        ],
+        [
+            -7.034857050445126,
+            -58.32798344755217,
+            33.973741546611905,
+            98.04203280120458,
+            16167926.085840443,
+            35.55550848811581,
+            135.9554760278184,
+        ],
+        [
+            18.2989195881388,
+            -10.50296192990578,
+            18.29832434054338,
+            -10.502031306762367,
+            118.41203285374439,
+            123.806948156299,
+            123.80724034359596,
+        ],
+        [
+            20.199412531949932,
+            126.42220798789629,
+            20.197873381773647,
+            126.42576295879849,
+            408.7572543580652,
+            114.63586081963368,
+            114.63708826568116,
+        ],
+        [
+            28.67585195008327,
+            25.438667623225996,
+            28.681885204325084,
+            25.43823289960706,
+            670.0170833128514,
+            -3.6357576356556254,
+            -3.6359662595049107,
+        ],
+        [
+            -71.61079383898178,
+            167.95584855071212,
+            -71.61063971471366,
+            167.958400430587,
+            91.51891486400388,
+            79.17028970412382,
+            79.1678681359547,
+        ],
+        [
+            82.43509983932293,
+            -76.86330142972193,
+            82.43803955596266,
+            -76.8594338968208,
+            333.17807387119007,
+            9.823464625854188,
+            9.827298510263015,
+        ],
+        [
+            30.51865933369851,
+            -86.19011763087701,
+            30.516819931352533,
+            -86.18449280812837,
+            577.106548839474,
+            110.69068948906754,
+            110.69354580281855,
+        ],
+    ];
+
+    #[test]
+    fn geo_uri_distance_to() -> Result<(), Error> {
+        let utrecht = GeoUri::parse("geo:52.0907,5.1214")?;
+        let paris = GeoUri::parse("geo:48.8530,2.3499")?;
+        let distance = utrecht.distance_to(&paris, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 410_321.317, 1e-3);
+        assert_near(distance.initial_azimuth(), 209.724_958, 1e-6);
+        assert_near(distance.final_azimuth(), 207.586_243, 1e-6);
+        let distance = utrecht.distance_to(&paris, GeodesicMethod::Spherical)?;
+        assert_near(distance.distance(), 409_906.637, 1e-3);
+        assert_near(distance.initial_azimuth(), 209.6, 0.1);
+        assert_near(distance.final_azimuth(), 207.5, 0.1);
+        assert_eq!(
+            utrecht
+                .distance_to(&utrecht, GeodesicMethod::Ellipsoidal)?
+                .distance(),
+            0.0
+        );
+        assert_eq!(
+            utrecht
+                .distance_to(&utrecht, GeodesicMethod::Spherical)?
+                .distance(),
+            0.0
+        );
+
+        let wellington = GeoUri::parse("geo:-41.32,174.81")?;
+        let salamanca = GeoUri::parse("geo:40.96,-5.50")?;
+        let distance = wellington.distance_to(&salamanca, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 19_959_679.267_353, 1e-6);
+        assert_near(distance.initial_azimuth(), 161.067_669_986, 1e-9);
+        assert_near(distance.final_azimuth(), 18.825_195_123, 1e-9);
+
+        let geo_uri = GeoUri::parse("geo:0,0")?;
+        let geo_uri2 = GeoUri::parse("geo:0,-1")?;
+        let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 111_319.491, 1e-3);
+        assert_eq!(distance.initial_azimuth(), 270.0);
+        assert_eq!(distance.final_azimuth(), 270.0);
+        let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Spherical)?;
+        assert_near(distance.distance(), WGS84_DEGREE, 0.01);
+        assert_eq!(distance.initial_azimuth(), 270.0);
+        assert_eq!(distance.final_azimuth(), 270.0);
+
+        let north_pole = GeoUri::parse("geo:90,5.134")?;
+        let south_pole = GeoUri::parse("geo:-90,-22.43")?;
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let distance = north_pole.distance_to(&south_pole, method)?;
+            assert_near(distance.distance(), 20_003_931.459, 0.5e6);
+            assert_eq!(distance.initial_azimuth(), 180.0);
+            assert_eq!(distance.final_azimuth(), 180.0);
+        }
+        let distance = north_pole.distance_to(&south_pole, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 20_003_931.459, 1e-3);
+
+        let geo_uri = GeoUri::parse("geo:0,179.5")?;
+        let geo_uri2 = GeoUri::parse("geo:0,-179.5")?;
+        let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 111_319.491, 1e-3);
+        assert_eq!(distance.initial_azimuth(), 90.0);
+
+        let geo_uri = GeoUri::parse("geo:0,0;crs=mars")?;
+        let geo_uri2 = GeoUri::parse("geo:0,90;crs=mars")?;
+        let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(
+            distance.distance(),
+            3_396_190.0 * core::f64::consts::FRAC_PI_2,
+            1e-3,
+        );
+
+        assert_eq!(
+            utrecht.distance_to(&geo_uri, GeodesicMethod::Ellipsoidal),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_distance_to_reference() -> Result<(), GeoUriBuilderError> {
+        for [lat1, lon1, lat2, lon2, distance, initial_azimuth, final_azimuth] in GEODESICS {
+            let geo_uri = GeoUri::builder().latitude(lat1).longitude(lon1).build()?;
+            let geo_uri2 = GeoUri::builder().latitude(lat2).longitude(lon2).build()?;
+            let solution = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+            assert_near(solution.distance(), distance, 1e-4);
+            assert_near(
+                wrap_degrees(solution.initial_azimuth() - initial_azimuth),
+                0.0,
+                1e-8,
+            );
+            assert_near(
+                wrap_degrees(solution.final_azimuth() - final_azimuth),
+                0.0,
+                1e-8,
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_distance_to_custom_crs() -> Result<(), GeoUriBuilderError> {
+        let mut builder = GeoUri::builder();
+        builder
+            .crs(CoordRefSystem::Custom(&WEST_EARTH))
+            .latitude(0.0)
+            .longitude(0.0);
+        let geo_uri = builder.build()?;
+        let geo_uri2 = builder.longitude(1.0).build()?;
+        let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 111_319.491, 1e-3);
+        assert_eq!(distance.initial_azimuth(), 270.0);
+        let distance = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Spherical)?;
+        assert_eq!(distance.initial_azimuth(), 270.0);
+
+        let geo_uri = builder.crs(CoordRefSystem::Custom(&GRID)).build()?;
+        assert_eq!(
+            geo_uri.distance_to(&geo_uri, GeodesicMethod::Spherical),
+            Err(Error::MissingEllipsoid)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_distance_3d_to() -> Result<(), Error> {
+        let geo_uri = GeoUri::parse("geo:0,0,100")?;
+        let geo_uri2 = GeoUri::parse("geo:0,0.001,-100")?;
+        let surface = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(surface.distance(), 111.319, 1e-3);
+        let distance = geo_uri.distance_3d_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance, 228.893, 1e-3);
+
+        let geo_uri2 = GeoUri::parse("geo:0,0.001")?;
+        let distance = geo_uri.distance_3d_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_eq!(distance, surface.distance());
+
+        let geo_uri2 = GeoUri::parse("geo:0,0.001,0;crs=moon")?;
+        assert_eq!(
+            geo_uri.distance_3d_to(&geo_uri2, GeodesicMethod::Spherical),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_destination() -> Result<(), Error> {
+        let wellington = GeoUri::parse("geo:-41.32,174.81,10;u=20;foo=bar")?;
+        let destination = wellington.destination(
+            19_959_679.267_353,
+            161.067_669_986,
+            GeodesicMethod::Ellipsoidal,
+        )?;
+        assert_near(destination.latitude(), 40.96, 1e-9);
+        assert_near(destination.longitude(), -5.50, 1e-9);
+        assert_eq!(destination.altitude(), Some(10.0));
+        assert_eq!(destination.uncertainty(), None);
+        assert_eq!(
+            destination.to_string(),
+            destination.normalized().to_string()
+        );
+
+        let geo_uri = GeoUri::parse("geo:0,0")?;
+        let destination = geo_uri.destination(WGS84_DEGREE, 270.0, GeodesicMethod::Spherical)?;
+        assert_near(destination.latitude(), 0.0, 1e-12);
+        assert_near(destination.longitude(), -1.0, 1e-6);
+        let destination = geo_uri.destination(-WGS84_DEGREE, 90.0, GeodesicMethod::Spherical)?;
+        assert_near(destination.longitude(), -1.0, 1e-6);
+        let destination = geo_uri.destination(0.0, 123.0, GeodesicMethod::Ellipsoidal)?;
+        assert_eq!(destination, geo_uri);
+        let destination = geo_uri.destination(0.0, 123.0, GeodesicMethod::Spherical)?;
+        assert_eq!(destination, geo_uri);
+
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let geo_uri = GeoUri::parse("geo:89,10")?;
+            let destination = geo_uri.destination(3.0e6, 0.0, method)?;
+            assert!(destination.latitude() < 89.0);
+            assert_near(destination.longitude(), -170.0, 1e-9);
+
+            let destination = geo_uri.destination(1.0e6, 90.0, method)?;
+            assert!(destination.latitude() < 89.0);
+            assert!(destination.longitude() > 90.0);
+
+            let distance = geo_uri.distance_to(&destination, method)?;
+            assert_near(distance.distance(), 1.0e6, 1e-6);
+            assert_near(distance.initial_azimuth(), 90.0, 1e-9);
+
+            let destination = geo_uri.destination(f64::NAN, 0.0, method);
+            assert_eq!(destination, Err(Error::OutOfRangeLatitude));
+        }
+
+        let geo_uri = GeoUri::parse("geo:4.5,359.5;crs=mars")?;
+        let destination = geo_uri.destination(100_000.0, 90.0, GeodesicMethod::Ellipsoidal)?;
+        assert!(destination.longitude() > 1.0 && destination.longitude() < 2.0);
+        assert_eq!(destination.crs(), CoordRefSystem::Mars);
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_destination_custom_crs() -> Result<(), GeoUriBuilderError> {
+        let mut builder = GeoUri::builder();
+        builder
+            .crs(CoordRefSystem::Custom(&WEST_EARTH))
+            .latitude(0.0)
+            .longitude(0.5);
+        let geo_uri = builder.build()?;
+        let destination = geo_uri.destination(111_319.491, 90.0, GeodesicMethod::Ellipsoidal)?;
+        assert_near(destination.longitude(), 359.5, 1e-6);
+        assert_eq!(destination.crs(), geo_uri.crs());
+
+        let geo_uri = builder.crs(CoordRefSystem::Custom(&GRID)).build()?;
+        assert_eq!(
+            geo_uri.destination(1.0, 0.0, GeodesicMethod::Spherical),
+            Err(Error::MissingEllipsoid)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_intermediate_points() -> Result<(), Error> {
+        let utrecht = GeoUri::parse("geo:52.0907,5.1214,0")?;
+        let paris = GeoUri::parse("geo:48.8530,2.3499,100")?;
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let distance = utrecht.distance_to(&paris, method)?.distance();
+            let midpoint = utrecht.midpoint(&paris, method)?;
+            assert_near(
+                utrecht.distance_to(&midpoint, method)?.distance(),
+                distance / 2.0,
+                1e-6,
+            );
+            assert_near(
+                paris.distance_to(&midpoint, method)?.distance(),
+                distance / 2.0,
+                1e-6,
+            );
+            assert_eq!(midpoint.altitude(), Some(50.0));
+
+            let start = utrecht.intermediate_point(&paris, 0.0, method)?;
+            assert!(start.approx_eq(&utrecht, 1e-6));
+            let end = utrecht.intermediate_point(&paris, 1.0, method)?;
+            assert!(end.approx_eq(&paris, 1e-6));
+            let beyond = utrecht.intermediate_point(&paris, 2.0, method)?;
+            assert_near(
+                paris.distance_to(&beyond, method)?.distance(),
+                distance,
+                1e-6,
+            );
+            assert_near(beyond.altitude().unwrap_or_default(), 200.0, 1e-9);
+
+            let points = utrecht.intermediate_points(&paris, 4, method)?;
+            assert_eq!(points.len(), 4);
+            let mut previous = &utrecht;
+            for point in points.iter().chain([&paris]) {
+                let part = previous.distance_to(point, method)?.distance();
+                assert_near(part, distance / 5.0, 1e-6);
+                previous = point;
+            }
+            assert_eq!(points[1].altitude(), Some(40.0));
+            assert!(utrecht.intermediate_points(&paris, 0, method)?.is_empty());
+        }
+
+        let geo_uri = GeoUri::parse("geo:10,170,100")?;
+        let geo_uri2 = GeoUri::parse("geo:10,-170")?;
+        let midpoint = geo_uri.midpoint(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert!(midpoint.latitude() > 10.0);
+        assert_near(midpoint.longitude().abs(), 180.0, 1e-9);
+        assert_eq!(midpoint.altitude(), None);
+
+        let geo_uri2 = GeoUri::parse("geo:10,-170;crs=moon")?;
+        assert_eq!(
+            geo_uri.midpoint(&geo_uri2, GeodesicMethod::Ellipsoidal),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_approx_eq() -> Result<(), Error> {
+        let geo_uri = GeoUri::parse("geo:0,0")?;
+        let geo_uri2 = GeoUri::parse("geo:0,1")?;
+        assert!(geo_uri.approx_eq(&geo_uri2, WGS84_DEGREE + 0.01));
+        assert!(!geo_uri.approx_eq(&geo_uri2, WGS84_DEGREE - 0.01));
+        assert!(geo_uri.approx_eq(&geo_uri, 0.0));
+        assert!(!geo_uri.approx_eq(&geo_uri, -1.0));
+
+        let geo_uri = GeoUri::parse("geo:0,179.5")?;
+        let geo_uri2 = GeoUri::parse("geo:0,-179.5")?;
+        assert!(geo_uri.approx_eq(&geo_uri2, WGS84_DEGREE + 0.01));
+
+        let geo_uri = GeoUri::parse("geo:90,5.134")?;
+        let geo_uri2 = GeoUri::parse("geo:90,-22.43")?;
+        assert!(geo_uri.approx_eq(&geo_uri2, 0.0));
+
+        let geo_uri = GeoUri::parse("geo:52.107,5.134,10")?;
+        let geo_uri2 = GeoUri::parse("geo:52.107,5.134,40")?;
+        assert!(geo_uri.approx_eq(&geo_uri2, 30.0));
+        assert!(!geo_uri.approx_eq(&geo_uri2, 29.9));
+        let geo_uri2 = GeoUri::parse("geo:52.107,5.134")?;
+        assert!(geo_uri.approx_eq(&geo_uri2, 0.0));
+
+        let geo_uri2 = GeoUri::parse("geo:52.107,5.134,10;crs=moon")?;
+        assert!(!geo_uri.approx_eq(&geo_uri2, 1_000_000.0));
+
+        let geo_uri = GeoUri::parse("geo:0,0;crs=moon")?;
+        let geo_uri2 = GeoUri::parse("geo:0,1;crs=moon")?;
+        let moon_degree = 1_737_400.0_f64 * 1.0_f64.to_radians();
+        assert!(geo_uri.approx_eq(&geo_uri2, moon_degree + 0.01));
+        assert!(!geo_uri.approx_eq(&geo_uri2, moon_degree - 0.01));
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_approx_eq_within_uncertainty() -> Result<(), Error> {
+        let mut geo_uri = GeoUri::parse("geo:0,0")?;
+        let mut geo_uri2 = GeoUri::parse("geo:0,1")?;
+        assert!(!geo_uri.approx_eq_within_uncertainty(&geo_uri2, 0.0));
+
+        geo_uri.set_uncertainty(Some(WGS84_DEGREE / 2.0))?;
+        assert!(!geo_uri.approx_eq_within_uncertainty(&geo_uri2, 0.0));
+        assert!(geo_uri.approx_eq_within_uncertainty(&geo_uri2, WGS84_DEGREE / 2.0 + 0.01));
+
+        geo_uri2.set_uncertainty(Some(WGS84_DEGREE / 2.0 + 0.01))?;
+        assert!(geo_uri.approx_eq_within_uncertainty(&geo_uri2, 0.0));
+        assert!(geo_uri2.approx_eq_within_uncertainty(&geo_uri, 0.0));
+
+        geo_uri.set_altitude(Some(0.0));
+        geo_uri2.set_altitude(Some(1_000.0));
+        assert!(!geo_uri.approx_eq_within_uncertainty(&geo_uri2, 0.0));
+        assert!(geo_uri.approx_eq_within_uncertainty(&geo_uri2, 5.0));
+
+        let geo_uri2 = GeoUri::parse("geo:0,0;crs=mars")?;
+        assert!(!geo_uri.approx_eq_within_uncertainty(&geo_uri2, 1_000_000.0));
+
+        Ok(())
+    }
+}
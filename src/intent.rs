@@ -0,0 +1,383 @@
+//! Android/Google style geo intent URIs.
+
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::encoding::{query_decode, QueryEncoded};
+use crate::{Error, GeoUri};
+
+/// The maximum zoom level of a geo intent URI.
+const MAX_ZOOM: u8 = 21;
+
+/// A geo URI in the form used by Android intents and many (map) apps.
+///
+/// Besides the location of a plain geo URI, these can have a query part with a zoom level (`z`)
+/// and a query (`q`) that is either a location with an optional label or a search text, e.g.
+/// `geo:0,0?q=52.107,5.134(Utrecht)`, `geo:52.107,5.134?z=14` or `geo:0,0?q=Dom+tower`.
+/// By convention, the location `0,0` is used when only a query is provided.
+///
+/// See the
+/// [Android documentation](https://developer.android.com/guide/components/intents-common#Maps)
+/// for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{GeoIntent, GeoQuery, GeoUri};
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let geo_intent = GeoIntent::parse("geo:0,0?q=52.107,5.134(Utrecht)&z=14")?;
+/// assert_eq!(geo_intent.zoom(), Some(14));
+/// assert_eq!(geo_intent.label(), Some("Utrecht"));
+/// assert_eq!(geo_intent.to_geo_uri(), Some(GeoUri::parse("geo:52.107,5.134")?));
+///
+/// let geo_intent = GeoIntent::parse("geo:0,0?q=Dom+tower")?;
+/// assert_eq!(geo_intent.query(), Some(&GeoQuery::Search(String::from("Dom tower"))));
+/// assert_eq!(geo_intent.to_geo_uri(), None);
+///
+/// let mut geo_intent = GeoIntent::new(GeoUri::parse("geo:52.107,5.134")?);
+/// geo_intent.set_zoom(Some(14))?;
+/// assert_eq!(geo_intent.to_string(), "geo:52.107,5.134?z=14");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoIntent {
+    /// The location of the geo URI part.
+    location: GeoUri,
+
+    /// The zoom level, if provided.
+    zoom: Option<u8>,
+
+    /// The query, if provided.
+    query: Option<GeoQuery>,
+}
+
+/// The query (`q`) of a [`GeoIntent`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeoQuery {
+    /// A location with an optional label, e.g. `q=52.107,5.134(Utrecht)`.
+    Location {
+        /// The queried location.
+        location: GeoUri,
+
+        /// The label of the location, if provided.
+        label: Option<String>,
+    },
+
+    /// A search text, e.g. `q=Dom+tower`.
+    Search(String),
+}
+
+impl GeoIntent {
+    /// Creates a geo intent URI for the location without a zoom level or query.
+    pub fn new(location: GeoUri) -> Self {
+        Self {
+            location,
+            ..Default::default()
+        }
+    }
+
+    /// Try parsing a geo intent URI string into a `GeoIntent`.
+    ///
+    /// The geo URI part before the query part is parsed using [`GeoUri::parse`]. Only the `z`
+    /// and `q` query parameters are recognized, other query parameters are ignored.
+    /// A query consisting of a latitude and longitude, optionally followed by a label in
+    /// parentheses, is a [`GeoQuery::Location`], any other query is a [`GeoQuery::Search`].
+    /// Like on Android, a query with invalid coordinates, e.g. `q=nan,5`, is also a search.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if parsing the geo URI part fails or if the zoom level is invalid.
+    pub fn parse(uri: &str) -> Result<Self, Error> {
+        let (geo_uri, query) = uri.split_once('?').unwrap_or((uri, ""));
+        let mut geo_intent = Self::new(GeoUri::parse(geo_uri)?);
+
+        for part in query.split('&') {
+            let (name, value) = part.split_once('=').unwrap_or((part, ""));
+            match name {
+                "z" => {
+                    let zoom = value.parse().map_err(|_| Error::InvalidZoom)?;
+                    geo_intent.set_zoom(Some(zoom))?;
+                }
+                "q" if !value.is_empty() => {
+                    let query = GeoQuery::parse(&query_decode(value)?);
+                    geo_intent.set_query(Some(query));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(geo_intent)
+    }
+
+    /// Returns the location of the geo URI part.
+    ///
+    /// Note that this is `0,0` by convention if only a query is provided, see
+    /// [`GeoIntent::to_geo_uri`] for the location the geo intent URI refers to.
+    pub fn location(&self) -> &GeoUri {
+        &self.location
+    }
+
+    /// Changes the location of the geo URI part.
+    pub fn set_location(&mut self, location: GeoUri) {
+        self.location = location;
+    }
+
+    /// Returns the zoom level (if any).
+    pub fn zoom(&self) -> Option<u8> {
+        self.zoom
+    }
+
+    /// Changes the zoom level.
+    ///
+    /// # Errors
+    ///
+    /// If the zoom level is out of range of `0..=21`, an error will be returned.
+    pub fn set_zoom(&mut self, zoom: Option<u8>) -> Result<(), Error> {
+        if zoom.is_some_and(|zoom| zoom > MAX_ZOOM) {
+            return Err(Error::InvalidZoom);
+        }
+        self.zoom = zoom;
+
+        Ok(())
+    }
+
+    /// Returns the query (if any).
+    pub fn query(&self) -> Option<&GeoQuery> {
+        self.query.as_ref()
+    }
+
+    /// Changes the query.
+    pub fn set_query(&mut self, query: Option<GeoQuery>) {
+        self.query = query;
+    }
+
+    /// Returns the label of the queried location (if any).
+    pub fn label(&self) -> Option<&str> {
+        match &self.query {
+            Some(GeoQuery::Location { label, .. }) => label.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the location the geo intent URI refers to (if any).
+    ///
+    /// This is the queried location if the query is a location. If the query is a search text
+    /// and the location of the geo URI part is `0,0`, there is no location. Otherwise, it is
+    /// the location of the geo URI part.
+    pub fn to_geo_uri(&self) -> Option<GeoUri> {
+        match &self.query {
+            Some(GeoQuery::Location { location, .. }) => Some(location.clone()),
+            Some(GeoQuery::Search(_))
+                if self.location.latitude() == 0.0 && self.location.longitude() == 0.0 =>
+            {
+                None
+            }
+            _ => Some(self.location.clone()),
+        }
+    }
+}
+
+impl GeoQuery {
+    /// Parses a percent-decoded query, which is a search if it is not a valid location.
+    fn parse(query: &str) -> Self {
+        let query = query.trim();
+        let (coords, label) = match query.strip_suffix(')').and_then(|q| q.split_once('(')) {
+            Some((coords, label)) => (coords.trim_end(), Some(label)),
+            None => (query, None),
+        };
+        let location = coords.split_once(',').and_then(|(lat, lon)| {
+            let coords: (f64, f64) = (lat.trim().parse().ok()?, lon.trim().parse().ok()?);
+            GeoUri::try_from(coords).ok()
+        });
+
+        match location {
+            Some(location) => Self::Location {
+                location,
+                label: label.map(String::from),
+            },
+            None => Self::Search(String::from(query)),
+        }
+    }
+}
+
+impl fmt::Display for GeoIntent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.location)?;
+
+        let mut separator = '?';
+        if let Some(zoom) = self.zoom {
+            write!(f, "{separator}z={zoom}")?;
+            separator = '&';
+        }
+
+        match &self.query {
+            Some(GeoQuery::Location { location, label }) => {
+                let (latitude, longitude) = (location.latitude(), location.longitude());
+                write!(f, "{separator}q={latitude},{longitude}")?;
+                if let Some(label) = label {
+                    write!(f, "({})", QueryEncoded(label))?;
+                }
+            }
+            Some(GeoQuery::Search(text)) => write!(f, "{separator}q={}", QueryEncoded(text))?,
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl From<GeoUri> for GeoIntent {
+    fn from(location: GeoUri) -> Self {
+        Self::new(location)
+    }
+}
+
+impl FromStr for GeoIntent {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for GeoIntent {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geo_intent_parse() -> Result<(), Error> {
+        let geo_intent = GeoIntent::parse("geo:52.107,5.134")?;
+        assert_eq!(geo_intent.location(), &GeoUri::parse("geo:52.107,5.134")?);
+        assert_eq!(geo_intent.zoom(), None);
+        assert_eq!(geo_intent.query(), None);
+
+        let geo_intent = GeoIntent::parse("geo:52.107,5.134;u=10?z=14")?;
+        assert_eq!(geo_intent.location().uncertainty(), Some(10.0));
+        assert_eq!(geo_intent.zoom(), Some(14));
+
+        let geo_intent = GeoIntent::parse("geo:0,0?q=52.107,5.134(Dom%20tower)")?;
+        assert_eq!(
+            geo_intent.query(),
+            Some(&GeoQuery::Location {
+                location: GeoUri::parse("geo:52.107,5.134")?,
+                label: Some(String::from("Dom tower"))
+            })
+        );
+        assert_eq!(geo_intent.label(), Some("Dom tower"));
+
+        let geo_intent = GeoIntent::parse("geo:0,0?q=52.107+,+5.134+(Utrecht+(NL))&foo=bar")?;
+        assert_eq!(geo_intent.label(), Some("Utrecht (NL)"));
+        assert_eq!(
+            geo_intent.to_geo_uri(),
+            Some(GeoUri::parse("geo:52.107,5.134")?)
+        );
+
+        let geo_intent = GeoIntent::parse("geo:0,0?q=-33.8567,151.2153")?;
+        assert_eq!(geo_intent.label(), None);
+        assert_eq!(
+            geo_intent.to_geo_uri(),
+            Some(GeoUri::parse("geo:-33.8567,151.2153")?)
+        );
+
+        let geo_intent = GeoIntent::parse("geo:0,0?q=1600+Amphitheatre+Parkway%2C+CA")?;
+        assert_eq!(
+            geo_intent.query(),
+            Some(&GeoQuery::Search(String::from(
+                "1600 Amphitheatre Parkway, CA"
+            )))
+        );
+
+        let geo_intent = GeoIntent::parse("geo:0,0?q=100,5.134")?;
+        assert_eq!(
+            geo_intent.query(),
+            Some(&GeoQuery::Search(String::from("100,5.134")))
+        );
+        let geo_intent = GeoIntent::parse("geo:0,0?q=nan,5(Nowhere)")?;
+        assert_eq!(
+            geo_intent.query(),
+            Some(&GeoQuery::Search(String::from("nan,5(Nowhere)")))
+        );
+        assert_eq!(geo_intent.to_geo_uri(), None);
+
+        assert_eq!(
+            GeoIntent::parse("geo:52.107,5.134?z=22"),
+            Err(Error::InvalidZoom)
+        );
+        assert_eq!(
+            GeoIntent::parse("geo:52.107,5.134?z=far"),
+            Err(Error::InvalidZoom)
+        );
+        assert_eq!(
+            GeoIntent::parse("52.107,5.134?z=14"),
+            Err(Error::MissingScheme)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_intent_to_geo_uri() -> Result<(), Error> {
+        let geo_intent = GeoIntent::parse("geo:52.107,5.134?q=coffee")?;
+        assert_eq!(
+            geo_intent.to_geo_uri(),
+            Some(GeoUri::parse("geo:52.107,5.134")?)
+        );
+
+        let geo_intent = GeoIntent::parse("geo:0,0?q=coffee")?;
+        assert_eq!(geo_intent.to_geo_uri(), None);
+
+        let geo_intent = GeoIntent::parse("geo:0,0")?;
+        assert_eq!(geo_intent.to_geo_uri(), Some(GeoUri::parse("geo:0,0")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_intent_display() -> Result<(), Error> {
+        let mut geo_intent = GeoIntent::from(GeoUri::parse("geo:52.107,5.134")?);
+        assert_eq!(geo_intent.to_string(), "geo:52.107,5.134");
+
+        geo_intent.set_zoom(Some(14))?;
+        assert_eq!(geo_intent.to_string(), "geo:52.107,5.134?z=14");
+        assert_eq!(geo_intent.set_zoom(Some(22)), Err(Error::InvalidZoom));
+
+        geo_intent.set_query(Some(GeoQuery::Search(String::from("Dom tower & more+"))));
+        assert_eq!(
+            geo_intent.to_string(),
+            "geo:52.107,5.134?z=14&q=Dom%20tower%20%26%20more%2B"
+        );
+
+        geo_intent.set_location(GeoUri::default());
+        geo_intent.set_zoom(None)?;
+        geo_intent.set_query(Some(GeoQuery::Location {
+            location: GeoUri::parse("geo:52.107,5.134")?,
+            label: Some(String::from("Dom (tower)")),
+        }));
+        assert_eq!(
+            geo_intent.to_string(),
+            "geo:0,0?q=52.107,5.134(Dom%20%28tower%29)"
+        );
+
+        // Round-trip through parsing.
+        assert_eq!(GeoIntent::parse(&geo_intent.to_string())?, geo_intent);
+        assert_eq!(geo_intent.to_string().parse::<GeoIntent>()?, geo_intent);
+        assert_eq!(
+            GeoIntent::try_from("geo:0,0?q=x")?.to_string(),
+            "geo:0,0?q=x"
+        );
+
+        Ok(())
+    }
+}
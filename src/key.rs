@@ -0,0 +1,246 @@
+//! Keys for hashing and ordering geo URIs.
+
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use crate::normalize::unsigned_zero;
+use crate::{CoordRefSystem, Crs, GeoUri};
+
+/// A canonical key of a geo URI that can be hashed and totally ordered.
+///
+/// Two keys are equal if and only if the geo URIs they are made from are equal according to
+/// [`PartialEq`]: the longitude is ignored where the coordinate reference system says so (e.g. at
+/// the poles for WGS-84), `-0` and `0` are equal and the other parameters are not taken into
+/// account. This makes it suitable as key of a `HashMap` or `BTreeMap`.
+///
+/// Keys are ordered by coordinate reference system, latitude, longitude, altitude and
+/// uncertainty, where a missing altitude or uncertainty comes first.
+///
+/// Use [`GeoUri::key`] to get the key of a geo URI.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashSet;
+///
+/// use geo_uri::GeoUri;
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let geo_uris = [
+///     GeoUri::parse("geo:90,5.134")?,
+///     GeoUri::parse("geo:90,0")?,
+///     GeoUri::parse("geo:52.107,-0")?,
+///     GeoUri::parse("geo:52.107,0")?,
+/// ];
+/// let keys: HashSet<_> = geo_uris.iter().filter_map(GeoUri::key).collect();
+/// assert_eq!(keys.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+// Not `Copy`, like `GeoUri`, so that it can hold more than numbers without breaking changes.
+#[allow(missing_copy_implementations)]
+#[derive(Clone, Debug)]
+pub struct GeoUriKey {
+    /// The coordinate reference system.
+    crs: CoordRefSystem,
+
+    /// The latitude coordinate, without negative zero.
+    latitude: f64,
+
+    /// The longitude coordinate, without negative zero and `0.0` where it is ignored.
+    longitude: f64,
+
+    /// The altitude coordinate, without negative zero.
+    altitude: Option<f64>,
+
+    /// The uncertainty, without negative zero.
+    uncertainty: Option<f64>,
+}
+
+impl GeoUriKey {
+    /// Returns the numeric components of the key.
+    fn nums(&self) -> [Option<f64>; 4] {
+        [
+            Some(self.latitude),
+            Some(self.longitude),
+            self.altitude,
+            self.uncertainty,
+        ]
+    }
+}
+
+impl PartialEq for GeoUriKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for GeoUriKey {}
+
+impl Hash for GeoUriKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.crs.hash(state);
+        for num in self.nums() {
+            num.map(f64::to_bits).hash(state);
+        }
+    }
+}
+
+impl PartialOrd for GeoUriKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GeoUriKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let nums = self.nums().into_iter().zip(other.nums());
+        nums.fold(self.crs.cmp(&other.crs), |ordering, nums| {
+            ordering.then_with(|| match nums {
+                (Some(num), Some(other_num)) => num.total_cmp(&other_num),
+                (num, other_num) => num.is_some().cmp(&other_num.is_some()),
+            })
+        })
+    }
+}
+
+impl GeoUri {
+    /// Returns a canonical key of the geo URI that can be hashed and totally ordered.
+    ///
+    /// See [`GeoUriKey`] for details. There is no key if the altitude or uncertainty is NaN,
+    /// because such a geo URI is not even equal to itself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:-90,5.134;u=-0")?;
+    /// let geo_uri2 = GeoUri::parse("geo:-90,-22.43;u=0")?;
+    /// assert_eq!(geo_uri.key(), geo_uri2.key());
+    ///
+    /// let mut geo_uri = GeoUri::parse("geo:52.107,5.134")?;
+    /// geo_uri.set_altitude(Some(f64::NAN));
+    /// assert_eq!(geo_uri.key(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn key(&self) -> Option<GeoUriKey> {
+        let nums = [
+            Some(self.latitude),
+            Some(self.longitude),
+            self.altitude,
+            self.uncertainty,
+        ];
+        if nums.into_iter().flatten().any(f64::is_nan) {
+            return None;
+        }
+
+        let longitude = if self.crs.is_longitude_ignored(self.latitude) {
+            0.0
+        } else {
+            unsigned_zero(self.longitude)
+        };
+
+        Some(GeoUriKey {
+            crs: self.crs,
+            latitude: unsigned_zero(self.latitude),
+            longitude,
+            altitude: self.altitude.map(unsigned_zero),
+            uncertainty: self.uncertainty.map(unsigned_zero),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::*;
+    use crate::Error;
+
+    /// Returns the key of a geo URI string.
+    fn key(uri: &str) -> Result<Option<GeoUriKey>, Error> {
+        Ok(GeoUri::parse(uri)?.key())
+    }
+
+    #[test]
+    fn geo_uri_key() -> Result<(), Error> {
+        assert_eq!(key("geo:52.107,5.134")?, key("geo:52.1070,5.134")?);
+        assert_eq!(key("geo:-0,-0,-0;u=-0")?, key("geo:0,0,0;u=0")?);
+        assert_eq!(key("geo:90,5.134")?, key("geo:90,-180")?);
+        assert_ne!(key("geo:52.107,5.134")?, key("geo:52.107,5.134,0")?);
+        assert_ne!(key("geo:52.107,180")?, key("geo:52.107,-180")?);
+        assert_ne!(key("geo:4.5,137.4")?, key("geo:4.5,137.4;crs=mars")?);
+
+        let mut geo_uri = GeoUri::parse("geo:52.107,5.134")?;
+        geo_uri.set_altitude(Some(f64::NAN));
+        assert_eq!(geo_uri.key(), None);
+        geo_uri.set_altitude(Some(f64::INFINITY));
+        assert!(geo_uri.key().is_some());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn geo_uri_key_params() -> Result<(), Error> {
+        // The other parameters are ignored, like when comparing geo URIs.
+        assert_eq!(
+            key("geo:52.107,5.134;foo=bar;Baz")?,
+            key("geo:52.107,5.134;baz;FOO=bar")?
+        );
+        assert_eq!(
+            key("geo:52.107,5.134;foo=bar")?,
+            key("geo:52.107,5.134;foo=Bar")?
+        );
+        assert_eq!(key("geo:52.107,5.134;foo=bar")?, key("geo:52.107,5.134")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_key_hash_ord() -> Result<(), Error> {
+        let uris = [
+            "geo:52.107,5.134,3.6",
+            "geo:-0,-0",
+            "geo:90,5.134;u=10",
+            "geo:52.107,5.134",
+            "geo:0,0",
+            "geo:90,-22.43;u=10",
+            "geo:1,2;crs=moon",
+            "geo:52.107,5.134;u=10",
+        ];
+        let mut geo_uris = Vec::new();
+        for uri in uris {
+            geo_uris.push(GeoUri::parse(uri)?);
+        }
+
+        let hashed: HashSet<_> = geo_uris.iter().filter_map(GeoUri::key).collect();
+        assert_eq!(hashed.len(), 6);
+
+        let ordered: BTreeSet<_> = geo_uris.iter().filter_map(GeoUri::key).collect();
+        assert_eq!(ordered.len(), 6);
+        for (key, other_key) in ordered.iter().zip(ordered.iter().skip(1)) {
+            assert_eq!(key.cmp(other_key), Ordering::Less);
+        }
+        let first = ordered.first().expect("a key");
+        assert_eq!(Some(first), GeoUri::parse("geo:0,0")?.key().as_ref());
+        let last = ordered.last().expect("a key");
+        assert_eq!(
+            Some(last),
+            GeoUri::parse("geo:1,2;crs=moon")?.key().as_ref()
+        );
+
+        for geo_uri in &geo_uris {
+            for other in &geo_uris {
+                assert_eq!(geo_uri == other, geo_uri.key() == other.key());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 #![warn(
@@ -16,9 +17,47 @@
 )]
 #![deny(missing_docs)]
 
-use std::fmt;
-use std::num::ParseFloatError;
-use std::str::FromStr;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod bounding_box;
+#[cfg(feature = "std")]
+mod centroid;
+#[cfg(feature = "std")]
+mod circle;
+mod crs;
+#[cfg(feature = "std")]
+mod ecef;
+mod encoding;
+mod format;
+#[cfg(feature = "std")]
+mod geodesic;
+#[cfg(feature = "std")]
+mod geodesy;
+#[cfg(feature = "alloc")]
+mod intent;
+mod key;
+#[cfg(feature = "url")]
+mod map_url;
+mod normalize;
+mod parser;
+#[cfg(feature = "std")]
+mod rhumb;
+mod scan;
+#[cfg(all(test, feature = "std"))]
+pub(crate) mod test_util;
+#[cfg(feature = "std")]
+mod track;
+mod uri_ref;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::num::ParseFloatError;
+use core::str::FromStr;
 
 #[cfg(feature = "serde")]
 use serde_core::{
@@ -29,12 +68,61 @@ use thiserror::Error;
 #[cfg(feature = "url")]
 use url::Url;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use crate::bounding_box::BoundingBox;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use crate::centroid::Weighting;
+pub use crate::crs::{Axis, Crs, Ellipsoid, LatitudeConvention, LongitudeConvention};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use crate::ecef::{Ecef, Enu, Ned};
+use crate::format::Decimals;
+pub use crate::format::{DisplayWith, FormatOptions, Precision};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use crate::geodesy::{Distance, GeodesicMethod};
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use crate::intent::{GeoIntent, GeoQuery};
+pub use crate::key::GeoUriKey;
+#[cfg(feature = "url")]
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+pub use crate::map_url::MapProvider;
+pub use crate::normalize::Normalization;
+pub use crate::parser::{Component, Diagnostic, ParseError, ParseMode, ParseOptions};
+#[cfg(feature = "alloc")]
+pub use crate::scan::replace_all;
+pub use crate::scan::{find_iter, Match, Matches};
+pub use crate::uri_ref::{GeoUriRef, Params};
+
 /// The scheme name of a geo URI.
 const URI_SCHEME_NAME: &str = "geo";
 
 /// Possible geo URI errors.
-#[derive(Debug, Error, Eq, PartialEq)]
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
 pub enum Error {
+    /// The geo URI contains a parameter more than once.
+    ///
+    /// This is only returned when parsing in strict mode.
+    #[error("Duplicate parameter in geo URI")]
+    DuplicateParam,
+
+    /// The centroid of the geo URIs is indeterminate.
+    ///
+    /// This is returned if the locations are spread evenly around the reference ellipsoid, e.g.
+    /// if they are antipodal, or if the iterative computation of the centroid does not converge.
+    #[error("Indeterminate centroid of geo URIs")]
+    IndeterminateCentroid,
+
+    /// The closest point on a geodesic to a location is indeterminate.
+    ///
+    /// This is returned if finding it does not converge, which can happen if the location is
+    /// (nearly) a quarter of the circumference away from the geodesic in all directions.
+    #[error("Indeterminate closest point on geodesic")]
+    IndeterminateClosestPoint,
+
     /// The geo URI contains an unparsable/invalid coordinate.
     #[error("Invalid coordinate in geo URI: {0}")]
     InvalidCoord(ParseFloatError),
@@ -43,14 +131,67 @@ pub enum Error {
     #[error("Invalid coordinate reference system")]
     InvalidCoordRefSystem,
 
+    /// The geo URI contains a coordinate that does not follow the RFC 5870 number syntax.
+    ///
+    /// This is only returned when parsing in strict mode.
+    #[error("Invalid coordinate syntax in geo URI")]
+    InvalidCoordSyntax,
+
+    /// The geo URI contains a parameter with an invalid name or value.
+    ///
+    /// When parsing, this is only returned in strict mode.
+    #[error("Invalid parameter in geo URI")]
+    InvalidParam,
+
     /// The geo URI contains an unparsable/invalid uncertainty distance.
     #[error("Invalid distance in geo URI: {0}")]
     InvalidUncertainty(ParseFloatError),
 
+    /// The geo URI contains an uncertainty distance that does not follow the RFC 5870 number
+    /// syntax.
+    ///
+    /// This is only returned when parsing in strict mode.
+    #[error("Invalid distance syntax in geo URI")]
+    InvalidUncertaintySyntax,
+
+    /// The geo intent URI or map provider URL contains an unparsable zoom level or one that is
+    /// out of range.
+    ///
+    /// The range is `0..=21` for geo intent URIs and `0..=22` for map provider URLs.
+    #[error("Invalid zoom level")]
+    InvalidZoom,
+
+    /// The geo URIs have different coordinate reference systems.
+    ///
+    /// This is returned by computations involving multiple geo URIs.
+    #[error("Mismatched coordinate reference systems of geo URIs")]
+    MismatchedCoordRefSystem,
+
+    /// The geo URI contains a `crs` or `u` parameter that is not in the required position.
+    ///
+    /// The `crs` parameter must come first, followed by the `u` parameter and then the other
+    /// parameters.
+    /// This is only returned when parsing in strict mode.
+    #[error("Misplaced parameter in geo URI")]
+    MisplacedParam,
+
     /// The geo URI contains no coordinates.
     #[error("Missing coordinates in geo URI")]
     MissingCoords,
 
+    /// The coordinate reference system has no reference ellipsoid.
+    ///
+    /// This is returned by computations that need a reference ellipsoid, see
+    /// [`Crs::ellipsoid`].
+    #[error("Missing reference ellipsoid of coordinate reference system")]
+    MissingEllipsoid,
+
+    /// No geo URIs were given.
+    ///
+    /// This is returned by computations involving a path or collection of geo URIs.
+    #[error("Missing geo URIs")]
+    MissingGeoUris,
+
     /// The geo URI lacks the latitude coordinate.
     #[error("Missing latitude coordinate in geo URI")]
     MissingLatitude,
@@ -63,40 +204,115 @@ pub enum Error {
     #[error("Missing geo URI scheme")]
     MissingScheme,
 
+    /// A geo URI has no uncertainty.
+    ///
+    /// This is returned by computations that need the uncertainty of geo URIs.
+    #[error("Missing uncertainty")]
+    MissingUncertainty,
+
     /// The latitude coordinate is out of range of `-90.0..=90.0` degrees.
     ///
-    /// This can only fail for the WGS-84 coordinate reference system.
+    /// For other coordinate reference systems than WGS-84, the range of the first coordinate
+    /// is determined by the coordinate reference system.
     #[error("Latitude coordinate is out of range")]
     OutOfRangeLatitude,
 
     /// The longitude coordinate is out of range of `-180.0..=180.0` degrees.
     ///
-    /// This can only fail for the WGS-84 coordinate reference system.
+    /// For other coordinate reference systems than WGS-84, the range of the second coordinate
+    /// is determined by the coordinate reference system.
     #[error("Longitude coordinate is out of range")]
     OutOfRangeLongitude,
 
     /// The uncertainty distance is not positive.
     #[error("Uncertainty distance not positive")]
     OutOfRangeUncertainty,
+
+    /// The URL is not a link of a supported map provider.
+    #[error("Unrecognized map provider URL")]
+    UnrecognizedUrl,
 }
 
 /// The reference system of the provided coordinates.
 ///
-/// Currently only the `WGS-84` coordinate reference system is supported.
+/// By default, the `WGS-84` coordinate reference system is used.
 /// It defines the latitude and longitude of the [`GeoUri`] to be in decimal degrees and the
 /// altitude in meters.
+/// Besides that, planetocentric coordinate reference systems for some other celestial bodies
+/// based on the IAU 2015 body-fixed systems are built-in. Their latitude and longitude are also
+/// in decimal degrees, with the longitudes increasing eastwards, and the altitude in meters
+/// above the reference [`Ellipsoid`] of the body.
+/// Other coordinate reference systems can be provided by implementing the [`Crs`] trait, which
+/// this type also implements.
 ///
 /// For more details see the
 /// [component description](ttps://www.rfc-editor.org/rfc/rfc5870#section-3.4.2) in
 /// [RFC 5870](https://www.rfc-editor.org/rfc/rfc5870).
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{CoordRefSystem, Crs, GeoUri};
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let geo_uri = GeoUri::parse("geo:-4.5895,137.4417;crs=mars")?;
+/// assert_eq!(geo_uri.crs(), CoordRefSystem::Mars);
+/// assert_eq!(geo_uri.crs().ellipsoid().map(|e| e.equatorial_radius()), Some(3_396_190.0));
+///
+/// // Longitudes on Mars are in the range `0.0..=360.0` degrees.
+/// assert!(GeoUri::parse("geo:-4.5895,-137.4417;crs=mars").is_err());
+/// # Ok(())
+/// # }
+/// ```
 #[non_exhaustive]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default)]
 pub enum CoordRefSystem {
     /// The WGS-84 coordinate reference system.
+    #[default]
     Wgs84,
+
+    /// The planetocentric coordinate reference system of Mercury (IAU 2015 code 19902).
+    ///
+    /// Longitudes are in the range `0.0..=360.0` degrees. Its name is `mercury`. As the
+    /// reference ellipsoid is flattened, the planetocentric latitude differs from the geodetic
+    /// latitude, see [`LatitudeConvention`].
+    Mercury,
+
+    /// The planetocentric coordinate reference system of Venus (IAU 2015 code 29900).
+    ///
+    /// Longitudes are in the range `0.0..=360.0` degrees. Its name is `venus`.
+    Venus,
+
+    /// The planetocentric coordinate reference system of the Moon (IAU 2015 code 30100).
+    ///
+    /// Longitudes are in the range `-180.0..=180.0` degrees. Its name is `moon`.
+    Moon,
+
+    /// The planetocentric coordinate reference system of Mars (IAU 2015 code 49902).
+    ///
+    /// Longitudes are in the range `0.0..=360.0` degrees. Its name is `mars`. As the
+    /// reference ellipsoid is flattened, the planetocentric latitude differs from the geodetic
+    /// latitude, see [`LatitudeConvention`].
+    Mars,
+
+    /// A custom coordinate reference system.
+    ///
+    /// Like all coordinate reference systems, it is identified by its case-insensitive name, so
+    /// it is equal to a built-in one with the same name. See [`Crs`] for an example.
+    Custom(&'static dyn Crs),
 }
 
 impl CoordRefSystem {
+    /// The built-in coordinate reference systems.
+    const BUILT_IN: [Self; 5] = [
+        Self::Wgs84,
+        Self::Mercury,
+        Self::Venus,
+        Self::Moon,
+        Self::Mars,
+    ];
+
     /// Validates geolocation coordinates against the selected coordinate reference system.
     ///
     /// # Examples
@@ -120,23 +336,70 @@ impl CoordRefSystem {
     /// An error is returned if the latitude/longitude is out of range with respect to the
     /// coordinate reference system.
     pub fn validate(&self, latitude: f64, longitude: f64) -> Result<(), Error> {
-        // This holds only for WGS-84, but it is the only one supported right now!
-        if !(-90.0..=90.0).contains(&latitude) {
-            return Err(Error::OutOfRangeLatitude);
+        Crs::validate(self, latitude, longitude)
+    }
+
+    /// Returns the position of the variant in the declaration of the enum.
+    ///
+    /// A custom coordinate reference system with the name of a built-in one has the position of
+    /// the built-in one.
+    fn ordinal(&self) -> u8 {
+        match self {
+            Self::Wgs84 => 0,
+            Self::Mercury => 1,
+            Self::Venus => 2,
+            Self::Moon => 3,
+            Self::Mars => 4,
+            Self::Custom(crs) => Self::from_name(crs.name()).map_or(5, |crs| crs.ordinal()),
         }
+    }
+
+    /// Looks up a built-in coordinate reference system by its (case-insensitive) name.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Self::BUILT_IN
+            .into_iter()
+            .find(|crs| crs.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl PartialEq for CoordRefSystem {
+    fn eq(&self, other: &Self) -> bool {
+        // Coordinate reference systems are identified by their name, which is also what ends up
+        // in the `crs` parameter.
+        self.name().eq_ignore_ascii_case(other.name())
+    }
+}
 
-        // This holds only for WGS-84, but it is the only one supported right now!
-        if !(-180.0..=180.0).contains(&longitude) {
-            return Err(Error::OutOfRangeLongitude);
+impl Eq for CoordRefSystem {}
+
+impl Hash for CoordRefSystem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let ordinal = self.ordinal();
+        ordinal.hash(state);
+        if ordinal == 5 {
+            let name = self.name();
+            name.len().hash(state);
+            for b in name.bytes() {
+                b.to_ascii_lowercase().hash(state);
+            }
         }
+    }
+}
 
-        Ok(())
+impl PartialOrd for CoordRefSystem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl Default for CoordRefSystem {
-    fn default() -> Self {
-        Self::Wgs84
+impl Ord for CoordRefSystem {
+    /// Orders the built-in coordinate reference systems in the order of their declaration,
+    /// followed by the custom ones ordered by their case-insensitive name.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.ordinal().cmp(&other.ordinal()).then_with(|| {
+            let name = self.name().bytes().map(|b| b.to_ascii_lowercase());
+            name.cmp(other.name().bytes().map(|b| b.to_ascii_lowercase()))
+        })
     }
 }
 
@@ -232,7 +495,9 @@ impl Default for CoordRefSystem {
 /// # See also
 ///
 /// For the proposed IEEE standard, see [RFC 5870](https://www.rfc-editor.org/rfc/rfc5870).
-#[derive(Copy, Clone, Debug, Default)]
+// Never `Copy`, not even without the `alloc` feature, so that enabling it is additive.
+#[allow(missing_copy_implementations)]
+#[derive(Clone, Debug, Default)]
 pub struct GeoUri {
     /// The coordinate reference system used by the coordinates of this URI.
     crs: CoordRefSystem,
@@ -256,6 +521,16 @@ pub struct GeoUri {
     ///
     /// This distance needs to be positive.
     uncertainty: Option<f64>,
+
+    /// The number of decimal places of the coordinates and uncertainty in their original
+    /// textual representation, if known.
+    decimals: Decimals,
+
+    /// The other parameters of the URI, in order, as name and value pairs.
+    ///
+    /// Parameters without a value have an empty value.
+    #[cfg(feature = "alloc")]
+    params: Vec<(String, String)>,
 }
 
 impl GeoUri {
@@ -268,78 +543,68 @@ impl GeoUri {
     ///
     /// For the geo URI scheme syntax, see the proposed IEEE standard
     /// [RFC 5870](https://www.rfc-editor.org/rfc/rfc5870#section-3.3).
+    /// This parses in lenient mode, see [`ParseMode::Lenient`] for details, use
+    /// [`GeoUri::parse_with`] to parse in strict mode.
     ///
     /// # Errors
     ///
     /// Will return an error if the parsing fails in any way.
     pub fn parse(uri: &str) -> Result<Self, Error> {
-        let uri = uri.to_ascii_lowercase();
-        let uri_path = uri.strip_prefix("geo:").ok_or(Error::MissingScheme)?;
-        let mut parts = uri_path.split(';');
-
-        // Parse the coordinate part.
-        let coords_part = parts.next().expect("Split always yields at least one part");
-        // Don't iterate over anything if the coordinate part is empty!
-        let mut coords = if coords_part.is_empty() {
-            return Err(Error::MissingCoords);
-        } else {
-            coords_part.splitn(3, ',')
-        };
-        let latitude = coords
-            .next()
-            .ok_or(Error::MissingLatitude) // This cannot really happen
-            .and_then(|lat_s| lat_s.parse().map_err(Error::InvalidCoord))?;
-
-        let longitude = coords
-            .next()
-            .ok_or(Error::MissingLongitude)
-            .and_then(|lon_s| lon_s.parse().map_err(Error::InvalidCoord))?;
-
-        let altitude = coords
-            .next()
-            .map(|alt_s| alt_s.parse().map_err(Error::InvalidCoord))
-            .transpose()?;
-
-        // Parse the remaining (parameters) parts.
-        //
-        // TODO: Handle percent encoding of the parameters.
-        //
-        // If the "crs" parameter is passed, its value must be "wgs84" or it is unsupported.
-        // It can be followed by a "u" parameter or that can be the first one.
-        // All other parameters are ignored.
-        let mut param_parts = parts.flat_map(|part| part.split_once('='));
-        let (crs, uncertainty) = match param_parts.next() {
-            Some(("crs", value)) => {
-                if value != "wgs84" {
-                    return Err(Error::InvalidCoordRefSystem);
-                }
-
-                match param_parts.next() {
-                    Some(("u", value)) => (
-                        CoordRefSystem::Wgs84,
-                        Some(value.parse().map_err(Error::InvalidUncertainty)?),
-                    ),
-                    Some(_) | None => (CoordRefSystem::Wgs84, None),
-                }
-            }
-            Some(("u", value)) => (
-                CoordRefSystem::default(),
-                Some(value.parse().map_err(Error::InvalidUncertainty)?),
-            ),
-            Some(_) | None => (CoordRefSystem::default(), None),
-        };
+        Self::parse_with(uri, &ParseOptions::default())
+    }
 
-        // Validate the geo URI before returning it.
-        let geo_uri = GeoUri {
-            crs,
-            latitude,
-            longitude,
-            altitude,
-            uncertainty,
-        };
-        geo_uri.validate()?;
+    /// Try parsing a geo URI string into a `GeoUri` using the provided options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, ParseOptions};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let options = ParseOptions::strict();
+    /// let geo_uri = GeoUri::parse_with("geo:52.107,5.134;crs=wgs84;u=40", &options)?;
+    /// assert_eq!(geo_uri.latitude(), 52.107);
+    /// assert_eq!(geo_uri.longitude(), 5.134);
+    /// assert_eq!(geo_uri.uncertainty(), Some(40.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the parsing fails in any way.
+    pub fn parse_with(uri: &str, options: &ParseOptions) -> Result<Self, Error> {
+        parser::parse(uri, options).map_err(Error::from)
+    }
 
-        Ok(geo_uri)
+    /// Try parsing a geo URI string into a `GeoUri` using the provided options, returning
+    /// where parsing failed on error.
+    ///
+    /// See [`ParseError`] for the details that are provided.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{Component, Error, GeoUri, ParseOptions};
+    ///
+    /// let uri = "geo:94,5.134";
+    /// let error = GeoUri::parse_spanned(uri, &ParseOptions::default()).unwrap_err();
+    /// assert_eq!(error.kind(), &Error::OutOfRangeLatitude);
+    /// assert_eq!(error.component(), Component::Latitude);
+    /// assert_eq!(&uri[error.span()], "94");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the parsing fails in any way.
+    pub fn parse_spanned(uri: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        parser::parse(uri, options)
+    }
+
+    /// Returns the coordinate reference system.
+    pub fn crs(&self) -> CoordRefSystem {
+        self.crs
     }
 
     /// Returns the latitude coordinate.
@@ -356,6 +621,7 @@ impl GeoUri {
     pub fn set_latitude(&mut self, latitude: f64) -> Result<(), Error> {
         self.crs.validate(latitude, self.longitude)?;
         self.latitude = latitude;
+        self.decimals.latitude = None;
 
         Ok(())
     }
@@ -374,6 +640,7 @@ impl GeoUri {
     pub fn set_longitude(&mut self, longitude: f64) -> Result<(), Error> {
         self.crs.validate(self.latitude, longitude)?;
         self.longitude = longitude;
+        self.decimals.longitude = None;
 
         Ok(())
     }
@@ -386,6 +653,7 @@ impl GeoUri {
     /// Changes the altitude coordinate.
     pub fn set_altitude(&mut self, altitude: Option<f64>) {
         self.altitude = altitude;
+        self.decimals.altitude = None;
     }
 
     /// Returns the uncertainty around the location.
@@ -405,10 +673,309 @@ impl GeoUri {
             }
         }
         self.uncertainty = uncertainty;
+        self.decimals.uncertainty = None;
+
+        Ok(())
+    }
+
+    /// Returns the number of decimal places of the latitude coordinate in its original textual
+    /// representation (if known).
+    ///
+    /// This is known if the geo URI was parsed and the coordinate has the form
+    /// `[-]digits[.digits]` with at most 15 digits, or if it was set using
+    /// [`GeoUriBuilder::latitude_decimals`]. It is used to format the coordinate, see
+    /// [`Precision::Preserved`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.1070,5.1340")?;
+    /// assert_eq!(geo_uri.latitude(), 52.107);
+    /// assert_eq!(geo_uri.latitude_decimals(), Some(4));
+    /// assert_eq!(geo_uri.to_string(), "geo:52.1070,5.1340");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn latitude_decimals(&self) -> Option<u8> {
+        self.decimals.latitude
+    }
+
+    /// Returns the number of decimal places of the longitude coordinate in its original textual
+    /// representation (if known).
+    ///
+    /// See [`GeoUri::latitude_decimals`] for when this is known.
+    pub fn longitude_decimals(&self) -> Option<u8> {
+        self.decimals.longitude
+    }
+
+    /// Returns the number of decimal places of the altitude coordinate in its original textual
+    /// representation (if known).
+    ///
+    /// See [`GeoUri::latitude_decimals`] for when this is known.
+    pub fn altitude_decimals(&self) -> Option<u8> {
+        self.decimals.altitude
+    }
+
+    /// Returns the number of decimal places of the uncertainty in its original textual
+    /// representation (if known).
+    ///
+    /// See [`GeoUri::latitude_decimals`] for when this is known.
+    pub fn uncertainty_decimals(&self) -> Option<u8> {
+        self.decimals.uncertainty
+    }
+
+    /// Returns the precision in meters implied by the number of decimal places of the latitude
+    /// and longitude coordinates (if known).
+    ///
+    /// This is the distance along a meridian of one unit in the last decimal place of the least
+    /// precise coordinate, on the mean radius of the reference ellipsoid of the coordinate
+    /// reference system. It is unknown if the number of decimal places of either coordinate is
+    /// unknown, or if the coordinate reference system has no reference ellipsoid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.1070,5.134")?;
+    /// let precision = geo_uri.implied_precision().expect("known precision");
+    /// assert_eq!(precision.round(), 111.0); // 0.001 degrees
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn implied_precision(&self) -> Option<f64> {
+        let decimals = self.decimals.latitude?.min(self.decimals.longitude?);
+        let unit = (0..decimals).fold(1.0_f64, |unit, _| unit / 10.0);
+        let ellipsoid = self.crs.ellipsoid()?;
+
+        Some(unit.to_radians() * ellipsoid.mean_radius())
+    }
+
+    /// Returns the value of the (other) parameter with the given name (if any).
+    ///
+    /// Parameter names are matched case-insensitively.
+    /// For parameters without a value, an empty value is returned.
+    /// The returned value is percent-decoded.
+    ///
+    /// Note that the `crs` and `u` parameters are not part of the other parameters, see
+    /// [`GeoUri::uncertainty`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.107,5.134;u=10;foo=bar;baz")?;
+    /// assert_eq!(geo_uri.param("FOO"), Some("bar"));
+    /// assert_eq!(geo_uri.param("baz"), Some(""));
+    /// assert_eq!(geo_uri.param("u"), None);
+    /// assert_eq!(geo_uri.to_string(), "geo:52.107,5.134;u=10;foo=bar;baz");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param_name, _)| param_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns an iterator over the names and values of the other parameters, in order.
+    ///
+    /// Parameters without a value have an empty value.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Changes the value of the (other) parameter with the given name.
+    ///
+    /// If a parameter with the same name (matched case-insensitively) exists, its value is
+    /// replaced, otherwise the parameter is appended.
+    /// Use an empty value for a parameter without a value.
+    /// The value should not be percent-encoded, this happens when the URI is generated.
+    ///
+    /// # Errors
+    ///
+    /// If the parameter name is not valid, or if it is `crs` or `u` which have dedicated fields,
+    /// an error will be returned.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn set_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        validate_param_name(name)?;
+
+        match self
+            .params
+            .iter_mut()
+            .find(|(param_name, _)| param_name.eq_ignore_ascii_case(name))
+        {
+            Some((_, param_value)) => *param_value = value.to_owned(),
+            None => self.params.push((name.to_owned(), value.to_owned())),
+        }
 
         Ok(())
     }
 
+    /// Removes the (other) parameter with the given name and returns its value (if any).
+    ///
+    /// Parameter names are matched case-insensitively.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn remove_param(&mut self, name: &str) -> Option<String> {
+        let index = self
+            .params
+            .iter()
+            .position(|(param_name, _)| param_name.eq_ignore_ascii_case(name))?;
+
+        Some(self.params.remove(index).1)
+    }
+
+    /// Writes the geo URI string to a writer.
+    ///
+    /// This generates the same string as the [`Display`](fmt::Display) implementation does, but
+    /// can write to any [`fmt::Write`] implementation, also without the `std` and `alloc` features.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    ///
+    /// let geo_uri = GeoUri::try_from((52.107, 5.134)).expect("valid coordinates");
+    /// let mut s = String::new();
+    /// geo_uri.write_to(&mut s).expect("written geo URI");
+    /// assert_eq!(s, "geo:52.107,5.134");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the writer fails.
+    pub fn write_to<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        write!(writer, "{self}")
+    }
+
+    /// Returns a wrapper that formats the geo URI string using the provided options.
+    ///
+    /// See [`FormatOptions`] for the available options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{FormatOptions, GeoUri, Precision};
+    ///
+    /// let geo_uri = GeoUri::try_from((52.107, 5.134)).expect("valid coordinates");
+    /// let mut options = FormatOptions::new();
+    /// options.coords_precision(Precision::Decimals(4));
+    /// assert_eq!(geo_uri.display_with(&options).to_string(), "geo:52.1070,5.1340");
+    /// ```
+    pub fn display_with(&self, options: &FormatOptions) -> DisplayWith<'_> {
+        DisplayWith::new(self, *options)
+    }
+
+    /// Writes the geo URI string into a fixed-size buffer and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    ///
+    /// let geo_uri = GeoUri::try_from((52.107, 5.134, 3.6)).expect("valid coordinates");
+    /// let mut buf = [0; 64];
+    /// assert_eq!(geo_uri.format_into(&mut buf), Ok("geo:52.107,5.134,3.6"));
+    ///
+    /// let mut buf = [0; 8];
+    /// assert!(geo_uri.format_into(&mut buf).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer is too small to hold the geo URI string.
+    pub fn format_into<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, fmt::Error> {
+        let mut writer = SliceWriter { buf, len: 0 };
+        self.write_to(&mut writer)?;
+
+        let SliceWriter { buf, len } = writer;
+        core::str::from_utf8(&buf[..len]).map_err(|_| fmt::Error)
+    }
+
+    /// Returns whether this geo URI is equivalent to another geo URI.
+    ///
+    /// This implements the URI comparison rules of
+    /// [RFC 5870](https://www.rfc-editor.org/rfc/rfc5870#section-6):
+    ///
+    /// * The coordinate reference systems must be the same, where a missing `crs` parameter
+    ///   implies WGS-84
+    /// * The coordinates and uncertainty are compared numerically, thus `-0` and `0` are equal
+    /// * A missing altitude or uncertainty is not equal to any value
+    /// * The longitude is ignored where the coordinate reference system says so (see
+    ///   [`Crs::is_longitude_ignored`]), e.g. at the poles for WGS-84
+    /// * The other parameters are compared regardless of their order, with case-insensitive
+    ///   names and percent-decoded, case-sensitive values
+    ///
+    /// The [`PartialEq`] implementation compares geo URIs in the same way, except that it does
+    /// not compare the other parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:90,-22.43;crs=WGS84;FOO=this%2dthat;bar")?;
+    /// let geo_uri2 = GeoUri::parse("geo:90.0,46;bar;foo=this-that")?;
+    /// assert!(geo_uri.equivalent(&geo_uri2));
+    ///
+    /// let geo_uri2 = GeoUri::parse("geo:90,46;bar;foo=This-That")?;
+    /// # #[cfg(feature = "alloc")]
+    /// assert!(!geo_uri.equivalent(&geo_uri2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn equivalent(&self, other: &Self) -> bool {
+        self == other && self.params_equivalent(other)
+    }
+
+    /// Returns whether the other parameters are equivalent to those of another geo URI.
+    ///
+    /// The parameters are compared regardless of their order, and parameters occurring multiple
+    /// times must occur equally often in both.
+    #[cfg(feature = "alloc")]
+    fn params_equivalent(&self, other: &Self) -> bool {
+        let param_eq = |(name, value): &(String, String), (name2, value2): &(String, String)| {
+            name.eq_ignore_ascii_case(name2) && value == value2
+        };
+        let count = |params: &[(String, String)], param| {
+            params.iter().filter(|p| param_eq(p, param)).count()
+        };
+
+        self.params.len() == other.params.len()
+            && self
+                .params
+                .iter()
+                .all(|param| count(&self.params, param) == count(&other.params, param))
+    }
+
+    /// Returns whether the other parameters are equivalent to those of another geo URI.
+    ///
+    /// Without the `alloc` feature, there are no other parameters, so they are always equivalent.
+    #[cfg(not(feature = "alloc"))]
+    fn params_equivalent(&self, _other: &Self) -> bool {
+        true
+    }
+
     /// Validates the coordinates.
     ///
     /// This is only meant for internal use to prevent returning [`GeoUri`] objects that are
@@ -462,7 +1029,9 @@ impl GeoUri {
 ///   String::from("geo:52.107,5.134,3.6;u=1000")
 /// );
 /// ```
-#[derive(Clone, Copy, Debug, Default)]
+// Never `Copy`, not even without the `alloc` feature, so that enabling it is additive.
+#[allow(missing_copy_implementations)]
+#[derive(Clone, Debug, Default)]
 pub struct GeoUriBuilder {
     /// The coordinate reference system used by the coordinates of this URI.
     crs: Option<CoordRefSystem>,
@@ -486,6 +1055,16 @@ pub struct GeoUriBuilder {
     ///
     /// This distance needs to be positive.
     uncertainty: Option<f64>,
+
+    /// The number of decimal places of the coordinates and uncertainty, if provided.
+    decimals: Decimals,
+
+    /// How the coordinates are normalized.
+    normalization: Normalization,
+
+    /// The other parameters of the URI, in order, as name and value pairs.
+    #[cfg(feature = "alloc")]
+    params: Vec<(String, String)>,
 }
 
 impl GeoUriBuilder {
@@ -534,6 +1113,66 @@ impl GeoUriBuilder {
         self
     }
 
+    /// The number of decimal places of the latitude coordinate.
+    ///
+    /// This is used to format the coordinate, see [`Precision::Preserved`].
+    pub fn latitude_decimals(&mut self, value: u8) -> &mut Self {
+        self.decimals.latitude = Some(value);
+
+        self
+    }
+
+    /// The number of decimal places of the longitude coordinate.
+    ///
+    /// This is used to format the coordinate, see [`Precision::Preserved`].
+    pub fn longitude_decimals(&mut self, value: u8) -> &mut Self {
+        self.decimals.longitude = Some(value);
+
+        self
+    }
+
+    /// The number of decimal places of the altitude coordinate.
+    ///
+    /// This is used to format the coordinate, see [`Precision::Preserved`].
+    pub fn altitude_decimals(&mut self, value: u8) -> &mut Self {
+        self.decimals.altitude = Some(value);
+
+        self
+    }
+
+    /// The number of decimal places of the uncertainty.
+    ///
+    /// This is used to format the uncertainty, see [`Precision::Preserved`].
+    pub fn uncertainty_decimals(&mut self, value: u8) -> &mut Self {
+        self.decimals.uncertainty = Some(value);
+
+        self
+    }
+
+    /// How the coordinates are normalized.
+    ///
+    /// By default, the coordinates are not normalized and coordinates out of range are rejected.
+    /// See [`Normalization`] for the other options.
+    pub fn normalization(&mut self, value: Normalization) -> &mut Self {
+        self.normalization = value;
+
+        self
+    }
+
+    /// An other parameter of the URI.
+    ///
+    /// The parameter is added after the previously added parameters.
+    /// Use an empty value for a parameter without a value.
+    /// The name must be valid and cannot be `crs` or `u`, otherwise a later call to
+    /// [`GeoUriBuilder::build`] will fail.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn param(&mut self, name: &str, value: &str) -> &mut Self {
+        self.params.push((name.to_owned(), value.to_owned()));
+
+        self
+    }
+
     /// Builds a new [`GeoUri`].
     ///
     /// # Errors
@@ -543,32 +1182,48 @@ impl GeoUriBuilder {
     pub fn build(&self) -> Result<GeoUri, GeoUriBuilderError> {
         self.validate()?;
 
-        Ok(GeoUri {
-            crs: self.crs.unwrap_or_default(),
-            latitude: self
-                .latitude
+        let crs = self.crs.unwrap_or_default();
+        let (latitude, longitude) = self.normalization.apply(
+            &crs,
+            self.latitude
                 .ok_or(GeoUriBuilderError::UninitializedField("latitude"))?,
-            longitude: self
-                .longitude
+            self.longitude
                 .ok_or(GeoUriBuilderError::UninitializedField("longitude"))?,
+        );
+        let mut geo_uri = GeoUri {
+            crs,
+            latitude,
+            longitude,
             altitude: self.altitude,
             uncertainty: self.uncertainty,
-        })
+            decimals: self.decimals,
+            #[cfg(feature = "alloc")]
+            params: self.params.clone(),
+        };
+        if self.normalization != Normalization::Off {
+            geo_uri.normalize();
+        }
+
+        Ok(geo_uri)
     }
 
     /// Validates the coordinates.
     ///
-    /// Performs the validation that [`GeoUri::validate`] would perform, but also checks if the
-    /// uncertainty value is not negative if provided to the builder.
+    /// Performs the validation that [`GeoUri::validate`] would perform on the normalized
+    /// coordinates, but also checks if the uncertainty value is not negative if provided to the
+    /// builder.
     ///
     /// # Errors
     ///
     /// Returns an error if the currently configured coordinate values are invalid.
     fn validate(&self) -> Result<(), GeoUriBuilderError> {
-        self.crs.unwrap_or_default().validate(
+        let crs = self.crs.unwrap_or_default();
+        let (latitude, longitude) = self.normalization.apply(
+            &crs,
             self.latitude.unwrap_or_default(),
             self.longitude.unwrap_or_default(),
-        )?;
+        );
+        crs.validate(latitude, longitude)?;
 
         if let Some(unc) = self.uncertainty {
             if unc < 0.0 {
@@ -576,10 +1231,37 @@ impl GeoUriBuilder {
             }
         }
 
+        #[cfg(feature = "alloc")]
+        for (name, _) in &self.params {
+            validate_param_name(name)?;
+        }
+
         Ok(())
     }
 }
 
+/// Validates the name of an other parameter.
+///
+/// # Errors
+///
+/// Returns an error if the name is not a valid parameter name or if it is the name of the `crs` or
+/// `u` parameter.
+#[cfg(feature = "alloc")]
+fn validate_param_name(name: &str) -> Result<(), Error> {
+    if !parser::is_labeltext(name) || is_reserved_param_name(name) {
+        return Err(Error::InvalidParam);
+    }
+
+    Ok(())
+}
+
+/// Returns whether the name is the name of the `crs` or `u` parameter, which have dedicated
+/// fields.
+#[cfg(feature = "alloc")]
+fn is_reserved_param_name(name: &str) -> bool {
+    name.eq_ignore_ascii_case("crs") || name.eq_ignore_ascii_case("u")
+}
+
 /// Possible errors when using the geo URI builder [`GeoUriBuilder`].
 #[non_exhaustive]
 #[derive(Debug, Error)]
@@ -625,21 +1307,29 @@ impl<'de> Deserialize<'de> for GeoUri {
 
 impl fmt::Display for GeoUri {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self {
-            latitude,
-            longitude,
-            ..
-        } = self;
-        write!(f, "{URI_SCHEME_NAME}:{latitude},{longitude}")?;
+        self.display_with(&FormatOptions::default()).fmt(f)
+    }
+}
 
-        if let Some(altitude) = self.altitude {
-            write!(f, ",{altitude}")?;
-        }
+/// Writer that writes into a fixed-size buffer.
+///
+/// This is used by [`GeoUri::format_into`].
+struct SliceWriter<'b> {
+    /// The buffer to write into.
+    buf: &'b mut [u8],
 
-        // Don't write the CRS since there is only one supported at the moment.
-        if let Some(uncertainty) = self.uncertainty {
-            write!(f, ";u={uncertainty}")?;
-        }
+    /// The number of bytes written into the buffer so far.
+    len: usize,
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let dest = self
+            .buf
+            .get_mut(self.len..self.len + s.len())
+            .ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len += s.len();
 
         Ok(())
     }
@@ -676,7 +1366,7 @@ impl Serialize for GeoUri {
     where
         S: serde_core::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.collect_str(self)
     }
 }
 
@@ -741,8 +1431,9 @@ impl TryFrom<Url> for GeoUri {
 
 impl PartialEq for GeoUri {
     fn eq(&self, other: &Self) -> bool {
-        // In the WGS-84 CRS the the longitude is ignored for the poles.
-        let ignore_longitude = self.crs == CoordRefSystem::Wgs84 && self.latitude.abs() == 90.0;
+        // The coordinate reference system determines where the longitude is ignored, e.g. for
+        // the poles in WGS-84.
+        let ignore_longitude = self.crs.is_longitude_ignored(self.latitude);
 
         self.crs == other.crs
             && self.latitude == other.latitude
@@ -752,6 +1443,28 @@ impl PartialEq for GeoUri {
     }
 }
 
+/// Returns whether two geo URI strings are equivalent.
+///
+/// Both strings are parsed in lenient mode and then compared using [`GeoUri::equivalent`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use geo_uri::Error;
+/// # fn main() -> Result<(), Error> {
+/// assert!(geo_uri::equivalent("geo:22.300,-118.44", "GEO:22.3,-118.4400;CRS=wgs84")?);
+/// assert!(!geo_uri::equivalent("geo:22.3,-118.44", "geo:22.3,-118.44,0")?);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Will return an error if parsing either of the geo URI strings fails.
+pub fn equivalent(uri: &str, other_uri: &str) -> Result<bool, Error> {
+    Ok(GeoUri::parse(uri)?.equivalent(&GeoUri::parse(other_uri)?))
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "serde")]
@@ -775,6 +1488,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn coord_ref_system_hash_ord() {
+        use std::collections::HashSet;
+
+        #[derive(Debug)]
+        struct Grid(&'static str);
+
+        impl Crs for Grid {
+            fn name(&self) -> &str {
+                self.0
+            }
+
+            fn validate(&self, _latitude: f64, _longitude: f64) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+
+        static BETA: Grid = Grid("Beta");
+        static BETA_LOWER: Grid = Grid("beta");
+        static ALPHA: Grid = Grid("alpha");
+        static MARS: Grid = Grid("MARS");
+        let mut crss = vec![
+            CoordRefSystem::Custom(&BETA),
+            CoordRefSystem::Mars,
+            CoordRefSystem::Custom(&ALPHA),
+            CoordRefSystem::Wgs84,
+            CoordRefSystem::Custom(&MARS),
+            CoordRefSystem::Moon,
+            CoordRefSystem::Custom(&BETA_LOWER),
+        ];
+        let hashed: HashSet<_> = crss.iter().copied().collect();
+        assert_eq!(hashed.len(), 5);
+
+        crss.sort();
+        crss.dedup();
+        assert_eq!(
+            crss,
+            [
+                CoordRefSystem::Wgs84,
+                CoordRefSystem::Moon,
+                CoordRefSystem::Mars,
+                CoordRefSystem::Custom(&ALPHA),
+                CoordRefSystem::Custom(&BETA),
+            ]
+        );
+
+        // A custom coordinate reference system with the name of a built-in one is the same,
+        // as it is written to the `crs` parameter the same way.
+        static WGS84: Grid = Grid("WGS84");
+        let crs = CoordRefSystem::Custom(&WGS84);
+        assert_eq!(crs, CoordRefSystem::Wgs84);
+        assert_eq!(crs.cmp(&CoordRefSystem::Wgs84), Ordering::Equal);
+        assert!(hashed.contains(&crs));
+        assert_eq!(CoordRefSystem::Custom(&MARS), CoordRefSystem::Mars);
+    }
+
     #[test]
     fn geo_uri_builder() -> Result<(), GeoUriBuilderError> {
         let mut builder = GeoUri::builder();
@@ -934,6 +1703,7 @@ mod tests {
             longitude: 5.134,
             altitude: None,
             uncertainty: None,
+            ..Default::default()
         };
         assert_eq!(geo_uri.validate(), Ok(()));
 
@@ -957,7 +1727,9 @@ mod tests {
             longitude: 5.134,
             altitude: None,
             uncertainty: None,
+            ..Default::default()
         };
+        assert_eq!(geo_uri.crs(), CoordRefSystem::Wgs84);
         assert_eq!(geo_uri.latitude(), 52.107);
         assert_eq!(geo_uri.longitude(), 5.134);
         assert_eq!(geo_uri.altitude(), None);
@@ -993,6 +1765,7 @@ mod tests {
             longitude: 5.134,
             altitude: None,
             uncertainty: None,
+            ..Default::default()
         };
         assert_eq!(&geo_uri.to_string(), "geo:52.107,5.134");
 
@@ -1003,6 +1776,144 @@ mod tests {
         assert_eq!(&geo_uri.to_string(), "geo:52.107,5.134,3.6;u=25000");
     }
 
+    #[test]
+    fn geo_uri_decimals() -> Result<(), GeoUriBuilderError> {
+        let mut geo_uri = GeoUri::parse("geo:52.1070,5.134,3.60;u=10.0")?;
+        assert_eq!(geo_uri.latitude_decimals(), Some(4));
+        assert_eq!(geo_uri.longitude_decimals(), Some(3));
+        assert_eq!(geo_uri.altitude_decimals(), Some(2));
+        assert_eq!(geo_uri.uncertainty_decimals(), Some(1));
+        assert_eq!(&geo_uri.to_string(), "geo:52.1070,5.134,3.60;u=10.0");
+        let precision = geo_uri.implied_precision().expect("known precision");
+        assert!((precision - 111.19).abs() < 0.01);
+
+        geo_uri.set_latitude(52.1)?;
+        geo_uri.set_altitude(Some(3.6));
+        assert_eq!(geo_uri.latitude_decimals(), None);
+        assert_eq!(geo_uri.altitude_decimals(), None);
+        assert_eq!(geo_uri.implied_precision(), None);
+        assert_eq!(&geo_uri.to_string(), "geo:52.1,5.134,3.6;u=10.0");
+
+        let geo_uri = GeoUri::parse("geo:5.2107e1,5.134")?;
+        assert_eq!(geo_uri.latitude_decimals(), None);
+        assert_eq!(&geo_uri.to_string(), "geo:52.107,5.134");
+
+        let geo_uri = GeoUri::builder()
+            .latitude(52.107)
+            .longitude(5.134)
+            .uncertainty(10.0)
+            .latitude_decimals(4)
+            .longitude_decimals(4)
+            .uncertainty_decimals(2)
+            .build()?;
+        assert_eq!(&geo_uri.to_string(), "geo:52.1070,5.1340;u=10.00");
+        let precision = geo_uri.implied_precision().expect("known precision");
+        assert!((precision - 11.12).abs() < 0.01);
+
+        let geo_uri = GeoUri::builder()
+            .crs(CoordRefSystem::Mars)
+            .latitude(4.5)
+            .longitude(137.4)
+            .latitude_decimals(1)
+            .longitude_decimals(1)
+            .build()?;
+        let precision = geo_uri.implied_precision().expect("known precision");
+        assert!((precision - 5_915.9).abs() < 0.1);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn geo_uri_params() -> Result<(), GeoUriBuilderError> {
+        let mut builder = GeoUri::builder();
+        builder
+            .latitude(52.107)
+            .longitude(5.134)
+            .uncertainty(200.0)
+            .param("foo", "bar")
+            .param("baz", "");
+        let mut geo_uri = builder.build()?;
+        assert_eq!(
+            geo_uri.params,
+            vec![
+                (String::from("foo"), String::from("bar")),
+                (String::from("baz"), String::new())
+            ]
+        );
+
+        builder.param("U", "10");
+        assert!(matches!(
+            builder.build(),
+            Err(GeoUriBuilderError::ValidationError(Error::InvalidParam))
+        ));
+
+        let geo_uri2 = GeoUri::parse("geo:52.107,5.134,3.6;crs=wgs84;u=25000;foo=bar")?;
+        assert_eq!(
+            geo_uri2.params,
+            vec![(String::from("foo"), String::from("bar"))]
+        );
+
+        assert_eq!(geo_uri.param("foo"), Some("bar"));
+        assert_eq!(geo_uri.param("qux"), None);
+        assert_eq!(geo_uri.set_param("qux", "bar"), Ok(()));
+        assert_eq!(geo_uri.set_param("Baz", ""), Ok(()));
+        assert_eq!(geo_uri.set_param("FOO", "Qux"), Ok(()));
+        assert_eq!(geo_uri.set_param("crs", "wgs84"), Err(Error::InvalidParam));
+        assert_eq!(geo_uri.set_param("u", "10"), Err(Error::InvalidParam));
+        assert_eq!(geo_uri.set_param("a;b", "c"), Err(Error::InvalidParam));
+        assert_eq!(geo_uri.param("foo"), Some("Qux"));
+        assert_eq!(geo_uri.param("baz"), Some(""));
+        assert_eq!(
+            geo_uri.params().collect::<Vec<_>>(),
+            vec![("foo", "Qux"), ("baz", ""), ("qux", "bar")]
+        );
+
+        assert_eq!(geo_uri.remove_param("FOO"), Some(String::from("Qux")));
+        assert_eq!(geo_uri.remove_param("foo"), None);
+        assert_eq!(
+            geo_uri.params().collect::<Vec<_>>(),
+            vec![("baz", ""), ("qux", "bar")]
+        );
+
+        geo_uri.params = vec![
+            (String::from("foo"), String::from("Bar")),
+            (String::from("baz"), String::new()),
+        ];
+        assert_eq!(&geo_uri.to_string(), "geo:52.107,5.134;u=200;foo=Bar;baz");
+
+        geo_uri.params = vec![(String::from("foo"), String::from("Bar baz;café"))];
+        assert_eq!(
+            &geo_uri.to_string(),
+            "geo:52.107,5.134;u=200;foo=Bar%20baz%3Bcaf%C3%A9"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_write_to() {
+        let geo_uri = GeoUri {
+            crs: CoordRefSystem::Wgs84,
+            latitude: 52.107,
+            longitude: 5.134,
+            altitude: Some(3.6),
+            uncertainty: Some(25_000.0),
+            ..Default::default()
+        };
+        let mut s = String::new();
+        assert_eq!(geo_uri.write_to(&mut s), Ok(()));
+        assert_eq!(s, "geo:52.107,5.134,3.6;u=25000");
+
+        let mut buf = [0; 28];
+        assert_eq!(
+            geo_uri.format_into(&mut buf),
+            Ok("geo:52.107,5.134,3.6;u=25000")
+        );
+        let mut buf = [0; 27];
+        assert_eq!(geo_uri.format_into(&mut buf), Err(fmt::Error));
+    }
+
     #[cfg(feature = "url")]
     #[test]
     fn geo_uri_from() {
@@ -1012,6 +1923,7 @@ mod tests {
             longitude: 5.134,
             altitude: Some(3.6),
             uncertainty: Some(1000.0),
+            ..Default::default()
         };
         let url = Url::from(&geo_uri);
         assert_eq!(url.scheme(), "geo");
@@ -1020,6 +1932,15 @@ mod tests {
         let url = Url::from(geo_uri);
         assert_eq!(url.scheme(), "geo");
         assert_eq!(url.path(), "52.107,5.134,3.6;u=1000");
+
+        let geo_uri = GeoUri::builder()
+            .latitude(52.107)
+            .longitude(5.134)
+            .param("label", "Utrecht Centraal")
+            .build()
+            .expect("valid geo URI");
+        let url = Url::from(&geo_uri);
+        assert_eq!(url.path(), "52.107,5.134;label=Utrecht%20Centraal");
     }
 
     #[test]
@@ -1042,6 +1963,7 @@ mod tests {
             longitude: 5.134,
             altitude: Some(3.6),
             uncertainty: Some(1000.0),
+            ..Default::default()
         };
         assert_tokens(&geo_uri, &[Token::String("geo:52.107,5.134,3.6;u=1000")]);
 
@@ -1116,6 +2038,10 @@ mod tests {
         assert_eq!(geo_uri.altitude.unwrap(), 3.6);
         assert_eq!(geo_uri.uncertainty, Some(1000.0));
 
+        let url = Url::parse("geo:51.107,5.134;label=Café%20Utrecht").expect("valid URL");
+        let geo_uri = GeoUri::try_from(&url)?;
+        assert_eq!(geo_uri.param("label"), Some("Café Utrecht"));
+
         Ok(())
     }
 
@@ -1169,4 +2095,54 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn geo_uri_equivalent() -> Result<(), Error> {
+        let geo_uri = GeoUri::parse("geo:-0,0,-0;u=0")?;
+        let geo_uri2 = GeoUri::parse("geo:0,-0.0,0;u=0.0")?;
+        assert!(geo_uri.equivalent(&geo_uri2));
+
+        let geo_uri = GeoUri::parse("geo:52.107,5.134")?;
+        let geo_uri2 = GeoUri::parse("geo:52.107,5.134;crs=wgs84")?;
+        assert!(geo_uri.equivalent(&geo_uri2));
+
+        let geo_uri = GeoUri::parse("geo:-90,5.134")?;
+        let geo_uri2 = GeoUri::parse("geo:-90,-5.134")?;
+        assert!(geo_uri.equivalent(&geo_uri2));
+        let geo_uri2 = GeoUri::parse("geo:90,5.134")?;
+        assert!(!geo_uri.equivalent(&geo_uri2));
+
+        let geo_uri = GeoUri::parse("geo:52.107,5.134")?;
+        let geo_uri2 = GeoUri::parse("geo:52.107,5.134,0")?;
+        assert!(!geo_uri.equivalent(&geo_uri2));
+        let geo_uri2 = GeoUri::parse("geo:52.107,5.134;u=0")?;
+        assert!(!geo_uri.equivalent(&geo_uri2));
+
+        #[cfg(feature = "alloc")]
+        {
+            let geo_uri = GeoUri::parse("geo:52.107,5.134;Foo=a%20b;bar;foo=c")?;
+            let geo_uri2 = GeoUri::parse("geo:52.107,5.134;foo=c;BAR;FOO=a b")?;
+            assert!(geo_uri.equivalent(&geo_uri2));
+            let geo_uri2 = GeoUri::parse("geo:52.107,5.134;foo=c;BAR;FOO=A b")?;
+            assert!(!geo_uri.equivalent(&geo_uri2));
+            let geo_uri2 = GeoUri::parse("geo:52.107,5.134;foo=c;bar")?;
+            assert!(!geo_uri.equivalent(&geo_uri2));
+            assert_eq!(geo_uri, geo_uri2); // Equality does not compare the other parameters.
+
+            let geo_uri = GeoUri::parse("geo:52.107,5.134;foo=a;foo=a;foo=b")?;
+            let geo_uri2 = GeoUri::parse("geo:52.107,5.134;foo=a;foo=b;foo=b")?;
+            assert!(!geo_uri.equivalent(&geo_uri2));
+            assert!(!geo_uri2.equivalent(&geo_uri));
+
+            assert_eq!(
+                equivalent("geo:47,11;foo=blue", "geo:47,11;FOO=blue"),
+                Ok(true)
+            );
+            assert_eq!(equivalent("geo:47,11;foo=blue", "geo:47,11"), Ok(false));
+        }
+
+        assert_eq!(equivalent("geo:47,11", "47,11"), Err(Error::MissingScheme));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,548 @@
+//! Conversion to and from map provider web URLs.
+
+use core::f64::consts::PI;
+use core::ops::RangeInclusive;
+
+use url::Url;
+
+use crate::normalize::wrap_degrees;
+use crate::{CoordRefSystem, Ellipsoid, Error, GeoUri};
+
+/// The equatorial circumference of the Earth in meters (WGS-84).
+const EARTH_CIRCUMFERENCE: f64 = 2.0 * PI * Ellipsoid::WGS84.equatorial_radius();
+
+/// The zoom level used for exported links if the geo URI has no uncertainty.
+const DEFAULT_ZOOM: f64 = 15.0;
+
+/// The maximum zoom level used for exported links.
+const MAX_ZOOM: f64 = 21.0;
+
+/// The range of zoom levels accepted in imported links.
+const ZOOM_RANGE: RangeInclusive<f64> = 0.0..=22.0;
+
+/// A web map provider whose links can be converted to and from geo URIs.
+///
+/// Use [`GeoUri::from_map_url`] to import a link and [`GeoUri::to_map_url`] to export one.
+///
+/// The zoom level of a link is mapped to an approximate uncertainty and vice versa: the
+/// uncertainty is taken to be the radius of half a 256 pixel map tile at the zoom level and
+/// latitude of the location.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{GeoUri, MapProvider};
+/// use url::Url;
+/// # use geo_uri::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let url = Url::parse("https://www.openstreetmap.org/#map=15/52.107/5.134").expect("valid URL");
+/// assert_eq!(MapProvider::detect(&url), Some(MapProvider::OpenStreetMap));
+///
+/// let geo_uri = GeoUri::from_map_url(&url)?;
+/// assert_eq!(geo_uri.to_string(), "geo:52.107,5.134;u=376");
+/// assert_eq!(
+///     geo_uri.to_map_url(MapProvider::Google)?.as_str(),
+///     "https://www.google.com/maps?q=52.107,5.134&z=15"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MapProvider {
+    /// [Apple Maps](https://maps.apple.com), e.g. `https://maps.apple.com/?ll=52.107,5.134&z=15`.
+    Apple,
+
+    /// [Bing Maps](https://www.bing.com/maps), e.g.
+    /// `https://www.bing.com/maps?cp=52.107~5.134&lvl=15`.
+    Bing,
+
+    /// [Google Maps](https://www.google.com/maps), e.g.
+    /// `https://www.google.com/maps/@52.107,5.134,15z` or
+    /// `https://maps.google.com/?q=52.107,5.134`.
+    Google,
+
+    /// [HERE WeGo](https://wego.here.com), e.g. `https://wego.here.com/?map=52.107,5.134,15`.
+    Here,
+
+    /// [OpenStreetMap](https://www.openstreetmap.org), e.g.
+    /// `https://www.openstreetmap.org/#map=15/52.107/5.134` or
+    /// `https://www.openstreetmap.org/?mlat=52.107&mlon=5.134`.
+    OpenStreetMap,
+}
+
+impl MapProvider {
+    /// Detects the map provider of a web URL by its host.
+    ///
+    /// Returns `None` if the URL does not belong to a known map provider.
+    pub fn detect(url: &Url) -> Option<Self> {
+        let host = url.host_str()?;
+        let has_domain = |domain: &str| {
+            host.strip_suffix(domain)
+                .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+        };
+
+        if has_domain("maps.apple.com") || has_domain("maps.apple") {
+            Some(Self::Apple)
+        } else if has_domain("bing.com") {
+            Some(Self::Bing)
+        } else if is_google_host(host) {
+            Some(Self::Google)
+        } else if has_domain("here.com") {
+            Some(Self::Here)
+        } else if has_domain("openstreetmap.org") || has_domain("osm.org") {
+            Some(Self::OpenStreetMap)
+        } else {
+            None
+        }
+    }
+
+    /// Extracts the coordinates and zoom level (if any) from a URL of this map provider.
+    fn extract(&self, url: &Url) -> Option<((f64, f64), Option<f64>)> {
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value)
+        };
+        let zoom = |name: &str| query(name).and_then(|zoom| zoom.parse().ok());
+
+        match self {
+            Self::Apple => {
+                let coords = ["ll", "q", "sll"]
+                    .into_iter()
+                    .find_map(|name| query(name).and_then(|value| parse_coords(&value, ',')))?;
+
+                Some((coords, zoom("z")))
+            }
+            Self::Bing => {
+                let coords = query("cp").and_then(|value| parse_coords(&value, '~'))?;
+
+                Some((coords, zoom("lvl")))
+            }
+            Self::Google => {
+                let coords = ["q", "query", "ll", "center"]
+                    .into_iter()
+                    .find_map(|name| query(name).and_then(|value| parse_coords(&value, ',')));
+                let zoom = zoom("z").or_else(|| zoom("zoom"));
+
+                // The path can contain the map view, e.g. `/maps/place/Foo/@52.1,5.1,15z`.
+                let view = url
+                    .path_segments()
+                    .into_iter()
+                    .flatten()
+                    .find_map(|segment| segment.strip_prefix('@'))
+                    .and_then(|view| {
+                        let mut parts = view.split(',');
+                        let latitude = parts.next()?.parse().ok()?;
+                        let longitude = parts.next()?.parse().ok()?;
+                        let zoom = parts
+                            .next()
+                            .and_then(|part| part.strip_suffix('z'))
+                            .and_then(|part| part.parse().ok());
+
+                        Some(((latitude, longitude), zoom))
+                    });
+
+                // Prefer the queried location over the center of the map view.
+                let coords = coords.or(view.map(|(coords, _)| coords))?;
+
+                Some((coords, zoom.or(view.and_then(|(_, zoom)| zoom))))
+            }
+            Self::Here => {
+                if let Some(map) = query("map") {
+                    let mut parts = map.split(',');
+                    let latitude = parts.next()?.trim().parse().ok()?;
+                    let longitude = parts.next()?.trim().parse().ok()?;
+                    let zoom = parts.next().and_then(|zoom| zoom.parse().ok());
+
+                    return Some(((latitude, longitude), zoom));
+                }
+
+                // Share links, e.g. `https://share.here.com/l/52.107,5.134`.
+                let mut segments = url.path_segments()?;
+                segments.find(|segment| *segment == "l")?;
+                let coords = parse_coords(segments.next()?, ',')?;
+
+                Some((coords, zoom("z")))
+            }
+            Self::OpenStreetMap => {
+                // The map view is in the fragment, e.g. `#map=15/52.107/5.134`.
+                let view = url
+                    .fragment()
+                    .into_iter()
+                    .flat_map(|fragment| fragment.split('&'))
+                    .find_map(|part| part.strip_prefix("map="))
+                    .and_then(|map| {
+                        let mut parts = map.split('/');
+                        let zoom = parts.next()?.parse().ok()?;
+                        let latitude = parts.next()?.parse().ok()?;
+                        let longitude = parts.next()?.parse().ok()?;
+
+                        Some(((latitude, longitude), zoom))
+                    });
+
+                // Prefer the marker over the center of the map view.
+                let marker = query("mlat")
+                    .zip(query("mlon"))
+                    .and_then(|(lat, lon)| Some((lat.parse().ok()?, lon.parse().ok()?)));
+                let coords = marker.or(view.map(|(coords, _)| coords))?;
+
+                Some((coords, zoom("zoom").or(view.map(|(_, zoom)| zoom))))
+            }
+        }
+    }
+
+    /// Renders a link to the location of the geo URI on the map of this map provider.
+    fn render(&self, latitude: f64, longitude: f64, zoom: u8) -> String {
+        match self {
+            Self::Apple => format!("https://maps.apple.com/?ll={latitude},{longitude}&z={zoom}"),
+            Self::Bing => {
+                format!("https://www.bing.com/maps?cp={latitude}~{longitude}&lvl={zoom}")
+            }
+            Self::Google => {
+                format!("https://www.google.com/maps?q={latitude},{longitude}&z={zoom}")
+            }
+            Self::Here => format!("https://wego.here.com/?map={latitude},{longitude},{zoom}"),
+            Self::OpenStreetMap => format!(
+                "https://www.openstreetmap.org/?mlat={latitude}&mlon={longitude}\
+                 #map={zoom}/{latitude}/{longitude}"
+            ),
+        }
+    }
+}
+
+impl GeoUri {
+    /// Try importing a geo URI from a link of a map provider.
+    ///
+    /// See [`MapProvider`] for the supported map providers. If the link has a zoom level, it is
+    /// mapped to an approximate uncertainty of at least 1 meter. The location is in the WGS-84
+    /// coordinate reference system, where longitudes beyond the antimeridian (e.g. of a scrolled
+    /// map view) are wrapped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// use url::Url;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let url = Url::parse("https://www.google.com/maps/place/Dom/@52.0907,5.1214,17z")
+    ///     .expect("valid URL");
+    /// let geo_uri = GeoUri::from_map_url(&url)?;
+    /// assert_eq!(geo_uri.latitude(), 52.0907);
+    /// assert_eq!(geo_uri.longitude(), 5.1214);
+    /// assert_eq!(geo_uri.uncertainty(), Some(94.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the URL is not of a supported map provider, if it does not
+    /// contain a valid location or if its zoom level is not within `0..=22`.
+    pub fn from_map_url(url: &Url) -> Result<Self, Error> {
+        let provider = MapProvider::detect(url).ok_or(Error::UnrecognizedUrl)?;
+        let ((latitude, longitude), zoom) = provider.extract(url).ok_or(Error::MissingCoords)?;
+
+        // The center of a map view can be scrolled beyond the antimeridian.
+        let mut geo_uri = GeoUri::try_from((latitude, wrap_degrees(longitude)))?;
+        if let Some(zoom) = zoom {
+            if !ZOOM_RANGE.contains(&zoom) {
+                return Err(Error::InvalidZoom);
+            }
+            geo_uri.set_uncertainty(Some(zoom_to_uncertainty(zoom, latitude)))?;
+        }
+
+        Ok(geo_uri)
+    }
+
+    /// Exports the geo URI as a link for the map provider.
+    ///
+    /// The zoom level of the link is derived from the uncertainty, and a zoom level of 15 is used
+    /// if there is no uncertainty. Only the latitude and longitude are exported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, MapProvider};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:52.107,5.134;u=100")?;
+    /// assert_eq!(
+    ///     geo_uri.to_map_url(MapProvider::OpenStreetMap)?.as_str(),
+    ///     "https://www.openstreetmap.org/?mlat=52.107&mlon=5.134#map=17/52.107/5.134"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system is not WGS-84, because map
+    /// providers only show locations on the Earth.
+    pub fn to_map_url(&self, provider: MapProvider) -> Result<Url, Error> {
+        if self.crs != CoordRefSystem::Wgs84 {
+            return Err(Error::InvalidCoordRefSystem);
+        }
+
+        let zoom = self
+            .uncertainty
+            .map_or(DEFAULT_ZOOM, |unc| uncertainty_to_zoom(unc, self.latitude));
+        let link = provider.render(self.latitude, self.longitude, zoom as u8);
+
+        Ok(Url::parse(&link).expect("valid URL"))
+    }
+}
+
+/// Returns whether the host belongs to Google, whose registrable domains are `google.com` or
+/// `google.` followed by a country code top-level domain, optionally preceded by `co.` or `com.`,
+/// e.g. `google.nl`, `google.co.uk` or `google.com.au`.
+fn is_google_host(host: &str) -> bool {
+    let mut labels = host.rsplit('.');
+    let Some(top_level_domain) = labels.next() else {
+        return false;
+    };
+    let is_country_code =
+        top_level_domain.len() == 2 && top_level_domain.bytes().all(|b| b.is_ascii_lowercase());
+
+    match labels.next() {
+        Some("google") => top_level_domain == "com" || is_country_code,
+        Some("co" | "com") if is_country_code => labels.next() == Some("google"),
+        _ => false,
+    }
+}
+
+/// Parses a latitude and longitude separated by the separator.
+fn parse_coords(s: &str, separator: char) -> Option<(f64, f64)> {
+    let (latitude, longitude) = s.split_once(separator)?;
+
+    Some((
+        latitude.trim().parse().ok()?,
+        longitude.trim().parse().ok()?,
+    ))
+}
+
+/// Returns the approximate uncertainty in meters for a zoom level at the latitude.
+///
+/// The uncertainty is at least 1 meter, because tiles become infinitely small near the poles
+/// and an uncertainty of zero would claim the location is exact.
+fn zoom_to_uncertainty(zoom: f64, latitude: f64) -> f64 {
+    let tile_size = EARTH_CIRCUMFERENCE * latitude.to_radians().cos() / 2.0_f64.powf(zoom);
+
+    (tile_size / 2.0).round().max(1.0)
+}
+
+/// Returns the zoom level (rounded, within `0..=21`) for an uncertainty in meters at the
+/// latitude.
+fn uncertainty_to_zoom(uncertainty: f64, latitude: f64) -> f64 {
+    let tile_size = EARTH_CIRCUMFERENCE * latitude.to_radians().cos();
+
+    (tile_size / (2.0 * uncertainty))
+        .log2()
+        .round()
+        .clamp(0.0, MAX_ZOOM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).expect("valid URL")
+    }
+
+    #[test]
+    fn map_provider_detect() {
+        for (link, provider) in [
+            ("https://maps.apple.com/?ll=1,2", Some(MapProvider::Apple)),
+            ("https://www.bing.com/maps?cp=1~2", Some(MapProvider::Bing)),
+            (
+                "https://www.google.nl/maps/@1,2,3z",
+                Some(MapProvider::Google),
+            ),
+            ("https://maps.google.com/?q=1,2", Some(MapProvider::Google)),
+            (
+                "https://www.google.co.uk/maps?q=1,2",
+                Some(MapProvider::Google),
+            ),
+            (
+                "https://www.google.com.au/maps?q=1,2",
+                Some(MapProvider::Google),
+            ),
+            ("https://google.evil.com/maps?q=1,2", None),
+            ("https://google.co.evil/maps?q=1,2", None),
+            ("https://www.google.com.evil.com/maps?q=1,2", None),
+            ("https://google.evil/maps?q=1,2", None),
+            ("https://maps.google.zip/?q=1,2", None),
+            ("https://www.google.example/maps?q=1,2", None),
+            ("https://www.google.co.zip/maps?q=1,2", None),
+            ("https://wego.here.com/?map=1,2,3", Some(MapProvider::Here)),
+            (
+                "https://osm.org/#map=3/1/2",
+                Some(MapProvider::OpenStreetMap),
+            ),
+            ("https://www.notgoogle.com/maps", None),
+            ("https://example.com/", None),
+            ("geo:1,2", None),
+        ] {
+            assert_eq!(MapProvider::detect(&url(link)), provider, "{link}");
+        }
+    }
+
+    #[test]
+    fn geo_uri_from_map_url() -> Result<(), Error> {
+        for (link, expected) in [
+            (
+                "https://maps.apple.com/?ll=52.107,5.134&z=15",
+                "geo:52.107,5.134;u=376",
+            ),
+            ("https://maps.apple.com/?q=52.107,5.134", "geo:52.107,5.134"),
+            (
+                "https://www.bing.com/maps?cp=52.107~5.134&lvl=15",
+                "geo:52.107,5.134;u=376",
+            ),
+            (
+                "https://www.google.com/maps/@52.107,5.134,15z",
+                "geo:52.107,5.134;u=376",
+            ),
+            (
+                "https://www.google.com/maps/@52.107,5.134,1000m/data=x",
+                "geo:52.107,5.134",
+            ),
+            (
+                "https://maps.google.com/maps?q=52.107,+5.134",
+                "geo:52.107,5.134",
+            ),
+            (
+                "https://www.google.com/maps/search/?api=1&query=52.107%2C5.134",
+                "geo:52.107,5.134",
+            ),
+            (
+                "https://www.google.com/maps/place/Dom/@52.09,5.12,15z?q=52.107,5.134",
+                "geo:52.107,5.134;u=376",
+            ),
+            (
+                "https://wego.here.com/?map=52.107,5.134,15,normal",
+                "geo:52.107,5.134;u=376",
+            ),
+            ("https://share.here.com/l/52.107,5.134", "geo:52.107,5.134"),
+            (
+                "https://www.openstreetmap.org/#map=15/52.107/5.134",
+                "geo:52.107,5.134;u=376",
+            ),
+            (
+                "https://www.openstreetmap.org/?mlat=52.107&mlon=5.134#map=10/52/5",
+                "geo:52.107,5.134;u=12018",
+            ),
+            (
+                "https://www.openstreetmap.org/?mlat=52.107&mlon=5.134",
+                "geo:52.107,5.134",
+            ),
+            (
+                "https://www.openstreetmap.org/#map=15/52.1/185.5",
+                "geo:52.1,-174.5;u=376",
+            ),
+            ("https://maps.apple.com/?ll=90,5&z=3", "geo:90,5;u=1"),
+            ("https://maps.apple.com/?ll=89.9,5&z=20", "geo:89.9,5;u=1"),
+        ] {
+            let geo_uri = GeoUri::from_map_url(&url(link))?;
+            assert_eq!(geo_uri.to_string(), expected, "{link}");
+        }
+
+        assert_eq!(
+            GeoUri::from_map_url(&url("https://example.com/?q=52.107,5.134")),
+            Err(Error::UnrecognizedUrl)
+        );
+        assert_eq!(
+            GeoUri::from_map_url(&url("https://www.google.com/maps/place/Utrecht")),
+            Err(Error::MissingCoords)
+        );
+        assert_eq!(
+            GeoUri::from_map_url(&url("https://maps.apple.com/?ll=100,5.134")),
+            Err(Error::OutOfRangeLatitude)
+        );
+        for link in [
+            "https://www.openstreetmap.org/#map=NaN/52.107/5.134",
+            "https://www.openstreetmap.org/#map=-2000/52.107/5.134",
+            "https://www.openstreetmap.org/#map=23/52.107/5.134",
+            "https://maps.apple.com/?ll=52.107,5.134&z=inf",
+        ] {
+            assert_eq!(
+                GeoUri::from_map_url(&url(link)),
+                Err(Error::InvalidZoom),
+                "{link}"
+            );
+        }
+        let geo_uri = GeoUri::from_map_url(&url("https://maps.apple.com/?ll=0,0&z=22"))?;
+        assert_eq!(geo_uri.uncertainty(), Some(5.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_to_map_url() -> Result<(), Error> {
+        let geo_uri = GeoUri::parse("geo:52.107,5.134")?;
+        for (provider, expected) in [
+            (
+                MapProvider::Apple,
+                "https://maps.apple.com/?ll=52.107,5.134&z=15",
+            ),
+            (
+                MapProvider::Bing,
+                "https://www.bing.com/maps?cp=52.107~5.134&lvl=15",
+            ),
+            (
+                MapProvider::Google,
+                "https://www.google.com/maps?q=52.107,5.134&z=15",
+            ),
+            (
+                MapProvider::Here,
+                "https://wego.here.com/?map=52.107,5.134,15",
+            ),
+            (
+                MapProvider::OpenStreetMap,
+                "https://www.openstreetmap.org/?mlat=52.107&mlon=5.134#map=15/52.107/5.134",
+            ),
+        ] {
+            let url = geo_uri.to_map_url(provider)?;
+            assert_eq!(url.as_str(), expected);
+
+            // Round-trip through importing.
+            let geo_uri2 = GeoUri::from_map_url(&url)?;
+            assert_eq!(geo_uri2.latitude(), geo_uri.latitude());
+            assert_eq!(geo_uri2.longitude(), geo_uri.longitude());
+            assert_eq!(geo_uri2.uncertainty(), Some(376.0));
+        }
+
+        let geo_uri = GeoUri::parse("geo:52.107,5.134;u=0")?;
+        assert!(geo_uri
+            .to_map_url(MapProvider::Here)?
+            .as_str()
+            .ends_with(",21"));
+        let geo_uri = GeoUri::parse("geo:52.107,5.134;u=100000000")?;
+        assert!(geo_uri
+            .to_map_url(MapProvider::Here)?
+            .as_str()
+            .ends_with(",0"));
+
+        let geo_uri = GeoUri::parse("geo:10,300;crs=mars")?;
+        assert_eq!(
+            geo_uri.to_map_url(MapProvider::Google),
+            Err(Error::InvalidCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn zoom_uncertainty() {
+        assert_eq!(zoom_to_uncertainty(0.0, 0.0), 20_037_508.0);
+        assert_eq!(zoom_to_uncertainty(15.0, 0.0), 611.0);
+        assert_eq!(uncertainty_to_zoom(611.0, 0.0), 15.0);
+        assert_eq!(uncertainty_to_zoom(376.0, 52.107), 15.0);
+        assert_eq!(uncertainty_to_zoom(0.0, 52.107), 21.0);
+    }
+}
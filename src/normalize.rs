@@ -0,0 +1,283 @@
+//! Normalization of coordinates to a canonical form.
+
+use crate::{CoordRefSystem, Crs, GeoUri, LongitudeConvention};
+
+/// How coordinates are normalized to a canonical form.
+///
+/// Normalization only changes the representation of a location, not the location itself
+/// (except for [`Normalization::Clamp`]):
+///
+/// * Longitudes outside the range of the [`LongitudeConvention`] of the coordinate reference
+///   system are wrapped into it. Of the two longitudes at the ends of the range that denote the
+///   same meridian, the canonical one is `180.0` for the `-180.0..=180.0` range and `0.0` for the
+///   `0.0..=360.0` range.
+/// * The longitude of a location where it is ignored, like the poles, is set to `0.0`.
+/// * Negative zeros (`-0.0`) of all coordinates and the uncertainty are replaced by `0.0`.
+///
+/// Wrapping longitudes and handling latitudes beyond the poles is only done if the coordinates
+/// are in degrees, see [`Crs::axes`].
+///
+/// Use [`GeoUri::normalize`] to normalize a geo URI, or [`GeoUriBuilder::normalization`] to
+/// normalize the coordinates when building one.
+///
+/// [`GeoUriBuilder::normalization`]: crate::GeoUriBuilder::normalization
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// No normalization, coordinates out of range are rejected.
+    #[default]
+    Off,
+
+    /// Wrap longitudes, latitudes beyond the poles are rejected.
+    Wrap,
+
+    /// Wrap longitudes and clamp latitudes beyond the poles to the nearest pole.
+    Clamp,
+
+    /// Wrap longitudes and reflect latitudes beyond the poles back over the pole.
+    ///
+    /// This continues on the other side of the pole, e.g. a latitude of `100.0` and longitude of
+    /// `10.0` become a latitude of `80.0` and longitude of `-170.0`.
+    Reflect,
+}
+
+impl Normalization {
+    /// Normalizes the latitude and longitude for the coordinate reference system.
+    pub(crate) fn apply(self, crs: &CoordRefSystem, latitude: f64, longitude: f64) -> (f64, f64) {
+        if self == Self::Off {
+            return (latitude, longitude);
+        }
+
+        let (mut latitude, mut longitude) = (latitude, longitude);
+        let [lat_axis, lon_axis, _] = crs.axes();
+        if lat_axis.unit() == "degree" && lon_axis.unit() == "degree" {
+            let over_pole;
+            (latitude, over_pole) = match self {
+                Self::Clamp => (latitude.clamp(-90.0, 90.0), false),
+                Self::Reflect => reflect_latitude(latitude),
+                _ => (latitude, false),
+            };
+            if over_pole {
+                longitude += 180.0;
+            }
+            longitude = wrap_longitude(longitude, crs.longitude_convention());
+        }
+        if crs.is_longitude_ignored(latitude) {
+            longitude = 0.0;
+        }
+
+        (unsigned_zero(latitude), unsigned_zero(longitude))
+    }
+}
+
+impl GeoUri {
+    /// Normalizes the coordinates of the geo URI to a canonical form.
+    ///
+    /// See [`Normalization`] for what this entails. Because the coordinates of a geo URI are
+    /// always in range, this never changes the location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::GeoUri;
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let mut geo_uri = GeoUri::parse("geo:-90,5.134,-0")?;
+    /// geo_uri.normalize();
+    /// assert_eq!(geo_uri, GeoUri::parse("geo:-90,0,0")?);
+    ///
+    /// let mut geo_uri = GeoUri::parse("geo:52.107,-180")?;
+    /// geo_uri.normalize();
+    /// assert_eq!(geo_uri.longitude(), 180.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn normalize(&mut self) {
+        (self.latitude, self.longitude) =
+            Normalization::Wrap.apply(&self.crs, self.latitude, self.longitude);
+        self.altitude = self.altitude.map(unsigned_zero);
+        self.uncertainty = self.uncertainty.map(unsigned_zero);
+    }
+
+    /// Returns a copy of the geo URI with its coordinates normalized to a canonical form.
+    ///
+    /// See [`GeoUri::normalize`].
+    #[must_use]
+    pub fn normalized(&self) -> Self {
+        let mut geo_uri = self.clone();
+        geo_uri.normalize();
+
+        geo_uri
+    }
+}
+
+/// Reflects a latitude beyond the poles back over the pole.
+///
+/// Also returns whether the latitude was reflected, i.e. whether the location is on the other
+/// side of the pole.
+fn reflect_latitude(latitude: f64) -> (f64, bool) {
+    let latitude = wrap_degrees(latitude);
+    if latitude > 90.0 {
+        (180.0 - latitude, true)
+    } else if latitude < -90.0 {
+        (-180.0 - latitude, true)
+    } else {
+        (latitude, false)
+    }
+}
+
+/// Wraps a longitude into the range of the longitude convention.
+pub(crate) fn wrap_longitude(longitude: f64, convention: LongitudeConvention) -> f64 {
+    match convention {
+        LongitudeConvention::East180 => wrap_degrees(longitude),
+        LongitudeConvention::East360 | LongitudeConvention::West360 => {
+            let longitude = longitude % 360.0;
+            let longitude = if longitude < 0.0 {
+                longitude + 360.0
+            } else {
+                longitude
+            };
+
+            // Adding to a tiny negative longitude can round up to a full turn.
+            if longitude >= 360.0 {
+                0.0
+            } else {
+                longitude
+            }
+        }
+    }
+}
+
+/// Wraps an angle in degrees into the range `-180.0` (exclusive) up until `180.0` (inclusive).
+///
+/// Angles that are already in range are returned unchanged.
+pub(crate) fn wrap_degrees(degrees: f64) -> f64 {
+    // The remainder is exact, so this doesn't introduce rounding errors.
+    let degrees = degrees % 360.0;
+    if degrees > 180.0 {
+        degrees - 360.0
+    } else if degrees <= -180.0 {
+        degrees + 360.0
+    } else {
+        degrees
+    }
+}
+
+/// Replaces a negative zero by a positive zero.
+pub(crate) fn unsigned_zero(value: f64) -> f64 {
+    if value == 0.0 {
+        0.0
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, GeoUriBuilderError};
+
+    #[test]
+    fn normalization_apply() {
+        let wgs84 = CoordRefSystem::Wgs84;
+        assert_eq!(Normalization::Off.apply(&wgs84, 100.0, -0.0), (100.0, -0.0));
+        assert_eq!(
+            Normalization::Wrap.apply(&wgs84, 52.107, 5.134),
+            (52.107, 5.134)
+        );
+        assert_eq!(
+            Normalization::Wrap.apply(&wgs84, 100.0, 190.0),
+            (100.0, -170.0)
+        );
+        assert_eq!(Normalization::Wrap.apply(&wgs84, 0.0, -180.0), (0.0, 180.0));
+        assert_eq!(Normalization::Wrap.apply(&wgs84, 0.0, 540.0), (0.0, 180.0));
+        assert_eq!(Normalization::Wrap.apply(&wgs84, 0.0, -900.5), (0.0, 179.5));
+        assert_eq!(
+            Normalization::Wrap.apply(&wgs84, -90.0, 5.134),
+            (-90.0, 0.0)
+        );
+        assert_eq!(Normalization::Clamp.apply(&wgs84, 100.0, 10.0), (90.0, 0.0));
+        assert_eq!(
+            Normalization::Clamp.apply(&wgs84, -91.5, 10.0),
+            (-90.0, 0.0)
+        );
+        assert_eq!(
+            Normalization::Reflect.apply(&wgs84, 100.0, 10.0),
+            (80.0, -170.0)
+        );
+        assert_eq!(
+            Normalization::Reflect.apply(&wgs84, -100.0, -10.0),
+            (-80.0, 170.0)
+        );
+        assert_eq!(
+            Normalization::Reflect.apply(&wgs84, 270.0, 10.0),
+            (-90.0, 0.0)
+        );
+        assert_eq!(
+            Normalization::Reflect.apply(&wgs84, 360.0, 10.0),
+            (0.0, 10.0)
+        );
+
+        let (latitude, longitude) = Normalization::Wrap.apply(&wgs84, -0.0, -0.0);
+        assert!(latitude.is_sign_positive());
+        assert!(longitude.is_sign_positive());
+
+        let mars = CoordRefSystem::Mars;
+        assert_eq!(Normalization::Wrap.apply(&mars, 4.5, 360.0), (4.5, 0.0));
+        assert_eq!(Normalization::Wrap.apply(&mars, 4.5, -22.6), (4.5, 337.4));
+        assert_eq!(Normalization::Wrap.apply(&mars, 4.5, -1e-20), (4.5, 0.0));
+        assert_eq!(
+            Normalization::Reflect.apply(&mars, 95.0, 90.0),
+            (85.0, 270.0)
+        );
+    }
+
+    #[test]
+    fn geo_uri_normalize() -> Result<(), Error> {
+        let mut geo_uri = GeoUri::parse("geo:-0,-180,-0;u=-0")?;
+        geo_uri.normalize();
+        assert_eq!(geo_uri.to_string(), "geo:0,180,0;u=0");
+
+        let geo_uri = GeoUri::parse("geo:90,5.134")?;
+        assert_eq!(geo_uri.normalized().longitude(), 0.0);
+        assert_eq!(geo_uri.longitude(), 5.134);
+
+        let geo_uri = GeoUri::parse("geo:52.1070,5.1340")?;
+        assert_eq!(geo_uri.normalized().to_string(), "geo:52.1070,5.1340");
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_builder_normalization() -> Result<(), GeoUriBuilderError> {
+        let mut builder = GeoUri::builder();
+        builder.latitude(95.0).longitude(185.0);
+        assert!(matches!(
+            builder.build(),
+            Err(GeoUriBuilderError::ValidationError(
+                Error::OutOfRangeLatitude
+            ))
+        ));
+
+        let geo_uri = builder.normalization(Normalization::Reflect).build()?;
+        assert_eq!(geo_uri.latitude(), 85.0);
+        assert_eq!(geo_uri.longitude(), 5.0);
+
+        let geo_uri = builder.normalization(Normalization::Clamp).build()?;
+        assert_eq!(geo_uri.latitude(), 90.0);
+        assert_eq!(geo_uri.longitude(), 0.0);
+
+        assert!(matches!(
+            builder.normalization(Normalization::Wrap).build(),
+            Err(GeoUriBuilderError::ValidationError(
+                Error::OutOfRangeLatitude
+            ))
+        ));
+
+        let geo_uri = builder.latitude(-0.0).altitude(-0.0).build()?;
+        assert_eq!(geo_uri.to_string(), "geo:0,-175,0");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,723 @@
+//! Parsing of geo URI strings.
+
+use core::fmt;
+use core::ops::Range;
+
+use crate::encoding::is_paramchar;
+use crate::{CoordRefSystem, Crs, Error, GeoUri, GeoUriRef};
+
+/// The mode in which geo URIs are parsed.
+///
+/// See [`ParseOptions`] for how to select a mode.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Parse geo URIs liberally.
+    ///
+    /// Coordinates and the uncertainty are accepted in any format Rust can parse a float from
+    /// (including `+` signs, exponents, `inf` and `nan`) and parameter names are matched
+    /// case-insensitively. Only a `crs` parameter that comes first and a `u` parameter that
+    /// comes first or right after it are interpreted; elsewhere, including duplicates, they are
+    /// ignored. Parameters without a value are skipped when looking for them. Invalid UTF-8 in
+    /// percent-decoded parameter names and values is replaced.
+    #[default]
+    Lenient,
+
+    /// Parse geo URIs following the ABNF of
+    /// [RFC 5870](https://www.rfc-editor.org/rfc/rfc5870#section-3.3) exactly.
+    ///
+    /// This means that coordinates and the uncertainty must follow the `num`/`pnum` grammar,
+    /// the `crs` parameter must precede the `u` parameter which in turn must precede all other
+    /// parameters, parameters may not occur more than once, the `crs` parameter value must be a
+    /// label of letters, digits and `-` and parameter names and values may only consist of the
+    /// allowed characters.
+    Strict,
+}
+
+/// Options for parsing geo URIs.
+///
+/// Use [`GeoUri::parse_with`] to parse a geo URI with these options.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{Error, GeoUri, ParseMode, ParseOptions};
+///
+/// let uri = "geo:52.107,5.134;u=1000;crs=wgs84";
+/// assert!(GeoUri::parse_with(uri, &ParseOptions::lenient()).is_ok());
+/// assert_eq!(
+///     GeoUri::parse_with(uri, &ParseOptions::strict()), // The `crs` parameter must come first!
+///     Err(Error::MisplacedParam)
+/// );
+///
+/// let mut options = ParseOptions::new();
+/// options.mode(ParseMode::Strict);
+/// assert_eq!(
+///     GeoUri::parse_with("geo:5.2107e1,5.134", &options),
+///     Err(Error::InvalidCoordSyntax)
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParseOptions {
+    /// The mode to parse in.
+    mode: ParseMode,
+
+    /// The custom coordinate reference systems recognized in the `crs` parameter.
+    custom_crs: &'static [&'static dyn Crs],
+}
+
+impl ParseOptions {
+    /// Returns the default parse options.
+    ///
+    /// By default, geo URIs are parsed in lenient mode.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the parse options for parsing in lenient mode.
+    pub fn lenient() -> Self {
+        Self {
+            mode: ParseMode::Lenient,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the parse options for parsing in strict mode.
+    pub fn strict() -> Self {
+        Self {
+            mode: ParseMode::Strict,
+            ..Self::default()
+        }
+    }
+
+    /// The mode to parse in.
+    pub fn mode(&mut self, value: ParseMode) -> &mut Self {
+        self.mode = value;
+
+        self
+    }
+
+    /// The custom coordinate reference systems recognized in the `crs` parameter.
+    ///
+    /// These are looked up by their (case-insensitive) [name](Crs::name) after the built-in
+    /// coordinate reference systems. See [`Crs`] for an example.
+    pub fn custom_crs(&mut self, value: &'static [&'static dyn Crs]) -> &mut Self {
+        self.custom_crs = value;
+
+        self
+    }
+
+    /// Returns whether parsing happens in strict mode.
+    pub(crate) fn is_strict(&self) -> bool {
+        self.mode == ParseMode::Strict
+    }
+
+    /// Looks up the built-in or custom coordinate reference system with the given name.
+    pub(crate) fn find_crs(&self, name: &str) -> Option<CoordRefSystem> {
+        CoordRefSystem::from_name(name).or_else(|| {
+            self.custom_crs
+                .iter()
+                .find(|crs| crs.name().eq_ignore_ascii_case(name))
+                .map(|crs| CoordRefSystem::Custom(*crs))
+        })
+    }
+}
+
+/// The component of a geo URI.
+///
+/// This is used by [`ParseError`] to indicate in which component parsing failed.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Component {
+    /// The scheme, i.e. the prefix `geo:`.
+    Scheme,
+
+    /// The coordinates as a whole.
+    Coords,
+
+    /// The latitude coordinate.
+    Latitude,
+
+    /// The longitude coordinate.
+    Longitude,
+
+    /// The altitude coordinate.
+    Altitude,
+
+    /// The value of the `crs` parameter.
+    CoordRefSystem,
+
+    /// The value of the `u` parameter.
+    Uncertainty,
+
+    /// The name of a parameter.
+    ParamName,
+
+    /// The value of a parameter.
+    ParamValue,
+}
+
+/// An error that occurred while parsing a geo URI, including its location.
+///
+/// It holds the [`Error`](enum@Error) that occurred, the byte range in the parsed string of the
+/// offending component, which component that was and a description of what was expected instead.
+/// Use [`GeoUri::parse_spanned`] to get this error when parsing.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::{Component, Error, GeoUri, ParseOptions};
+///
+/// let uri = "geo:52.107,5.1x4;u=10";
+/// let error = GeoUri::parse_spanned(uri, &ParseOptions::default()).unwrap_err();
+/// assert!(matches!(error.kind(), Error::InvalidCoord(_)));
+/// assert_eq!(error.span(), 11..16);
+/// assert_eq!(error.component(), Component::Longitude);
+/// assert_eq!(error.expected(), "a number");
+/// assert_eq!(
+///     error.diagnostic(uri).to_string(),
+///     "geo:52.107,5.1x4;u=10\n           ^^^^^ expected a number"
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error("{kind} (expected {expected} at {}..{})", .span.start, .span.end)]
+pub struct ParseError {
+    /// The error that occurred.
+    kind: Error,
+
+    /// The byte range of the offending component in the parsed string.
+    span: Range<usize>,
+
+    /// The offending component.
+    component: Component,
+
+    /// A description of what was expected.
+    expected: &'static str,
+}
+
+impl ParseError {
+    /// Creates a new parse error for the offending part of the URI.
+    ///
+    /// The part must be a subslice of the URI.
+    pub(crate) fn new(
+        uri: &str,
+        part: &str,
+        kind: Error,
+        component: Component,
+        expected: &'static str,
+    ) -> Self {
+        let start = part.as_ptr() as usize - uri.as_ptr() as usize;
+        debug_assert!(start + part.len() <= uri.len(), "part must be a subslice");
+
+        Self {
+            kind,
+            span: start..start + part.len(),
+            component,
+            expected,
+        }
+    }
+
+    /// Returns the error that occurred.
+    pub fn kind(&self) -> &Error {
+        &self.kind
+    }
+
+    /// Returns the byte range of the offending component in the parsed string.
+    ///
+    /// The range is empty if something is missing, it then points to where it was expected.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the offending component.
+    pub fn component(&self) -> Component {
+        self.component
+    }
+
+    /// Returns a description of what was expected.
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// Returns a diagnostic that renders the parsed string with the offending component
+    /// underlined by carets when displayed.
+    ///
+    /// The string must be the same as the one that was parsed.
+    pub fn diagnostic<'a>(&'a self, uri: &'a str) -> Diagnostic<'a> {
+        Diagnostic { error: self, uri }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        error.kind
+    }
+}
+
+/// A rendered diagnostic for a [`ParseError`].
+///
+/// When displayed, it shows the parsed string on the first line and underlines the offending
+/// component with carets on the second line, followed by what was expected.
+/// Use [`ParseError::diagnostic`] to construct it.
+#[derive(Clone, Copy, Debug)]
+pub struct Diagnostic<'a> {
+    /// The error to render.
+    error: &'a ParseError,
+
+    /// The string that was parsed.
+    uri: &'a str,
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Align by characters and not bytes; always underline at least one character.
+        let span = &self.error.span;
+        let column = self.uri.get(..span.start).map_or(0, |s| s.chars().count());
+        let width = self.uri.get(span.clone()).map_or(0, |s| s.chars().count());
+        writeln!(f, "{}", self.uri)?;
+        write!(f, "{:column$}", "")?;
+        for _ in 0..width.max(1) {
+            f.write_str("^")?;
+        }
+
+        write!(f, " expected {}", self.error.expected)
+    }
+}
+
+/// Parses a geo URI string using the provided options.
+///
+/// # Errors
+///
+/// Will return an error if the parsing fails in any way.
+pub(crate) fn parse(uri: &str, options: &ParseOptions) -> Result<GeoUri, ParseError> {
+    GeoUriRef::parse_with(uri, options)?.to_geo_uri()
+}
+
+/// Returns the number of decimal places of a number of the form `[-]digits[.digits]`.
+///
+/// Returns `None` if the number does not have this form, or if it has more than 15 digits since
+/// these cannot be represented exactly as [`f64`].
+pub(crate) fn num_decimals(s: &str) -> Option<u8> {
+    if !is_num(s) || s.bytes().filter(u8::is_ascii_digit).count() > 15 {
+        return None;
+    }
+
+    let decimals = s.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+    u8::try_from(decimals).ok()
+}
+
+/// Returns whether the string matches `num = [ "-" ] pnum`.
+pub(crate) fn is_num(s: &str) -> bool {
+    is_pnum(s.strip_prefix('-').unwrap_or(s))
+}
+
+/// Returns whether the string matches `pnum = 1*DIGIT [ "." 1*DIGIT ]`.
+pub(crate) fn is_pnum(s: &str) -> bool {
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    match s.split_once('.') {
+        Some((int, frac)) => is_digits(int) && is_digits(frac),
+        None => is_digits(s),
+    }
+}
+
+/// Returns whether the string matches `labeltext = 1*( alphanum / "-" )`.
+pub(crate) fn is_labeltext(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+/// Returns whether the string matches `pvalue = 1*paramchar`.
+///
+/// Here `paramchar = p-unreserved / unreserved / pct-encoded`.
+pub(crate) fn is_pvalue(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let is_hex_at = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_hexdigit);
+                if !is_hex_at(index + 1) || !is_hex_at(index + 2) {
+                    return false;
+                }
+                index += 3;
+            }
+            b if is_paramchar(b) => index += 1,
+            _ => return false,
+        }
+    }
+
+    !bytes.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the geo URI discarding the location of any error.
+    fn parse(uri: &str, options: &ParseOptions) -> Result<GeoUri, Error> {
+        super::parse(uri, options).map_err(Error::from)
+    }
+
+    #[test]
+    fn parse_options() {
+        assert_eq!(ParseOptions::default().mode, ParseMode::Lenient);
+        assert_eq!(ParseOptions::new().mode, ParseMode::Lenient);
+        assert_eq!(ParseOptions::lenient().mode, ParseMode::Lenient);
+        assert_eq!(ParseOptions::strict().mode, ParseMode::Strict);
+
+        let mut options = ParseOptions::new();
+        options.mode(ParseMode::Strict);
+        assert_eq!(options.mode, ParseMode::Strict);
+
+        assert!(options.custom_crs.is_empty());
+        assert_eq!(options.find_crs("WGS84"), Some(CoordRefSystem::Wgs84));
+        assert_eq!(options.find_crs("foo"), None);
+    }
+
+    #[test]
+    fn parse_lenient() -> Result<(), Error> {
+        let options = ParseOptions::lenient();
+
+        let geo_uri = parse("GEO:+52.107,5.134e0,3.6;U=25000;foo", &options)?;
+        assert_eq!(geo_uri.latitude, 52.107);
+        assert_eq!(geo_uri.longitude, 5.134);
+        assert_eq!(geo_uri.altitude, Some(3.6));
+        assert_eq!(geo_uri.uncertainty, Some(25_000.0));
+
+        // Only a first "crs" parameter and a "u" parameter that comes first or right after it
+        // are interpreted.
+        let geo_uri = parse("geo:52.107,5.134;CRS=wgs84;u=100;crs=foo;u=200", &options)?;
+        assert_eq!(geo_uri.crs, CoordRefSystem::Wgs84);
+        assert_eq!(geo_uri.uncertainty, Some(100.0));
+        let geo_uri = parse("geo:52.107,5.134;u=100;crs=foo", &options)?;
+        assert_eq!(geo_uri.uncertainty, Some(100.0));
+        let geo_uri = parse("geo:52.107,5.134;foo=bar;u=100;crs=foo", &options)?;
+        assert_eq!(geo_uri.uncertainty, None);
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            geo_uri.params,
+            vec![(String::from("foo"), String::from("bar"))]
+        );
+
+        let geo_uri = parse("geo:52.107,5.134;crs;u;;Foo=Bar;baz", &options)?;
+        assert_eq!(geo_uri.uncertainty, None);
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            geo_uri.params,
+            vec![
+                (String::from("Foo"), String::from("Bar")),
+                (String::from("baz"), String::new())
+            ]
+        );
+
+        #[cfg(feature = "alloc")]
+        {
+            let geo_uri = parse(
+                "geo:52.107,5.134;%66oo=a%20b%3b%zz;caf%C3%A9=caf%C3%A9",
+                &options,
+            )?;
+            assert_eq!(
+                geo_uri.params,
+                vec![
+                    (String::from("foo"), String::from("a b;%zz")),
+                    (String::from("café"), String::from("café"))
+                ]
+            );
+
+            let geo_uri = parse("geo:52.107,5.134;foo=%C3", &options)?;
+            assert_eq!(
+                geo_uri.params,
+                vec![(String::from("foo"), String::from("\u{FFFD}"))]
+            );
+
+            let geo_uri = parse("geo:52.107,5.134;%63rs=moon;%75=5", &options)?;
+            assert_eq!(geo_uri.crs, CoordRefSystem::Wgs84);
+            assert_eq!(geo_uri.uncertainty, None);
+            assert!(geo_uri.params.is_empty());
+        }
+
+        let geo_uri = parse("geo:nan,5.134", &options);
+        assert_eq!(geo_uri, Err(Error::OutOfRangeLatitude));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_strict() -> Result<(), Error> {
+        let options = ParseOptions::strict();
+
+        let geo_uri = parse(
+            "GEO:52.107,-5.134,3;CRS=WGS84;U=25000;foo=a-b%2F[c]:&+$._~;bar",
+            &options,
+        )?;
+        assert_eq!(geo_uri.crs, CoordRefSystem::Wgs84);
+        assert_eq!(geo_uri.latitude, 52.107);
+        assert_eq!(geo_uri.longitude, -5.134);
+        assert_eq!(geo_uri.altitude, Some(3.0));
+        assert_eq!(geo_uri.uncertainty, Some(25_000.0));
+
+        assert_eq!(
+            parse("geo:+52.107,5.134", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,.5", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,5e1", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,inf", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+        assert_eq!(
+            parse("geo:nan,5.134", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134,3,4", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134 ", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+
+        assert_eq!(
+            parse("geo:52.107,5.134;u=-1", &options),
+            Err(Error::InvalidUncertaintySyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;u=1e3", &options),
+            Err(Error::InvalidUncertaintySyntax)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;crs=foo", &options),
+            Err(Error::InvalidCoordRefSystem)
+        );
+
+        assert_eq!(
+            parse("geo:52.107,5.134;u=1;crs=wgs84", &options),
+            Err(Error::MisplacedParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo=bar;u=1", &options),
+            Err(Error::MisplacedParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo;crs=wgs84", &options),
+            Err(Error::MisplacedParam)
+        );
+
+        assert_eq!(
+            parse("geo:52.107,5.134;u=1;U=2", &options),
+            Err(Error::DuplicateParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;crs=wgs84;crs=wgs84", &options),
+            Err(Error::DuplicateParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo=1;FOO=2", &options),
+            Err(Error::DuplicateParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo;foo", &options),
+            Err(Error::DuplicateParam)
+        );
+
+        assert_eq!(
+            parse("geo:52.107,5.134;", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;u", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;fo_o=bar", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo=", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo=b r", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo=b%2", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo=b%zz", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134;foo=a=b", &options),
+            Err(Error::InvalidParam)
+        );
+        assert_eq!(
+            parse("geo:52.107,5.134?q=foo", &options),
+            Err(Error::InvalidCoordSyntax)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_error() {
+        let spanned = |uri, options| super::parse(uri, &options).unwrap_err();
+
+        let error = spanned("gea:52.107,5.134", ParseOptions::lenient());
+        assert_eq!(error.kind(), &Error::MissingScheme);
+        assert_eq!((error.span(), error.component()), (0..0, Component::Scheme));
+
+        let error = spanned("geo:;u=10", ParseOptions::lenient());
+        assert_eq!(error.kind(), &Error::MissingCoords);
+        assert_eq!((error.span(), error.component()), (4..4, Component::Coords));
+
+        let error = spanned("geo:52.107;u=10", ParseOptions::lenient());
+        assert_eq!(error.kind(), &Error::MissingLongitude);
+        assert_eq!(
+            (error.span(), error.component()),
+            (10..10, Component::Longitude)
+        );
+
+        let error = spanned("geo:52.107,5.134,x", ParseOptions::lenient());
+        assert!(matches!(error.kind(), Error::InvalidCoord(_)));
+        assert_eq!(
+            (error.span(), error.component()),
+            (17..18, Component::Altitude)
+        );
+
+        let error = spanned("geo:52.107,-200", ParseOptions::lenient());
+        assert_eq!(error.kind(), &Error::OutOfRangeLongitude);
+        assert_eq!(
+            (error.span(), error.component()),
+            (11..15, Component::Longitude)
+        );
+
+        let error = spanned("geo:52.107,5.134;crs=foo", ParseOptions::lenient());
+        assert_eq!(error.kind(), &Error::InvalidCoordRefSystem);
+        assert_eq!(
+            (error.span(), error.component()),
+            (21..24, Component::CoordRefSystem)
+        );
+
+        let error = spanned("geo:52.107,5.134;u=-5", ParseOptions::lenient());
+        assert_eq!(error.kind(), &Error::OutOfRangeUncertainty);
+        assert_eq!(
+            (error.span(), error.component()),
+            (19..21, Component::Uncertainty)
+        );
+
+        #[cfg(feature = "alloc")]
+        {
+            let error = spanned("geo:52.107,5.134;foo=%FF", ParseOptions::strict());
+            assert_eq!(error.kind(), &Error::InvalidParam);
+            assert_eq!(
+                (error.span(), error.component()),
+                (21..24, Component::ParamValue)
+            );
+        }
+
+        let error = spanned("geo:52.107,5.134;u=1e3", ParseOptions::strict());
+        assert_eq!(error.kind(), &Error::InvalidUncertaintySyntax);
+        assert_eq!(
+            (error.span(), error.component()),
+            (19..22, Component::Uncertainty)
+        );
+
+        let error = spanned("geo:52.107,5.134;foo;f_o", ParseOptions::strict());
+        assert_eq!(error.kind(), &Error::InvalidParam);
+        assert_eq!(
+            (error.span(), error.component()),
+            (21..24, Component::ParamName)
+        );
+
+        let error = spanned("geo:52.107,5.134;foo;FOO", ParseOptions::strict());
+        assert_eq!(error.kind(), &Error::DuplicateParam);
+        assert_eq!(
+            (error.span(), error.component()),
+            (21..24, Component::ParamName)
+        );
+
+        let error = spanned("geo:52.107,5.134;foo;u=1", ParseOptions::strict());
+        assert_eq!(error.kind(), &Error::MisplacedParam);
+        assert_eq!(
+            (error.span(), error.component()),
+            (21..22, Component::ParamName)
+        );
+
+        let error = spanned("geo:52.107,5.134;u", ParseOptions::strict());
+        assert_eq!(error.kind(), &Error::InvalidParam);
+        assert_eq!(
+            (error.span(), error.component()),
+            (18..18, Component::ParamValue)
+        );
+        assert_eq!(
+            error.to_string(),
+            "Invalid parameter in geo URI (expected `=` followed by a parameter value at 18..18)"
+        );
+    }
+
+    #[test]
+    fn diagnostic() {
+        let uri = "geo:52.107,5.1x4";
+        let error = super::parse(uri, &ParseOptions::lenient()).unwrap_err();
+        assert_eq!(
+            error.diagnostic(uri).to_string(),
+            "geo:52.107,5.1x4\n           ^^^^^ expected a number"
+        );
+
+        let uri = "geo:52.107";
+        let error = super::parse(uri, &ParseOptions::lenient()).unwrap_err();
+        assert_eq!(
+            error.diagnostic(uri).to_string(),
+            "geo:52.107\n          ^ expected `,` followed by a longitude"
+        );
+
+        let uri = "geo:52.107,5.134;crs=föö;naam=café";
+        let error = super::parse(uri, &ParseOptions::lenient()).unwrap_err();
+        assert_eq!(
+            error.diagnostic(uri).to_string(),
+            "geo:52.107,5.134;crs=föö;naam=café\n                     ^^^ expected a supported coordinate reference system like `wgs84`"
+        );
+    }
+
+    #[test]
+    fn grammar() {
+        assert_eq!(num_decimals("52"), Some(0));
+        assert_eq!(num_decimals("-52.1070"), Some(4));
+        assert_eq!(num_decimals("1.23456789012345"), Some(14));
+        assert_eq!(num_decimals("0.123456789012345"), None);
+        assert_eq!(num_decimals("5.2107e1"), None);
+        assert_eq!(num_decimals("+52.1"), None);
+
+        assert!(is_num("0"));
+        assert!(is_num("-0.5"));
+        assert!(!is_num("-"));
+        assert!(!is_num("--1"));
+        assert!(is_pnum("12.34"));
+        assert!(!is_pnum("-12.34"));
+        assert!(!is_pnum("1.2.3"));
+        assert!(!is_pnum(""));
+
+        assert!(is_labeltext("crs"));
+        assert!(is_labeltext("x-Foo-1"));
+        assert!(!is_labeltext(""));
+        assert!(!is_labeltext("foo_bar"));
+
+        assert!(is_pvalue("%2d%2D"));
+        assert!(!is_pvalue(""));
+        assert!(!is_pvalue("%"));
+        assert!(!is_pvalue("é"));
+    }
+}
@@ -0,0 +1,379 @@
+//! Rhumb lines (loxodromes), which cross all meridians at the same angle.
+
+use core::f64::consts::FRAC_PI_2;
+
+use crate::geodesic::{a1m1f, atan2d, c1f, c1pf, norm, sin_series, sincosd};
+use crate::geodesy::{bearing, Distance};
+use crate::normalize::wrap_degrees;
+use crate::{Crs, Ellipsoid, Error, GeoUri, GeodesicMethod};
+
+/// The difference in isometric latitude below which the radius of the parallel at the mean
+/// latitude is used instead of the ratio of the differences in meridian distance and isometric
+/// latitude, which suffers from cancellation.
+const PARALLEL_THRESHOLD: f64 = 1e-6;
+
+/// Rhumb lines on an ellipsoid of revolution.
+///
+/// A meridian is a geodesic, so the meridian distance and its inverse use the same series
+/// expansions as the geodesic problems, which are accurate to round-off.
+#[derive(Copy, Clone, Debug)]
+struct Rhumb {
+    /// The equatorial radius.
+    a: f64,
+
+    /// The first eccentricity.
+    e: f64,
+
+    /// One minus the flattening.
+    f1: f64,
+
+    /// The length of a quarter meridian divided by `PI / 2`.
+    rectifying_radius: f64,
+
+    /// The coefficients of the `C1` series for meridians.
+    c1a: [f64; 7],
+
+    /// The coefficients of the reverted `C1` series for meridians.
+    c1pa: [f64; 7],
+}
+
+impl Rhumb {
+    /// Creates the rhumb line problems for the ellipsoid.
+    fn new(ellipsoid: &Ellipsoid) -> Self {
+        let f = ellipsoid.flattening();
+        // The expansion parameter of a meridian is the third flattening.
+        let n = f / (2.0 - f);
+
+        Self {
+            a: ellipsoid.equatorial_radius(),
+            e: (f * (2.0 - f)).sqrt(),
+            f1: 1.0 - f,
+            rectifying_radius: ellipsoid.polar_radius() * (1.0 + a1m1f(n)),
+            c1a: c1f(n),
+            c1pa: c1pf(n),
+        }
+    }
+
+    /// Solves the inverse problem: the rhumb line between two points.
+    ///
+    /// The points are given as latitude and longitude in degrees, with east-positive longitudes.
+    /// Returns the distance and the azimuth in degrees. The rhumb line crosses the antimeridian
+    /// if that is shorter, and runs along a meridian if either point is at a pole.
+    fn inverse(&self, lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> (f64, f64) {
+        let delta_lambda = if lat1.abs() == 90.0 || lat2.abs() == 90.0 {
+            0.0
+        } else {
+            wrap_degrees(lon2 - lon1).to_radians()
+        };
+
+        let north = self.meridian_distance(lat2) - self.meridian_distance(lat1);
+        let east = delta_lambda * self.parallel_scale(lat1, lat2, north);
+
+        (north.hypot(east), east.atan2(north).to_degrees())
+    }
+
+    /// Solves the direct problem: the point reached by following the rhumb line from a point with
+    /// an azimuth for a distance.
+    ///
+    /// The points are given as latitude and longitude in degrees, with east-positive longitudes,
+    /// and the azimuth is in degrees clockwise from the north. The distance may be negative to
+    /// follow the rhumb line backwards. A rhumb line spirals around a pole in a finite distance,
+    /// so the point reached is the pole if the distance is longer than that.
+    fn direct(&self, lat1: f64, lon1: f64, azimuth: f64, distance: f64) -> (f64, f64) {
+        let (sin_alpha, cos_alpha) = sincosd(azimuth);
+        let north = distance * cos_alpha;
+        let mu2 = (self.meridian_distance(lat1) + north) / self.rectifying_radius;
+        if mu2.abs() >= FRAC_PI_2 {
+            return (90.0_f64.copysign(mu2), 0.0);
+        }
+
+        let lat2 = self.latitude(mu2);
+        let delta_lambda = if lat1.abs() == 90.0 {
+            0.0
+        } else {
+            distance * sin_alpha / self.parallel_scale(lat1, lat2, north)
+        };
+
+        (lat2, lon1 + delta_lambda.to_degrees())
+    }
+
+    /// Returns the ratio of the distance along a rhumb line to the east and the difference in
+    /// longitude in radians between two latitudes in degrees, given the meridian distance
+    /// between them.
+    fn parallel_scale(&self, lat1: f64, lat2: f64, north: f64) -> f64 {
+        let delta_psi = self.isometric_latitude(lat2) - self.isometric_latitude(lat1);
+        if delta_psi.abs() > PARALLEL_THRESHOLD {
+            north / delta_psi
+        } else {
+            let (sin_phi, cos_phi) = sincosd((lat1 + lat2) / 2.0);
+            self.a * cos_phi / (1.0 - (self.e * sin_phi).powi(2)).sqrt()
+        }
+    }
+
+    /// Returns the distance along the meridian from the equator to a latitude in degrees.
+    fn meridian_distance(&self, lat: f64) -> f64 {
+        let (sin_phi, cos_phi) = sincosd(lat);
+        let (sbet, cbet) = norm(self.f1 * sin_phi, cos_phi);
+
+        self.rectifying_radius * (sbet.atan2(cbet) + sin_series(sbet, cbet, &self.c1a))
+    }
+
+    /// Returns the latitude in degrees of a rectifying latitude in radians, i.e. the inverse of
+    /// the meridian distance divided by the rectifying radius.
+    fn latitude(&self, mu: f64) -> f64 {
+        let (sin_mu, cos_mu) = mu.sin_cos();
+        let (sbet, cbet) = (mu + sin_series(sin_mu, cos_mu, &self.c1pa)).sin_cos();
+
+        atan2d(sbet, self.f1 * cbet)
+    }
+
+    /// Returns the isometric latitude of a latitude in degrees.
+    fn isometric_latitude(&self, lat: f64) -> f64 {
+        let (sin_phi, cos_phi) = sincosd(lat);
+
+        (sin_phi / cos_phi).asinh() - self.e * (self.e * sin_phi).atanh()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl GeoUri {
+    /// Returns the distance to the location of another geo URI along the rhumb line, and its
+    /// constant azimuth.
+    ///
+    /// A rhumb line (or loxodrome) crosses all meridians at the same angle, so it can be
+    /// followed with a constant heading (bearing), unlike a geodesic, but it is longer. The
+    /// azimuths of the returned distance are both that heading. Of the two rhumb lines around
+    /// the globe, the one that is shorter is used, which may cross the antimeridian. If either
+    /// location is at a pole, the rhumb line is the meridian through the other one.
+    ///
+    /// The rhumb line is computed on the reference ellipsoid of the coordinate reference system
+    /// or on a sphere with its mean radius, depending on the method. The altitudes are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:51.9225,4.4792")?; // Rotterdam
+    /// let geo_uri2 = GeoUri::parse("geo:40.6892,-74.0445")?; // New York
+    /// let rhumb = geo_uri.rhumb_distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+    /// let geodesic = geo_uri.distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+    /// assert!(rhumb.distance() > geodesic.distance());
+    /// assert_eq!(rhumb.initial_azimuth(), rhumb.final_azimuth());
+    /// assert_eq!(rhumb.initial_azimuth().round(), 258.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems or if
+    /// the coordinate reference system has no reference ellipsoid.
+    pub fn rhumb_distance_to(
+        &self,
+        other: &Self,
+        method: GeodesicMethod,
+    ) -> Result<Distance, Error> {
+        let rhumb = Rhumb::new(&rhumb_ellipsoid(self.common_ellipsoid(other)?, method));
+        let (lat1, lon1) = self.east_coords();
+        let (lat2, lon2) = other.east_coords();
+        let (distance, azimuth) = rhumb.inverse(lat1, lon1, lat2, lon2);
+
+        Ok(Distance {
+            distance,
+            initial_azimuth: bearing(azimuth),
+            final_azimuth: bearing(azimuth),
+        })
+    }
+
+    /// Returns the geo URI of the location reached by travelling a distance along the rhumb line
+    /// with an azimuth.
+    ///
+    /// This is the location reached when keeping a constant heading (bearing), see
+    /// [`GeoUri::rhumb_distance_to`]. Because a rhumb line that is not a meridian spirals
+    /// around a pole in a finite distance, the location reached is the pole if the distance is
+    /// longer than that. See [`GeoUri::destination`] for the other properties of the resulting
+    /// geo URI.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let geo_uri = GeoUri::parse("geo:51.9225,4.4792")?; // Rotterdam
+    /// let destination = geo_uri.rhumb_destination(1e6, 270.0, GeodesicMethod::Ellipsoidal)?;
+    /// assert!((destination.latitude() - 51.9225).abs() < 1e-9);
+    /// assert_eq!(destination.longitude().round(), -10.0);
+    ///
+    /// let destination = geo_uri.rhumb_destination(1e8, 45.0, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(destination.latitude(), 90.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system has no reference ellipsoid, or
+    /// if the resulting coordinates are invalid, e.g. because the distance is not finite.
+    pub fn rhumb_destination(
+        &self,
+        distance: f64,
+        azimuth: f64,
+        method: GeodesicMethod,
+    ) -> Result<Self, Error> {
+        let ellipsoid = self.crs.ellipsoid().ok_or(Error::MissingEllipsoid)?;
+        let rhumb = Rhumb::new(&rhumb_ellipsoid(ellipsoid, method));
+        let (lat1, lon1) = self.east_coords();
+        let (latitude, longitude) = rhumb.direct(lat1, lon1, azimuth, distance);
+
+        self.at_east_coords(latitude, longitude, self.altitude)
+    }
+}
+
+/// Returns the ellipsoid to compute rhumb lines on for the method.
+fn rhumb_ellipsoid(ellipsoid: Ellipsoid, method: GeodesicMethod) -> Ellipsoid {
+    match method {
+        GeodesicMethod::Spherical => {
+            let radius = ellipsoid.mean_radius();
+            Ellipsoid::new(radius, radius)
+        }
+        GeodesicMethod::Ellipsoidal => ellipsoid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_near;
+
+    #[test]
+    fn rhumb_meridian_distance() {
+        let rhumb = Rhumb::new(&Ellipsoid::WGS84);
+        assert_eq!(rhumb.meridian_distance(0.0), 0.0);
+        assert_near(rhumb.meridian_distance(90.0), 10_001_965.729_313, 1e-6);
+        assert_near(rhumb.meridian_distance(-90.0), -10_001_965.729_313, 1e-6);
+        assert_near(rhumb.meridian_distance(45.0), 4_984_944.377_98, 1e-5);
+
+        for lat in [-89.9, -45.0, 0.0, 12.3, 52.1, 89.99] {
+            let mu = rhumb.meridian_distance(lat) / rhumb.rectifying_radius;
+            assert_near(rhumb.latitude(mu), lat, 1e-12);
+        }
+        assert_eq!(rhumb.latitude(FRAC_PI_2), 90.0);
+    }
+
+    #[test]
+    fn geo_uri_rhumb_distance_to() -> Result<(), Error> {
+        let rotterdam = GeoUri::parse("geo:51.9225,4.4792")?;
+        let new_york = GeoUri::parse("geo:40.6892,-74.0445")?;
+        let distance = rotterdam.rhumb_distance_to(&new_york, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 6_146_084.553, 1e-3);
+        assert_near(distance.initial_azimuth(), 258.277_970_9, 1e-7);
+        assert_eq!(distance.final_azimuth(), distance.initial_azimuth());
+        let back = new_york.rhumb_distance_to(&rotterdam, GeodesicMethod::Ellipsoidal)?;
+        assert_near(back.distance(), distance.distance(), 1e-6);
+        assert_near(
+            back.initial_azimuth(),
+            distance.initial_azimuth() - 180.0,
+            1e-9,
+        );
+
+        // Along a parallel the rhumb line has the length of the arc of the parallel.
+        let geo_uri = GeoUri::parse("geo:60,179.5")?;
+        let geo_uri2 = GeoUri::parse("geo:60,-179.5")?;
+        let distance = geo_uri.rhumb_distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 55_800.002, 1e-3);
+        assert_eq!(distance.initial_azimuth(), 90.0);
+        let distance = geo_uri.rhumb_distance_to(&geo_uri2, GeodesicMethod::Spherical)?;
+        assert_near(distance.distance(), 55_597.540, 1e-3);
+
+        // Along the equator the rhumb line is the geodesic.
+        let geo_uri = GeoUri::parse("geo:0,0")?;
+        let geo_uri2 = GeoUri::parse("geo:0,-1")?;
+        let distance = geo_uri.rhumb_distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 111_319.491, 1e-3);
+        assert_eq!(distance.initial_azimuth(), 270.0);
+
+        let north_pole = GeoUri::parse("geo:90,5.134")?;
+        let geo_uri = GeoUri::parse("geo:0,-22.43")?;
+        let distance = geo_uri.rhumb_distance_to(&north_pole, GeodesicMethod::Ellipsoidal)?;
+        assert_near(distance.distance(), 10_001_965.729, 1e-3);
+        assert_eq!(distance.initial_azimuth(), 0.0);
+        let distance = north_pole.rhumb_distance_to(&geo_uri, GeodesicMethod::Ellipsoidal)?;
+        assert_eq!(distance.initial_azimuth(), 180.0);
+        let distance = north_pole.rhumb_distance_to(&north_pole, GeodesicMethod::Spherical)?;
+        assert_eq!(distance.distance(), 0.0);
+
+        let geo_uri2 = GeoUri::parse("geo:0,0;crs=mars")?;
+        assert_eq!(
+            geo_uri.rhumb_distance_to(&geo_uri2, GeodesicMethod::Ellipsoidal),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_rhumb_destination() -> Result<(), Error> {
+        let rotterdam = GeoUri::parse("geo:51.9225,4.4792,5")?;
+        let new_york = GeoUri::parse("geo:40.6892,-74.0445")?;
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let distance = rotterdam.rhumb_distance_to(&new_york, method)?;
+            let destination = rotterdam.rhumb_destination(
+                distance.distance(),
+                distance.initial_azimuth(),
+                method,
+            )?;
+            assert_near(destination.latitude(), new_york.latitude(), 1e-9);
+            assert_near(destination.longitude(), new_york.longitude(), 1e-9);
+            assert_eq!(destination.altitude(), Some(5.0));
+
+            let destination = new_york.rhumb_destination(
+                -distance.distance(),
+                distance.initial_azimuth(),
+                method,
+            )?;
+            assert_near(destination.latitude(), rotterdam.latitude(), 1e-9);
+            assert_near(destination.longitude(), rotterdam.longitude(), 1e-9);
+
+            // Crossing the antimeridian.
+            let geo_uri = GeoUri::parse("geo:-30,170")?;
+            let destination = geo_uri.rhumb_destination(3e6, 100.0, method)?;
+            assert!(destination.longitude() < -150.0);
+            let distance = geo_uri.rhumb_distance_to(&destination, method)?;
+            assert_near(distance.distance(), 3e6, 1e-6);
+            assert_near(distance.initial_azimuth(), 100.0, 1e-9);
+
+            // Spiralling into the pole.
+            let geo_uri = GeoUri::parse("geo:-89,10")?;
+            let destination = geo_uri.rhumb_destination(1e6, 200.0, method)?;
+            assert_eq!(destination.latitude(), -90.0);
+            assert_eq!(destination.longitude(), 0.0);
+            let destination = geo_uri.rhumb_destination(100_000.0, 179.0, method)?;
+            assert!(destination.latitude() > -90.0);
+
+            // Leaving the pole along the meridian.
+            let geo_uri = GeoUri::parse("geo:90,45")?;
+            let destination = geo_uri.rhumb_destination(1e6, 180.0, method)?;
+            assert!(destination.latitude() < 90.0);
+            assert_eq!(destination.longitude(), 0.0);
+        }
+
+        let geo_uri = GeoUri::parse("geo:4.5,359.5;crs=mars")?;
+        let destination =
+            geo_uri.rhumb_destination(100_000.0, 90.0, GeodesicMethod::Ellipsoidal)?;
+        assert_near(destination.latitude(), 4.5, 1e-9);
+        assert!(destination.longitude() > 1.0 && destination.longitude() < 2.0);
+
+        let geo_uri = GeoUri::parse("geo:0,0")?;
+        assert_eq!(
+            geo_uri.rhumb_destination(f64::NAN, 0.0, GeodesicMethod::Spherical),
+            Err(Error::OutOfRangeLatitude)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,453 @@
+//! Finding geo URIs in free text.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::ops::Range;
+
+use crate::{GeoUri, ParseOptions, URI_SCHEME_NAME};
+
+/// Characters that are stripped from the end of a match, because they are more likely to be
+/// punctuation of the surrounding text than part of the geo URI.
+///
+/// A closing `]` is also stripped if it has no matching opening `[` in the match.
+const TRAILING_PUNCTUATION: &[u8] = b".,;:&=";
+
+/// Separators at which a candidate match is truncated if it cannot be parsed.
+const SEPARATORS: &[u8] = b";&,";
+
+/// The number of bytes after the scheme within which a candidate match is parsed first.
+///
+/// This bounds the work for each candidate that cannot be parsed, as long as its coordinates
+/// fit within it.
+const HEAD_LEN: usize = 256;
+
+/// Returns an iterator over the geo URIs found in the text.
+///
+/// A geo URI is recognized when the scheme `geo:` (case-insensitive) does not directly follow
+/// a letter or digit. It extends as far as the characters allowed in a geo URI go, so it stops
+/// at whitespace, quotes, angle brackets, parentheses and the like. Similar to how URL
+/// linkifiers work, trailing punctuation (`.`, `,`, `;`, `:`, `&`, `=` and an unbalanced `]`) is
+/// not considered part of the geo URI. If it still cannot be parsed and the last part cannot be
+/// parsed, the symbols at its end are stripped, and otherwise it is shortened at the first
+/// separator (`;`, `&`, `,`) in the part that cannot be parsed, or else the last one before it,
+/// until it can be. A geo URI never extends across the scheme of a following one.
+/// Candidates that cannot be parsed at all are skipped, as are geo URIs with coordinates longer
+/// than 256 bytes.
+///
+/// The geo URIs are parsed using [`GeoUri::parse`].
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::GeoUri;
+///
+/// let text = "Meet at <geo:52.107,5.134;u=10>, or else at geo:52.090,5.121.";
+/// let mut matches = geo_uri::find_iter(text);
+///
+/// let m = matches.next().expect("a match");
+/// assert_eq!(m.as_str(), "geo:52.107,5.134;u=10");
+/// assert_eq!(m.span(), 9..30);
+/// assert_eq!(m.geo_uri().uncertainty(), Some(10.0));
+///
+/// let m = matches.next().expect("a match");
+/// assert_eq!(m.as_str(), "geo:52.090,5.121");
+/// assert_eq!(m.geo_uri().latitude(), 52.09);
+///
+/// assert!(matches.next().is_none());
+/// ```
+pub fn find_iter(text: &str) -> Matches<'_> {
+    Matches {
+        text,
+        pos: 0,
+        run_end: 0,
+    }
+}
+
+/// Replaces all geo URIs found in the text by the result of the callback.
+///
+/// See [`find_iter`] for how geo URIs are found.
+///
+/// # Examples
+///
+/// ```rust
+/// let text = "Meet at geo:52.107,5.134, near the tower.";
+/// let replaced = geo_uri::replace_all(text, |m| {
+///     format!("<a href=\"{}\">{}</a>", m.as_str(), m.as_str())
+/// });
+/// assert_eq!(
+///     replaced,
+///     "Meet at <a href=\"geo:52.107,5.134\">geo:52.107,5.134</a>, near the tower."
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn replace_all<F>(text: &str, mut replacement: F) -> String
+where
+    F: FnMut(&Match<'_>) -> String,
+{
+    let mut replaced = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for m in find_iter(text) {
+        replaced.push_str(&text[last_end..m.start()]);
+        replaced.push_str(&replacement(&m));
+        last_end = m.end();
+    }
+    replaced.push_str(&text[last_end..]);
+
+    replaced
+}
+
+/// A geo URI found in a text.
+///
+/// See [`find_iter`].
+#[derive(Clone, Debug)]
+pub struct Match<'a> {
+    /// The text of the geo URI.
+    text: &'a str,
+
+    /// The byte range of the geo URI in the text that was searched.
+    span: Range<usize>,
+
+    /// The parsed geo URI.
+    geo_uri: GeoUri,
+}
+
+impl<'a> Match<'a> {
+    /// Returns the text of the geo URI.
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns the byte offset of the start of the geo URI in the text that was searched.
+    pub fn start(&self) -> usize {
+        self.span.start
+    }
+
+    /// Returns the byte offset of the end of the geo URI in the text that was searched.
+    pub fn end(&self) -> usize {
+        self.span.end
+    }
+
+    /// Returns the byte range of the geo URI in the text that was searched.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the parsed geo URI.
+    pub fn geo_uri(&self) -> &GeoUri {
+        &self.geo_uri
+    }
+
+    /// Converts the match into the parsed geo URI.
+    pub fn into_geo_uri(self) -> GeoUri {
+        self.geo_uri
+    }
+}
+
+/// Iterator over the geo URIs found in a text.
+///
+/// Use [`find_iter`] to construct it.
+#[derive(Clone, Debug)]
+pub struct Matches<'a> {
+    /// The text that is searched.
+    text: &'a str,
+
+    /// The byte offset in the text to continue searching from.
+    pos: usize,
+
+    /// The byte offset of the end of the last found run of characters allowed in a geo URI.
+    run_end: usize,
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = Match<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.text.as_bytes();
+        let scheme_len = URI_SCHEME_NAME.len() + 1;
+
+        while let Some(offset) = bytes[self.pos..]
+            .windows(scheme_len)
+            .position(|window| window.eq_ignore_ascii_case(b"geo:"))
+        {
+            let start = self.pos + offset;
+            self.pos = start + scheme_len;
+
+            // The scheme should not be the end of another word, e.g. `ageo:`.
+            if start > 0 && bytes[start - 1].is_ascii_alphanumeric() {
+                continue;
+            }
+
+            // Find the end of the run of characters allowed in a geo URI once for all candidates
+            // that start in it.
+            if self.pos > self.run_end {
+                self.run_end = bytes[self.pos..]
+                    .iter()
+                    .position(|b| !is_uri_char(*b))
+                    .map_or(bytes.len(), |len| self.pos + len);
+            }
+
+            // A candidate never extends across the scheme of a following geo URI.
+            let run_end = find_scheme(&bytes[..self.run_end], self.pos).unwrap_or(self.run_end);
+
+            // First parse the candidate up to the last parameter that starts within the head, so
+            // that candidates that cannot be parsed do not take time proportional to the run.
+            let mut end = run_end;
+            let mut is_head = false;
+            let mut is_partial = false;
+            if end > self.pos + HEAD_LEN {
+                let head = &bytes[self.pos..self.pos + HEAD_LEN];
+                end = self.pos + head.iter().rposition(|b| *b == b';').unwrap_or(HEAD_LEN);
+                is_head = true;
+                is_partial = true;
+            }
+            loop {
+                let mut unmatched_brackets = None;
+                while end > self.pos {
+                    if bytes[end - 1] == b']' {
+                        let unmatched = unmatched_brackets
+                            .get_or_insert_with(|| unmatched_brackets_len(&bytes[self.pos..end]));
+                        if *unmatched == 0 {
+                            break;
+                        }
+                        *unmatched -= 1;
+                    } else if !TRAILING_PUNCTUATION.contains(&bytes[end - 1]) {
+                        break;
+                    }
+                    end -= 1;
+                }
+
+                // All candidate characters are ASCII, so this is always a character boundary.
+                let candidate = &self.text[start..end];
+                let error = match GeoUri::parse_spanned(candidate, &ParseOptions::default()) {
+                    // Only the parameters that are interpreted as `crs` or `u` can make the rest
+                    // of the candidate fail in lenient mode. Parse up to these after the head,
+                    // and only then parse the candidate completely.
+                    Ok(_) if is_partial => {
+                        end = run_end;
+                        if is_head {
+                            is_head = false;
+                            let params_end =
+                                self.pos + interpreted_path_len(&bytes[self.pos..run_end]);
+                            if params_end < run_end {
+                                end = params_end;
+                                continue;
+                            }
+                        }
+                        is_partial = false;
+                        continue;
+                    }
+                    Ok(geo_uri) => {
+                        self.pos = end;
+                        return Some(Match {
+                            text: candidate,
+                            span: start..end,
+                            geo_uri,
+                        });
+                    }
+                    Err(error) => error,
+                };
+                is_head = false;
+                is_partial = false;
+
+                // If the last part could not be parsed, strip the symbols at its end, which are
+                // more likely to be part of the surrounding text, e.g. `geo:1,2$`.
+                let error_start = (start + error.span().start).clamp(self.pos, end);
+                let error_end = (start + error.span().end).clamp(error_start, end);
+                if error_end == end {
+                    let symbols_start = bytes[error_start..end]
+                        .iter()
+                        .rposition(u8::is_ascii_alphanumeric)
+                        .map_or(error_start, |index| error_start + index + 1);
+                    if symbols_start > error_start && symbols_start < end {
+                        end = symbols_start;
+                        continue;
+                    }
+                }
+
+                // Shorten the candidate at the first separator in the part that could not be
+                // parsed, or else the last separator before it. A part that contains a separator
+                // cannot be parsed when shortened at a later separator either.
+                let is_separator = |b: &u8| SEPARATORS.contains(b);
+                match bytes[error_start..error_end]
+                    .iter()
+                    .position(is_separator)
+                    .map(|index| error_start + index)
+                    .or_else(|| {
+                        bytes[self.pos..error_start]
+                            .iter()
+                            .rposition(is_separator)
+                            .map(|index| self.pos + index)
+                    }) {
+                    Some(separator) => end = separator,
+                    None => break,
+                }
+            }
+        }
+        self.pos = bytes.len();
+
+        None
+    }
+}
+
+/// Returns the byte offset of the first scheme of a geo URI in the bytes from the offset on.
+///
+/// As in [`find_iter`], the scheme should not directly follow a letter or digit.
+fn find_scheme(bytes: &[u8], offset: usize) -> Option<usize> {
+    let scheme_len = URI_SCHEME_NAME.len() + 1;
+
+    bytes[offset..]
+        .windows(scheme_len)
+        .enumerate()
+        .map(|(index, window)| (offset + index, window))
+        .find(|(start, window)| {
+            window.eq_ignore_ascii_case(b"geo:") && !bytes[start - 1].is_ascii_alphanumeric()
+        })
+        .map(|(start, _)| start)
+}
+
+/// Returns the number of closing `]` in the bytes that have no matching opening `[`.
+fn unmatched_brackets_len(bytes: &[u8]) -> usize {
+    let count = |bracket| bytes.iter().filter(|b| **b == bracket).count();
+
+    count(b']').saturating_sub(count(b'['))
+}
+
+/// Returns the length of the start of the geo URI path up to and including the first two
+/// parameters with a value.
+///
+/// In lenient mode, only these can be interpreted as the `crs` and `u` parameters.
+fn interpreted_path_len(path: &[u8]) -> usize {
+    let mut len = 0;
+    let mut params_with_value = 0;
+    for (index, part) in path.split(|b| *b == b';').enumerate() {
+        len += usize::from(index > 0) + part.len();
+        if index > 0 && part.contains(&b'=') {
+            params_with_value += 1;
+            if params_with_value == 2 {
+                break;
+            }
+        }
+    }
+
+    len
+}
+
+/// Returns whether the byte can occur in a geo URI after the scheme.
+///
+/// These are the characters of the `geo-path` and `paramchar` grammar of RFC 5870, including
+/// `%` for percent-encoding.
+fn is_uri_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~[]:&+$;=,%".contains(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CoordRefSystem;
+
+    /// Returns the texts of the geo URIs found in the text.
+    fn find_all(text: &str) -> Vec<&str> {
+        find_iter(text).map(|m| m.as_str()).collect()
+    }
+
+    #[test]
+    fn scan_find_iter() {
+        assert_eq!(find_all(""), Vec::<&str>::new());
+        assert_eq!(find_all("no geo URIs here: geo:"), Vec::<&str>::new());
+        assert_eq!(find_all("geo:52.107,5.134"), vec!["geo:52.107,5.134"]);
+        assert_eq!(
+            find_all("GEO:52.107,5.134;u=10 and geo:1,2,3."),
+            vec!["GEO:52.107,5.134;u=10", "geo:1,2,3"]
+        );
+        assert_eq!(
+            find_all("(see geo:52.107,5.134), \"geo:1,2\" <geo:3,4>"),
+            vec!["geo:52.107,5.134", "geo:1,2", "geo:3,4"]
+        );
+        assert_eq!(
+            find_all("<a href=\"geo:52.107,5.134;u=10&amp;z=1\">here</a>"),
+            vec!["geo:52.107,5.134;u=10"]
+        );
+        assert_eq!(
+            find_all("geo:52.107,5.134;foo=bar;, or geo:52.107,5.134,x"),
+            vec!["geo:52.107,5.134;foo=bar", "geo:52.107,5.134"]
+        );
+        assert_eq!(find_all("ageo:1,2 geo:x,y geo:100,5"), Vec::<&str>::new());
+        assert_eq!(find_all("café geo:1,2 ☕"), vec!["geo:1,2"]);
+
+        // Closing brackets, symbols and schemes of following geo URIs.
+        assert_eq!(find_all("see [geo:3,4] now"), vec!["geo:3,4"]);
+        for symbol in ["$", "~", "+", "_", "-", "%"] {
+            assert_eq!(find_all(&format!("geo:3,4{symbol} x")), vec!["geo:3,4"]);
+        }
+        assert_eq!(find_all("geo:3,4;u=5] x"), vec!["geo:3,4;u=5"]);
+        assert_eq!(find_all("geo:3,4;foo=bar] x"), vec!["geo:3,4;foo=bar"]);
+        assert_eq!(find_all("geo:3,4;foo=[bar] x"), vec!["geo:3,4;foo=[bar]"]);
+        assert_eq!(find_all("geo:3,4;u=5$"), vec!["geo:3,4;u=5"]);
+        assert_eq!(find_all("geo:3,4;u=$"), vec!["geo:3,4"]);
+        assert_eq!(find_all("geo:1,2:geo:3,4"), vec!["geo:1,2", "geo:3,4"]);
+        assert_eq!(
+            find_all("geo:1,2;foo=geo:3,4"),
+            vec!["geo:1,2;foo", "geo:3,4"]
+        );
+        assert_eq!(
+            find_all("geo:1,2;foo=ageo:3,4"),
+            vec!["geo:1,2;foo=ageo:3,4"]
+        );
+
+        let text = "Somewhere, at geo:52.107,5.134.";
+        let m = find_iter(text).next().expect("a match");
+        assert_eq!(m.start(), 14);
+        assert_eq!(m.end(), 30);
+        assert_eq!(&text[m.span()], m.as_str());
+        assert_eq!(m.clone().into_geo_uri(), *m.geo_uri());
+    }
+
+    #[test]
+    fn scan_find_iter_long() {
+        // A long candidate that only parses when shortened to its start.
+        let mut text = String::from("geo:1,2;u=x");
+        text.extend(core::iter::repeat(";u=x").take(100_000));
+        assert_eq!(find_all(&text), vec!["geo:1,2"]);
+
+        // Many candidates in a single run that cannot be parsed.
+        assert_eq!(find_all(&"geo:".repeat(100_000)), Vec::<&str>::new());
+        assert_eq!(find_all(&"geo:1,".repeat(100_000)), Vec::<&str>::new());
+        assert_eq!(find_all(&"geo:100,0;".repeat(10_000)), Vec::<&str>::new());
+        assert_eq!(
+            find_all(&format!("geo:1,2{}", "]".repeat(100_000))),
+            vec!["geo:1,2"]
+        );
+
+        // A long geo URI is still found completely.
+        let mut text = String::from("geo:1,2,3;u=5");
+        text.extend(core::iter::repeat(";foo=bar").take(100_000));
+        let m = find_iter(&text).next().expect("a match");
+        assert_eq!(m.end(), text.len());
+        assert_eq!(m.geo_uri().altitude(), Some(3.0));
+
+        // The first `crs` parameter can come after the head if preceded by parameters without
+        // a value.
+        let mut text = String::from("geo:1,2");
+        text.extend(core::iter::repeat(";foo").take(100));
+        assert_eq!(find_all(&format!("{text};crs=foo")), vec![text.as_str()]);
+        assert_eq!(
+            find_iter(&format!("{text};crs=moon"))
+                .next()
+                .map(|m| m.geo_uri().crs()),
+            Some(CoordRefSystem::Moon)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn scan_replace_all() {
+        assert_eq!(
+            replace_all("At geo:52.107,5.134; and geo:1,2.", |m| {
+                format!("[{}]", m.geo_uri().latitude())
+            }),
+            "At [52.107]; and [1]."
+        );
+        assert_eq!(replace_all("nothing", |_| String::new()), "nothing");
+    }
+}
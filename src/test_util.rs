@@ -0,0 +1,50 @@
+//! Fixtures shared by the tests of the geodesic computations.
+
+use crate::{CoordRefSystem, Crs, Ellipsoid, Error, LongitudeConvention};
+
+/// A coordinate reference system on the WGS-84 ellipsoid with west-positive longitudes.
+#[derive(Debug)]
+pub(crate) struct WestEarth;
+
+impl Crs for WestEarth {
+    fn name(&self) -> &str {
+        "west-earth"
+    }
+
+    fn validate(&self, latitude: f64, longitude: f64) -> Result<(), Error> {
+        CoordRefSystem::Wgs84.validate(latitude, longitude - 180.0)
+    }
+
+    fn ellipsoid(&self) -> Option<Ellipsoid> {
+        Some(Ellipsoid::WGS84)
+    }
+
+    fn longitude_convention(&self) -> LongitudeConvention {
+        LongitudeConvention::West360
+    }
+}
+
+/// A coordinate reference system without a reference ellipsoid.
+#[derive(Debug)]
+pub(crate) struct Grid;
+
+impl Crs for Grid {
+    fn name(&self) -> &str {
+        "grid"
+    }
+
+    fn validate(&self, _latitude: f64, _longitude: f64) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+pub(crate) static WEST_EARTH: WestEarth = WestEarth;
+pub(crate) static GRID: Grid = Grid;
+
+/// Asserts that a number is within a tolerance of the expected number.
+pub(crate) fn assert_near(number: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (number - expected).abs() <= tolerance,
+        "{number} is not within {tolerance} of {expected}"
+    );
+}
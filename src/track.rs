@@ -0,0 +1,488 @@
+//! Distances to and closest locations on paths along geodesics.
+
+use crate::geodesy::{direct, inverse};
+use crate::{Error, GeoUri, GeodesicMethod};
+
+/// The maximum number of iterations to find the foot of the perpendicular to a geodesic.
+const MAX_ITERATIONS: u32 = 20;
+
+/// The step in meters below which the foot of the perpendicular to a geodesic is found.
+const TOLERANCE: f64 = 1e-6;
+
+/// The foot of the perpendicular from a location to a geodesic.
+#[derive(Copy, Clone, Debug)]
+struct Foot {
+    /// The latitude and longitude increasing eastwards, in degrees.
+    coords: (f64, f64),
+
+    /// The signed distance from the start of the geodesic in meters.
+    along_track: f64,
+
+    /// The signed distance from the geodesic in meters, positive to the right.
+    cross_track: f64,
+
+    /// The distance from the start to the end of the geodesic in meters.
+    length: f64,
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl GeoUri {
+    /// Returns the distance from the location to the geodesic through the locations of two
+    /// other geo URIs.
+    ///
+    /// The distance is signed: it is positive if the location is to the right of the geodesic
+    /// when travelling from the start to the end, and negative if it is to the left. The
+    /// geodesic is extended beyond the start and end, see [`GeoUri::closest_point_on_segment`]
+    /// for the distance to the segment between them. If the start and end coincide, the
+    /// geodesic is the meridian through them.
+    ///
+    /// The distance is computed on the reference ellipsoid of the coordinate reference system
+    /// using the method, and the altitudes are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let start = GeoUri::parse("geo:0,0")?;
+    /// let end = GeoUri::parse("geo:0,10")?;
+    /// let geo_uri = GeoUri::parse("geo:-0.001,5")?;
+    /// let distance = geo_uri.cross_track_distance(&start, &end, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(distance.round(), 111.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems, if the
+    /// coordinate reference system has no reference ellipsoid or if the location is (nearly) a
+    /// quarter of the circumference away from the geodesic in all directions.
+    pub fn cross_track_distance(
+        &self,
+        start: &Self,
+        end: &Self,
+        method: GeodesicMethod,
+    ) -> Result<f64, Error> {
+        Ok(self.foot(start, end, method)?.cross_track)
+    }
+
+    /// Returns the distance along the geodesic through the locations of two other geo URIs
+    /// from the start to the point closest to the location.
+    ///
+    /// The distance is signed: it is negative if the closest point lies before the start. See
+    /// [`GeoUri::cross_track_distance`] for how the geodesic and the distance are determined.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let start = GeoUri::parse("geo:0,0")?;
+    /// let end = GeoUri::parse("geo:0,10")?;
+    /// let geo_uri = GeoUri::parse("geo:-0.001,1")?;
+    /// let distance = geo_uri.along_track_distance(&start, &end, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(distance.round(), 111_319.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems, if the
+    /// coordinate reference system has no reference ellipsoid or if the location is (nearly) a
+    /// quarter of the circumference away from the geodesic in all directions.
+    pub fn along_track_distance(
+        &self,
+        start: &Self,
+        end: &Self,
+        method: GeodesicMethod,
+    ) -> Result<f64, Error> {
+        Ok(self.foot(start, end, method)?.along_track)
+    }
+
+    /// Returns the geo URI of the point on the geodesic segment between the locations of two
+    /// other geo URIs that is closest to the location, and the distance to it in meters.
+    ///
+    /// This is the foot of the perpendicular to the geodesic (see
+    /// [`GeoUri::cross_track_distance`]) if it lies between the start and the end, and the
+    /// nearest of them otherwise. If both the start and the end have an altitude, it is
+    /// interpolated linearly, otherwise the closest point has none. See
+    /// [`GeoUri::destination`] for the other properties of the resulting geo URI.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let start = GeoUri::parse("geo:0,0,0")?;
+    /// let end = GeoUri::parse("geo:0,10,100")?;
+    /// let geo_uri = GeoUri::parse("geo:1,2")?;
+    /// let (closest, distance) =
+    ///     geo_uri.closest_point_on_segment(&start, &end, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(closest.latitude(), 0.0);
+    /// assert_eq!(closest.longitude().round(), 2.0);
+    /// assert_eq!(closest.altitude().map(f64::round), Some(20.0));
+    /// assert_eq!(distance.round(), 110_574.0);
+    ///
+    /// let geo_uri = GeoUri::parse("geo:1,-2")?;
+    /// let (closest, _) =
+    ///     geo_uri.closest_point_on_segment(&start, &end, GeodesicMethod::Ellipsoidal)?;
+    /// assert_eq!(closest, start);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URIs have different coordinate reference systems, if the
+    /// coordinate reference system has no reference ellipsoid or if the location is (nearly) a
+    /// quarter of the circumference away from the geodesic in all directions.
+    pub fn closest_point_on_segment(
+        &self,
+        start: &Self,
+        end: &Self,
+        method: GeodesicMethod,
+    ) -> Result<(Self, f64), Error> {
+        let foot = self.foot(start, end, method)?;
+        let (coords, fraction) = if foot.along_track <= 0.0 {
+            (start.east_coords(), 0.0)
+        } else if foot.along_track >= foot.length {
+            (end.east_coords(), 1.0)
+        } else {
+            (foot.coords, foot.along_track / foot.length)
+        };
+
+        let altitude = start.interpolate_altitude(end, fraction);
+        let closest = start.at_east_coords(coords.0, coords.1, altitude)?;
+        let distance = self.distance_to(&closest, method)?.distance();
+
+        Ok((closest, distance))
+    }
+
+    /// Returns the geo URI of the point on a path that is closest to the location, and the
+    /// distance to it in meters.
+    ///
+    /// The path consists of the geodesic segments between consecutive geo URIs, see
+    /// [`GeoUri::closest_point_on_segment`]. A path of a single geo URI is that location.
+    /// Segments on which the closest point is indeterminate are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo_uri::{GeoUri, GeodesicMethod};
+    /// # use geo_uri::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let path = [
+    ///     GeoUri::parse("geo:52.0907,5.1214")?, // Utrecht
+    ///     GeoUri::parse("geo:52.3676,4.9041")?, // Amsterdam
+    ///     GeoUri::parse("geo:52.3874,4.6462")?, // Haarlem
+    /// ];
+    /// let geo_uri = GeoUri::parse("geo:52.38,4.8")?;
+    /// let (closest, distance) =
+    ///     geo_uri.closest_point_on_path(&path, GeodesicMethod::Ellipsoidal)?;
+    /// assert!(closest.longitude() > 4.6462 && closest.longitude() < 4.9041);
+    /// assert!(distance < 500.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the path is empty, if the geo URIs have different coordinate
+    /// reference systems, if the coordinate reference system has no reference ellipsoid or if
+    /// the location is (nearly) a quarter of the circumference away from the geodesics of all
+    /// segments of the path in all directions.
+    pub fn closest_point_on_path(
+        &self,
+        path: &[Self],
+        method: GeodesicMethod,
+    ) -> Result<(Self, f64), Error> {
+        if let [point] = path {
+            return self.closest_point_on_segment(point, point, method);
+        }
+
+        let mut closest: Option<(Self, f64)> = None;
+        let mut is_indeterminate = false;
+        for segment in path.windows(2) {
+            let candidate = match self.closest_point_on_segment(&segment[0], &segment[1], method) {
+                Ok(candidate) => candidate,
+                // The closest point can still be determined on the other segments.
+                Err(Error::IndeterminateClosestPoint) => {
+                    is_indeterminate = true;
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+            if closest
+                .as_ref()
+                .map_or(true, |(_, distance)| candidate.1 < *distance)
+            {
+                closest = Some(candidate);
+            }
+        }
+
+        closest.ok_or(if is_indeterminate {
+            Error::IndeterminateClosestPoint
+        } else {
+            Error::MissingGeoUris
+        })
+    }
+
+    /// Returns the foot of the perpendicular from the location to the geodesic through the
+    /// locations of two other geo URIs.
+    ///
+    /// This iterates the along-track distance by solving the right spherical triangle between
+    /// the location, the current point on the geodesic and the foot, which is exact on a sphere
+    /// and converges quickly on an ellipsoid (S. Baselga and J. C. Martínez-Llario, "Intersection
+    /// and point-to-line solutions for geodesics on the ellipsoid", Stud. Geophys. Geod. 62,
+    /// 353–363 (2018)).
+    fn foot(&self, start: &Self, end: &Self, method: GeodesicMethod) -> Result<Foot, Error> {
+        let ellipsoid = start.common_ellipsoid(end)?;
+        self.common_ellipsoid(start)?;
+        let radius = ellipsoid.mean_radius();
+        let coords = self.east_coords();
+        let line = inverse(&ellipsoid, method, start.east_coords(), end.east_coords());
+
+        let mut foot = Foot {
+            coords: start.east_coords(),
+            along_track: 0.0,
+            cross_track: 0.0,
+            length: line.distance(),
+        };
+        for iteration in 0.. {
+            if iteration == MAX_ITERATIONS {
+                return Err(Error::IndeterminateClosestPoint);
+            }
+
+            let (latitude, longitude, azimuth) = direct(
+                &ellipsoid,
+                method,
+                start.east_coords(),
+                line.initial_azimuth(),
+                foot.along_track,
+            );
+            let to_location = inverse(&ellipsoid, method, (latitude, longitude), coords);
+            let angle = (to_location.initial_azimuth() - azimuth).to_radians();
+            foot.coords = (latitude, longitude);
+            foot.cross_track = to_location.distance().copysign(angle.sin()) + 0.0;
+
+            let distance = to_location.distance() / radius;
+            let step = radius * (distance.sin() * angle.cos()).atan2(distance.cos());
+            if step.abs() < TOLERANCE {
+                break;
+            }
+            foot.along_track += step;
+        }
+
+        Ok(foot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_near;
+
+    #[test]
+    fn geo_uri_track_distance() -> Result<(), Error> {
+        let start = GeoUri::parse("geo:53.3206,-1.7297")?;
+        let end = GeoUri::parse("geo:53.1887,0.1334")?;
+        let geo_uri = GeoUri::parse("geo:53.2611,-0.7972")?;
+        let method = GeodesicMethod::Spherical;
+        let cross_track = geo_uri.cross_track_distance(&start, &end, method)?;
+        assert_near(cross_track, -307.550, 1e-3);
+        let along_track = geo_uri.along_track_distance(&start, &end, method)?;
+        assert_near(along_track, 62_331.579, 1e-3);
+
+        let method = GeodesicMethod::Ellipsoidal;
+        let foot = geo_uri.foot(&start, &end, method)?;
+        assert_near(foot.cross_track, cross_track, cross_track.abs() * 0.005);
+        assert_near(foot.along_track, along_track, along_track * 0.005);
+
+        // The foot lies on the geodesic, perpendicular to the location.
+        let foot_uri = start.at_east_coords(foot.coords.0, foot.coords.1, None)?;
+        let to_foot = start.distance_to(&foot_uri, method)?;
+        let from_foot = foot_uri.distance_to(&end, method)?;
+        assert_near(to_foot.distance(), foot.along_track, 1e-6);
+        assert_near(from_foot.distance(), foot.length - foot.along_track, 1e-6);
+        assert_near(to_foot.final_azimuth(), from_foot.initial_azimuth(), 1e-9);
+        let to_location = foot_uri.distance_to(&geo_uri, method)?;
+        assert_near(to_location.distance(), -foot.cross_track, 1e-6);
+        assert_near(
+            to_location.initial_azimuth(),
+            to_foot.final_azimuth() - 90.0,
+            1e-6,
+        );
+
+        // Beyond the start and end, across the antimeridian and far from the geodesic.
+        let start = GeoUri::parse("geo:0,170")?;
+        let end = GeoUri::parse("geo:0,-170")?;
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let geo_uri = GeoUri::parse("geo:-30,160")?;
+            let distance = geo_uri.distance_to(&GeoUri::parse("geo:0,160")?, method)?;
+            let cross_track = geo_uri.cross_track_distance(&start, &end, method)?;
+            assert_near(cross_track, distance.distance(), 1e-6);
+            let along_track = geo_uri.along_track_distance(&start, &end, method)?;
+            assert!(along_track < -1e6);
+
+            let geo_uri = GeoUri::parse("geo:10,-175")?;
+            assert!(geo_uri.cross_track_distance(&start, &end, method)? < 0.0);
+            let along_track = geo_uri.along_track_distance(&start, &end, method)?;
+            let half = start.distance_to(&end, method)?.distance() * 0.75;
+            assert_near(along_track, half, 1e-6);
+
+            let on_track = GeoUri::parse("geo:0,175")?;
+            assert_near(
+                on_track.cross_track_distance(&start, &end, method)?,
+                0.0,
+                1e-6,
+            );
+        }
+
+        // More than a quarter of the circumference along the geodesic.
+        let start = GeoUri::parse("geo:0,0")?;
+        let end = GeoUri::parse("geo:0,10")?;
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let geo_uri = GeoUri::parse("geo:0,120")?;
+            let distance = start.distance_to(&geo_uri, method)?.distance();
+            let along_track = geo_uri.along_track_distance(&start, &end, method)?;
+            assert_near(along_track, distance, 1e-6);
+            let cross_track = geo_uri.cross_track_distance(&start, &end, method)?;
+            assert_near(cross_track, 0.0, 1e-6);
+        }
+
+        let geo_uri = GeoUri::parse("geo:90,0")?;
+        assert_eq!(
+            geo_uri.cross_track_distance(&start, &end, GeodesicMethod::Spherical),
+            Err(Error::IndeterminateClosestPoint)
+        );
+
+        let geo_uri = GeoUri::parse("geo:0,0;crs=moon")?;
+        assert_eq!(
+            geo_uri.cross_track_distance(&start, &end, method),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_closest_point_on_segment() -> Result<(), Error> {
+        let start = GeoUri::parse("geo:0,0,0;u=10")?;
+        let end = GeoUri::parse("geo:0,10,100")?;
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let geo_uri = GeoUri::parse("geo:1,2")?;
+            let (closest, distance) = geo_uri.closest_point_on_segment(&start, &end, method)?;
+            assert_near(closest.latitude(), 0.0, 1e-12);
+            assert_near(closest.longitude(), 2.0, 1e-9);
+            assert_near(closest.altitude().unwrap_or_default(), 20.0, 1e-9);
+            assert_eq!(closest.uncertainty(), None);
+            assert_near(
+                distance,
+                geo_uri.distance_to(&closest, method)?.distance(),
+                0.0,
+            );
+
+            let geo_uri = GeoUri::parse("geo:-1,-2")?;
+            let (closest, distance) = geo_uri.closest_point_on_segment(&start, &end, method)?;
+            assert_eq!(closest.latitude(), 0.0);
+            assert_eq!(closest.longitude(), 0.0);
+            assert_eq!(closest.altitude(), Some(0.0));
+            assert_near(
+                distance,
+                geo_uri.distance_to(&start, method)?.distance(),
+                0.0,
+            );
+
+            let geo_uri = GeoUri::parse("geo:1,12")?;
+            let (closest, _) = geo_uri.closest_point_on_segment(&start, &end, method)?;
+            assert_eq!(closest, end);
+
+            let (closest, distance) = geo_uri.closest_point_on_segment(&end, &end, method)?;
+            assert_eq!(closest, end);
+            assert_eq!(distance, geo_uri.distance_to(&end, method)?.distance());
+        }
+
+        let end = GeoUri::parse("geo:0,10")?;
+        let geo_uri = GeoUri::parse("geo:1,2")?;
+        let (closest, _) =
+            geo_uri.closest_point_on_segment(&start, &end, GeodesicMethod::Ellipsoidal)?;
+        assert_eq!(closest.altitude(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_closest_point_on_path() -> Result<(), Error> {
+        let mut path = Vec::new();
+        for uri in ["geo:0,0", "geo:0,10", "geo:10,10", "geo:10,0"] {
+            path.push(GeoUri::parse(uri)?);
+        }
+
+        let method = GeodesicMethod::Ellipsoidal;
+        let geo_uri = GeoUri::parse("geo:5,8")?;
+        let (closest, distance) = geo_uri.closest_point_on_path(&path, method)?;
+        assert_near(closest.longitude(), 10.0, 1e-9);
+        assert!(closest.latitude() > 4.9 && closest.latitude() < 5.1);
+        let (_, segment_distance) = geo_uri.closest_point_on_segment(&path[1], &path[2], method)?;
+        assert_eq!(distance, segment_distance);
+
+        let geo_uri = GeoUri::parse("geo:11,-1")?;
+        let (closest, _) = geo_uri.closest_point_on_path(&path, method)?;
+        assert_eq!(closest, path[3]);
+
+        let (closest, distance) = geo_uri.closest_point_on_path(&path[..1], method)?;
+        assert_eq!(closest, path[0]);
+        assert_eq!(distance, geo_uri.distance_to(&path[0], method)?.distance());
+
+        assert_eq!(
+            geo_uri.closest_point_on_path(&[], method),
+            Err(Error::MissingGeoUris)
+        );
+
+        // The location is at the pole of the great circle of the first segment.
+        let method = GeodesicMethod::Spherical;
+        let north_pole = GeoUri::parse("geo:90,0")?;
+        assert_eq!(
+            north_pole.closest_point_on_path(&path[..2], method),
+            Err(Error::IndeterminateClosestPoint)
+        );
+        let (closest, distance) = north_pole.closest_point_on_path(&path[..3], method)?;
+        assert_eq!(closest, path[2]);
+        assert_eq!(
+            distance,
+            north_pole.distance_to(&path[2], method)?.distance()
+        );
+        path.push(GeoUri::parse("geo:0,0;crs=mars")?);
+        assert_eq!(
+            geo_uri.closest_point_on_path(&path, method),
+            Err(Error::MismatchedCoordRefSystem)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_track_distance_pole() -> Result<(), Error> {
+        let start = GeoUri::parse("geo:80,0")?;
+        let end = GeoUri::parse("geo:80,180")?;
+        let geo_uri = GeoUri::parse("geo:85,90")?;
+        for method in [GeodesicMethod::Spherical, GeodesicMethod::Ellipsoidal] {
+            let (closest, distance) = geo_uri.closest_point_on_segment(&start, &end, method)?;
+            assert_near(closest.latitude(), 90.0, 1e-9);
+            let north_pole = GeoUri::parse("geo:90,0")?;
+            let to_pole = geo_uri.distance_to(&north_pole, method)?.distance();
+            assert_near(distance, to_pole, 1e-3);
+        }
+
+        Ok(())
+    }
+}
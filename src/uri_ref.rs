@@ -0,0 +1,706 @@
+//! Borrowed geo URIs.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::iter::Skip;
+use core::str::Split;
+
+#[cfg(feature = "alloc")]
+use crate::encoding::{percent_decode, percent_decode_lossy};
+use crate::format::Decimals;
+#[cfg(feature = "alloc")]
+use crate::is_reserved_param_name;
+use crate::parser::{is_labeltext, is_num, is_pnum, is_pvalue, num_decimals};
+use crate::{Component, CoordRefSystem, Error, GeoUri, ParseError, ParseOptions};
+
+/// A borrowed view of a geo URI string.
+///
+/// Contrary to [`GeoUri`], this does not allocate: parsing only determines where the components
+/// are located in the string (and checks their syntax in strict mode). The raw components can be
+/// accessed as string slices, and are parsed into numbers and validated when they are accessed.
+/// Use [`GeoUriRef::to_geo_uri`] to get an owned and fully validated [`GeoUri`].
+///
+/// # Examples
+///
+/// ```rust
+/// use geo_uri::GeoUriRef;
+/// # use geo_uri::ParseError;
+///
+/// # fn main() -> Result<(), ParseError> {
+/// let geo_uri_ref = GeoUriRef::parse("geo:52.107,5.134;crs=wgs84;u=1000;foo=bar%20baz")?;
+/// assert_eq!(geo_uri_ref.latitude_str(), "52.107");
+/// assert_eq!(geo_uri_ref.latitude()?, 52.107);
+/// assert_eq!(geo_uri_ref.crs_str(), Some("wgs84"));
+/// assert_eq!(geo_uri_ref.uncertainty_str(), Some("1000"));
+/// assert_eq!(geo_uri_ref.params().collect::<Vec<_>>(), vec![("foo", "bar%20baz")]);
+///
+/// let geo_uri = geo_uri_ref.to_geo_uri()?;
+/// # #[cfg(feature = "alloc")]
+/// assert_eq!(geo_uri.param("foo"), Some("bar baz"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GeoUriRef<'a> {
+    /// The complete geo URI string.
+    uri: &'a str,
+
+    /// The raw latitude coordinate.
+    latitude: &'a str,
+
+    /// The raw longitude coordinate.
+    longitude: &'a str,
+
+    /// The raw altitude coordinate, if provided.
+    altitude: Option<&'a str>,
+
+    /// The raw value of the `crs` parameter, if provided.
+    crs: Option<&'a str>,
+
+    /// The coordinate reference system the `crs` parameter refers to, if it is known.
+    known_crs: Option<CoordRefSystem>,
+
+    /// The raw value of the `u` parameter, if provided.
+    uncertainty: Option<&'a str>,
+
+    /// The raw parameters part, i.e. everything after the coordinates.
+    ///
+    /// If not empty, it starts with the separator `;`.
+    params: &'a str,
+
+    /// Whether the geo URI string was parsed in strict mode.
+    strict: bool,
+}
+
+impl<'a> GeoUriRef<'a> {
+    /// Try parsing a geo URI string into a `GeoUriRef`.
+    ///
+    /// This parses in lenient mode, see [`ParseMode::Lenient`](crate::ParseMode::Lenient).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URI string is structurally invalid, i.e. if the scheme or
+    /// the latitude or longitude coordinate is missing.
+    pub fn parse(uri: &'a str) -> Result<Self, ParseError> {
+        Self::parse_with(uri, &ParseOptions::default())
+    }
+
+    /// Try parsing a geo URI string into a `GeoUriRef` using the provided options.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the geo URI string is structurally invalid, or in strict mode, if
+    /// it does not follow the syntax of RFC 5870.
+    pub fn parse_with(uri: &'a str, options: &ParseOptions) -> Result<Self, ParseError> {
+        let error =
+            |part, kind, component, expected| ParseError::new(uri, part, kind, component, expected);
+
+        let uri_path = strip_scheme(uri)
+            .ok_or_else(|| error(&uri[..0], Error::MissingScheme, Component::Scheme, "`geo:`"))?;
+        let (coords_part, params) = uri_path.split_at(uri_path.find(';').unwrap_or(uri_path.len()));
+
+        // Split the coordinate part.
+        // Don't iterate over anything if the coordinate part is empty!
+        let mut coords = if coords_part.is_empty() {
+            return Err(error(
+                coords_part,
+                Error::MissingCoords,
+                Component::Coords,
+                "coordinates",
+            ));
+        } else {
+            coords_part.splitn(3, ',')
+        };
+        let coords_end = &coords_part[coords_part.len()..];
+        let latitude = coords.next().ok_or_else(|| {
+            // This cannot really happen
+            error(
+                coords_end,
+                Error::MissingLatitude,
+                Component::Latitude,
+                "a latitude",
+            )
+        })?;
+        let longitude = coords.next().ok_or_else(|| {
+            error(
+                coords_end,
+                Error::MissingLongitude,
+                Component::Longitude,
+                "`,` followed by a longitude",
+            )
+        })?;
+        let altitude = coords.next();
+
+        if options.is_strict() {
+            for (coord, component) in [
+                (Some(latitude), Component::Latitude),
+                (Some(longitude), Component::Longitude),
+                (altitude, Component::Altitude),
+            ] {
+                if let Some(coord) = coord.filter(|coord| !is_num(coord)) {
+                    return Err(error(
+                        coord,
+                        Error::InvalidCoordSyntax,
+                        component,
+                        "a number of the form `[-]digits[.digits]`",
+                    ));
+                }
+            }
+        }
+
+        // Locate the "crs" and "u" parameters.
+        let mut geo_uri_ref = Self {
+            uri,
+            latitude,
+            longitude,
+            altitude,
+            crs: None,
+            known_crs: None,
+            uncertainty: None,
+            params,
+            strict: options.is_strict(),
+        };
+        let mut has_other = false;
+        let mut position = 0;
+        for (index, part) in split_params(params).enumerate() {
+            let (name, value) = split_param(part);
+
+            if options.is_strict() {
+                validate_param(uri, params, index, name, value)?;
+            }
+
+            // In lenient mode, the "crs" parameter is only interpreted if it comes first and the
+            // "u" parameter if it comes first or right after it, not counting parameters without
+            // a value. Elsewhere, they are ignored. In strict mode, they are errors elsewhere.
+            let is_crs = name.eq_ignore_ascii_case("crs");
+            let is_u = name.eq_ignore_ascii_case("u");
+            let is_interpreted = options.is_strict()
+                || position == 0
+                || (position == 1 && is_u && geo_uri_ref.crs.is_some());
+            if value.is_some() {
+                position += 1;
+            }
+            match value {
+                Some(value) if is_crs && is_interpreted => {
+                    if options.is_strict() && (geo_uri_ref.uncertainty.is_some() || has_other) {
+                        return Err(error(
+                            name,
+                            Error::MisplacedParam,
+                            Component::ParamName,
+                            "the `crs` parameter before all other parameters",
+                        ));
+                    }
+
+                    if options.is_strict() && !is_labeltext(value) {
+                        return Err(error(
+                            value,
+                            Error::InvalidCoordRefSystem,
+                            Component::CoordRefSystem,
+                            "a coordinate reference system name of letters, digits and `-`",
+                        ));
+                    }
+
+                    geo_uri_ref.crs = Some(value);
+                    geo_uri_ref.known_crs = options.find_crs(value);
+                }
+                Some(value) if is_u && is_interpreted => {
+                    if options.is_strict() && has_other {
+                        return Err(error(
+                            name,
+                            Error::MisplacedParam,
+                            Component::ParamName,
+                            "the `u` parameter before all parameters other than `crs`",
+                        ));
+                    }
+                    if options.is_strict() && !is_pnum(value) {
+                        return Err(error(
+                            value,
+                            Error::InvalidUncertaintySyntax,
+                            Component::Uncertainty,
+                            "a number of the form `digits[.digits]`",
+                        ));
+                    }
+
+                    geo_uri_ref.uncertainty = Some(value);
+                }
+                // Parameters without a value are only invalid for "crs" and "u", and empty
+                // parameters are invalid for all. Skip these in lenient mode.
+                None if is_crs || is_u || name.is_empty() => {
+                    if options.is_strict() {
+                        return Err(error(
+                            &name[name.len()..],
+                            Error::InvalidParam,
+                            Component::ParamValue,
+                            "`=` followed by a parameter value",
+                        ));
+                    }
+                }
+                _ => has_other = true,
+            }
+        }
+
+        Ok(geo_uri_ref)
+    }
+
+    /// Returns the complete geo URI string.
+    pub fn as_str(&self) -> &'a str {
+        self.uri
+    }
+
+    /// Returns the raw latitude coordinate.
+    pub fn latitude_str(&self) -> &'a str {
+        self.latitude
+    }
+
+    /// Returns the latitude coordinate.
+    ///
+    /// Note that the coordinate is not validated against the coordinate reference system.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate cannot be parsed.
+    pub fn latitude(&self) -> Result<f64, ParseError> {
+        self.parse_coord(self.latitude, Component::Latitude)
+    }
+
+    /// Returns the raw longitude coordinate.
+    pub fn longitude_str(&self) -> &'a str {
+        self.longitude
+    }
+
+    /// Returns the longitude coordinate.
+    ///
+    /// Note that the coordinate is not validated against the coordinate reference system.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate cannot be parsed.
+    pub fn longitude(&self) -> Result<f64, ParseError> {
+        self.parse_coord(self.longitude, Component::Longitude)
+    }
+
+    /// Returns the raw altitude coordinate (if any).
+    pub fn altitude_str(&self) -> Option<&'a str> {
+        self.altitude
+    }
+
+    /// Returns the altitude coordinate (if any).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate cannot be parsed.
+    pub fn altitude(&self) -> Result<Option<f64>, ParseError> {
+        self.altitude
+            .map(|alt_s| self.parse_coord(alt_s, Component::Altitude))
+            .transpose()
+    }
+
+    /// Returns the raw value of the `crs` parameter (if any).
+    pub fn crs_str(&self) -> Option<&'a str> {
+        self.crs
+    }
+
+    /// Returns the coordinate reference system.
+    ///
+    /// If the `crs` parameter is absent, the default coordinate reference system is returned.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the coordinate reference system is not built-in nor registered
+    /// with [`ParseOptions::custom_crs`].
+    pub fn crs(&self) -> Result<CoordRefSystem, ParseError> {
+        match (self.crs, self.known_crs) {
+            (Some(_), Some(crs)) => Ok(crs),
+            (Some(crs_s), None) => Err(ParseError::new(
+                self.uri,
+                crs_s,
+                Error::InvalidCoordRefSystem,
+                Component::CoordRefSystem,
+                "a supported coordinate reference system like `wgs84`",
+            )),
+            (None, _) => Ok(CoordRefSystem::default()),
+        }
+    }
+
+    /// Returns the raw value of the `u` parameter (if any).
+    pub fn uncertainty_str(&self) -> Option<&'a str> {
+        self.uncertainty
+    }
+
+    /// Returns the uncertainty around the location (if any).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the uncertainty cannot be parsed or if it is negative.
+    pub fn uncertainty(&self) -> Result<Option<f64>, ParseError> {
+        self.uncertainty
+            .map(|unc_s| {
+                let error = |kind, expected| {
+                    ParseError::new(self.uri, unc_s, kind, Component::Uncertainty, expected)
+                };
+                match unc_s.parse() {
+                    Ok(unc) if unc < 0.0 => Err(error(
+                        Error::OutOfRangeUncertainty,
+                        "a distance that is not negative",
+                    )),
+                    Ok(unc) => Ok(unc),
+                    Err(e) => Err(error(Error::InvalidUncertainty(e), "a number")),
+                }
+            })
+            .transpose()
+    }
+
+    /// Returns an iterator over the raw names and values of the other parameters, in order.
+    ///
+    /// These are all parameters except `crs` and `u`. The names and values are not
+    /// percent-decoded, and parameters without a value have an empty value.
+    pub fn params(&self) -> Params<'a> {
+        Params {
+            parts: split_params(self.params),
+        }
+    }
+
+    /// Converts this into an owned and validated [`GeoUri`].
+    ///
+    /// This parses all components, percent-decodes the parameters and validates the coordinates
+    /// against the coordinate reference system.
+    /// Other parameters whose name is `crs` or `u` after percent-decoding are ignored, and in
+    /// lenient mode, invalid UTF-8 in the decoded parameters is replaced.
+    /// Without the `alloc` feature, the other parameters are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the components cannot be parsed or is invalid, or in strict
+    /// mode, if a parameter does not decode to UTF-8.
+    pub fn to_geo_uri(&self) -> Result<GeoUri, ParseError> {
+        let latitude = self.latitude()?;
+        let longitude = self.longitude()?;
+        let altitude = self.altitude()?;
+        let crs = self.crs()?;
+        let uncertainty = self.uncertainty()?;
+
+        #[cfg(feature = "alloc")]
+        let mut params = Vec::new();
+        #[cfg(feature = "alloc")]
+        for (name, value) in self.params() {
+            let decode = |part, component| {
+                if !self.strict {
+                    return Ok(percent_decode_lossy(part));
+                }
+
+                percent_decode(part).map_err(|kind| {
+                    ParseError::new(self.uri, part, kind, component, "percent-encoded UTF-8")
+                })
+            };
+            // Ignore percent-encoded names that decode to the name of the "crs" or "u" parameter,
+            // like other misplaced "crs" and "u" parameters. These are not labels in strict mode.
+            let decoded_name = decode(name, Component::ParamName)?;
+            if is_reserved_param_name(&decoded_name) {
+                continue;
+            }
+            params.push((decoded_name, decode(value, Component::ParamValue)?));
+        }
+
+        // Validate the coordinates before returning the geo URI.
+        crs.validate(latitude, longitude)
+            .map_err(|kind| match kind {
+                Error::OutOfRangeLatitude => ParseError::new(
+                    self.uri,
+                    self.latitude,
+                    kind,
+                    Component::Latitude,
+                    "a latitude within the range of the coordinate reference system",
+                ),
+                _ => ParseError::new(
+                    self.uri,
+                    self.longitude,
+                    kind,
+                    Component::Longitude,
+                    "a longitude within the range of the coordinate reference system",
+                ),
+            })?;
+
+        Ok(GeoUri {
+            crs,
+            latitude,
+            longitude,
+            altitude,
+            uncertainty,
+            decimals: Decimals {
+                latitude: num_decimals(self.latitude),
+                longitude: num_decimals(self.longitude),
+                altitude: self.altitude.and_then(num_decimals),
+                uncertainty: self.uncertainty.and_then(num_decimals),
+            },
+            #[cfg(feature = "alloc")]
+            params,
+        })
+    }
+
+    /// Parses a raw coordinate.
+    fn parse_coord(&self, coord: &'a str, component: Component) -> Result<f64, ParseError> {
+        coord.parse().map_err(|e| {
+            ParseError::new(
+                self.uri,
+                coord,
+                Error::InvalidCoord(e),
+                component,
+                "a number",
+            )
+        })
+    }
+}
+
+impl TryFrom<GeoUriRef<'_>> for GeoUri {
+    type Error = ParseError;
+
+    fn try_from(geo_uri_ref: GeoUriRef<'_>) -> Result<Self, Self::Error> {
+        geo_uri_ref.to_geo_uri()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for GeoUriRef<'a> {
+    type Error = ParseError;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+/// Iterator over the raw names and values of the other parameters of a [`GeoUriRef`].
+///
+/// Use [`GeoUriRef::params`] to construct it.
+#[derive(Clone, Debug)]
+pub struct Params<'a> {
+    /// The remaining parameter parts.
+    parts: Skip<Split<'a, char>>,
+}
+
+impl<'a> Iterator for Params<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parts
+            .by_ref()
+            .map(split_param)
+            .find_map(|(name, value)| {
+                // Skip the "crs" and "u" parameters, including their duplicates, and empty ones.
+                let is_crs_or_u =
+                    name.eq_ignore_ascii_case("crs") || name.eq_ignore_ascii_case("u");
+                if is_crs_or_u || name.is_empty() {
+                    return None;
+                }
+
+                Some((name, value.unwrap_or(&name[name.len()..])))
+            })
+    }
+}
+
+/// Strips the (case-insensitive) `geo:` scheme from the URI.
+///
+/// Returns `None` if the URI does not start with the scheme.
+fn strip_scheme(uri: &str) -> Option<&str> {
+    let scheme_len = crate::URI_SCHEME_NAME.len();
+    let (scheme, rest) = (uri.get(..scheme_len)?, uri.get(scheme_len..)?);
+    if !scheme.eq_ignore_ascii_case(crate::URI_SCHEME_NAME) {
+        return None;
+    }
+
+    rest.strip_prefix(':')
+}
+
+/// Splits the raw parameters part into the separate parameters.
+fn split_params(params: &str) -> Skip<Split<'_, char>> {
+    // Skip what is before the first separator, i.e. nothing.
+    params.split(';').skip(1)
+}
+
+/// Splits a raw parameter into its name and value (if any).
+fn split_param(part: &str) -> (&str, Option<&str>) {
+    match part.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (part, None),
+    }
+}
+
+/// Validates the syntax of a raw parameter for strict mode.
+///
+/// The parameter with the given index must not have the same name as any of the parameters
+/// before it.
+///
+/// # Errors
+///
+/// Returns an error if the name or the value of the parameter does not follow the syntax of
+/// RFC 5870 or if the name is a duplicate.
+fn validate_param(
+    uri: &str,
+    params: &str,
+    index: usize,
+    name: &str,
+    value: Option<&str>,
+) -> Result<(), ParseError> {
+    if !is_labeltext(name) {
+        return Err(ParseError::new(
+            uri,
+            name,
+            Error::InvalidParam,
+            Component::ParamName,
+            "a parameter name of letters, digits and `-`",
+        ));
+    }
+    if let Some(value) = value.filter(|value| !is_pvalue(value)) {
+        return Err(ParseError::new(
+            uri,
+            value,
+            Error::InvalidParam,
+            Component::ParamValue,
+            "a parameter value of unreserved or percent-encoded characters",
+        ));
+    }
+
+    // Parameter names are case-insensitive, so check for duplicates that way.
+    let mut prev_names = split_params(params)
+        .take(index)
+        .map(|prev_part| split_param(prev_part).0);
+    if prev_names.any(|prev_name| prev_name.eq_ignore_ascii_case(name)) {
+        return Err(ParseError::new(
+            uri,
+            name,
+            Error::DuplicateParam,
+            Component::ParamName,
+            "a parameter name that did not occur before",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geo_uri_ref_parse() -> Result<(), ParseError> {
+        let geo_uri_ref = GeoUriRef::parse("GEO:52.107,5.134")?;
+        assert_eq!(geo_uri_ref.as_str(), "GEO:52.107,5.134");
+        assert_eq!(geo_uri_ref.latitude_str(), "52.107");
+        assert_eq!(geo_uri_ref.longitude_str(), "5.134");
+        assert_eq!(geo_uri_ref.altitude_str(), None);
+        assert_eq!(geo_uri_ref.crs_str(), None);
+        assert_eq!(geo_uri_ref.uncertainty_str(), None);
+        assert_eq!(geo_uri_ref.params().next(), None);
+
+        let geo_uri_ref = GeoUriRef::parse("geo:52.107,5.134,3.6;foo;Crs=WGS84;U=10;u=20;bar=%20")?;
+        assert_eq!(geo_uri_ref.altitude_str(), Some("3.6"));
+        assert_eq!(geo_uri_ref.crs_str(), Some("WGS84"));
+        assert_eq!(geo_uri_ref.uncertainty_str(), Some("10"));
+        assert_eq!(
+            geo_uri_ref.params().collect::<Vec<_>>(),
+            vec![("foo", ""), ("bar", "%20")]
+        );
+
+        // Coordinates are only parsed on access.
+        let geo_uri_ref = GeoUriRef::parse("geo:x,y,z;crs=foo;u=-1")?;
+        assert!(matches!(
+            geo_uri_ref.latitude().map_err(Error::from),
+            Err(Error::InvalidCoord(_))
+        ));
+        assert!(matches!(
+            geo_uri_ref.longitude().map_err(Error::from),
+            Err(Error::InvalidCoord(_))
+        ));
+        assert!(matches!(
+            geo_uri_ref.altitude().map_err(Error::from),
+            Err(Error::InvalidCoord(_))
+        ));
+        assert_eq!(
+            geo_uri_ref.crs().map_err(Error::from),
+            Err(Error::InvalidCoordRefSystem)
+        );
+        assert_eq!(
+            geo_uri_ref.uncertainty().map_err(Error::from),
+            Err(Error::OutOfRangeUncertainty)
+        );
+
+        let geo_uri_ref =
+            GeoUriRef::parse_with("geo:52.107,5.134;u=1;crs=wgs84", &ParseOptions::strict());
+        assert_eq!(geo_uri_ref.map_err(Error::from), Err(Error::MisplacedParam));
+
+        // The coordinate reference system must be a label in strict mode.
+        let geo_uri_ref = GeoUriRef::parse("geo:52.107,5.134;crs=wgs_84")?;
+        assert_eq!(geo_uri_ref.crs_str(), Some("wgs_84"));
+        let error = GeoUriRef::parse_with("geo:52.107,5.134;crs=wgs_84", &ParseOptions::strict())
+            .unwrap_err();
+        assert_eq!(error.kind(), &Error::InvalidCoordRefSystem);
+        assert_eq!(error.span(), 21..27);
+        assert_eq!(
+            error.expected(),
+            "a coordinate reference system name of letters, digits and `-`"
+        );
+
+        assert_eq!(
+            GeoUriRef::try_from("52.107,5.134").map_err(Error::from),
+            Err(Error::MissingScheme)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn geo_uri_ref_to_geo_uri() -> Result<(), ParseError> {
+        let geo_uri_ref = GeoUriRef::parse("geo:52.107,5.134,3.6;crs=wgs84;u=10;foo=b%20r")?;
+        assert_eq!(geo_uri_ref.latitude()?, 52.107);
+        assert_eq!(geo_uri_ref.longitude()?, 5.134);
+        assert_eq!(geo_uri_ref.altitude()?, Some(3.6));
+        assert_eq!(geo_uri_ref.crs()?, CoordRefSystem::Wgs84);
+        assert_eq!(geo_uri_ref.uncertainty()?, Some(10.0));
+
+        let geo_uri = geo_uri_ref.to_geo_uri()?;
+        assert_eq!(geo_uri.latitude(), 52.107);
+        assert_eq!(geo_uri.longitude(), 5.134);
+        assert_eq!(geo_uri.altitude(), Some(3.6));
+        assert_eq!(geo_uri.uncertainty(), Some(10.0));
+        #[cfg(feature = "alloc")]
+        assert_eq!(geo_uri.param("foo"), Some("b r"));
+        assert_eq!(GeoUri::try_from(geo_uri_ref)?, geo_uri);
+
+        #[cfg(feature = "alloc")]
+        for (uri, name) in [
+            ("geo:1,2;%63rs=moon", "%63rs"),
+            ("geo:1,2;u=3;%75=5", "%75"),
+            ("geo:1,2;foo=bar;%55", "%55"),
+        ] {
+            let geo_uri = GeoUriRef::parse(uri)?.to_geo_uri()?;
+            assert_eq!(geo_uri.crs(), CoordRefSystem::Wgs84);
+            assert_eq!(geo_uri.params().count(), usize::from(uri.contains("foo")));
+
+            let error = GeoUriRef::parse_with(uri, &ParseOptions::strict()).unwrap_err();
+            assert_eq!(error.kind(), &Error::InvalidParam);
+            assert_eq!(error.component(), Component::ParamName);
+            assert_eq!(&uri[error.span()], name);
+        }
+
+        #[cfg(feature = "alloc")]
+        {
+            let uri = "geo:1,2;foo=%C3";
+            let geo_uri = GeoUriRef::parse(uri)?.to_geo_uri()?;
+            assert_eq!(geo_uri.param("foo"), Some("\u{FFFD}"));
+
+            let error = GeoUriRef::parse_with(uri, &ParseOptions::strict())?
+                .to_geo_uri()
+                .unwrap_err();
+            assert_eq!(error.kind(), &Error::InvalidParam);
+            assert_eq!(error.component(), Component::ParamValue);
+            assert_eq!(&uri[error.span()], "%C3");
+        }
+
+        let geo_uri_ref = GeoUriRef::parse("geo:100,5.134")?;
+        assert_eq!(geo_uri_ref.latitude()?, 100.0);
+        assert_eq!(
+            geo_uri_ref.to_geo_uri().map_err(Error::from),
+            Err(Error::OutOfRangeLatitude)
+        );
+
+        Ok(())
+    }
+}